@@ -17,183 +17,3607 @@
  * For more information see <https://github.com/Gymmasssorla/anevicon>.
  */
 
-use std::io;
-use std::net::UdpSocket;
-use std::num::NonZeroUsize;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, Cursor, IsTerminal, Write};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use super::config::ArgsConfig;
-use super::summary::AttackSummary;
+use super::config::{
+    check_length_overflow, check_packet_length, decode_hex, parse_template, read_payload,
+    ArgsConfig, ArgsConfigError, LengthDistribution, PayloadFileError, TemplatePart,
+    MAGIC_HEADER_LENGTH, SEQUENCE_NUMBER_LENGTH,
+};
+use super::logging::label_prefix;
+#[cfg(target_os = "linux")]
+use super::socket::set_priority;
+use super::socket::{
+    bind_to_device, send_batch, set_dont_fragment, set_multicast_hops_v6, set_send_buffer_size,
+    set_tos, Socket,
+};
+use super::summary::{prepare_csv, AttackSummary, Statistics, StopReason};
 
-use log::info;
-use rand::{thread_rng, RngCore};
+use humantime::format_duration;
+use log::{info, warn};
+use rand::distributions::Normal;
+use rand::rngs::StdRng;
+use rand::{FromEntropy, Rng, RngCore, SeedableRng};
 
 #[derive(Debug)]
 pub struct Attacker<'a> {
-    socket: UdpSocket,
+    sockets: Vec<Socket>,
+    senders: Vec<SocketAddr>,
     buffer: Vec<u8>,
+    payloads: Vec<Vec<u8>>,
+    next_payload: usize,
+    rng: StdRng,
+    packets: usize,
+    byte_limit: Option<u64>,
+    share: f64,
+    sequence: Arc<AtomicU64>,
+    template: Option<Vec<TemplatePart>>,
+    batch: Vec<u8>,
+    progress: ProgressStyle,
+    csv: Option<File>,
     args_config: &'a ArgsConfig,
 }
 
 impl<'a> Attacker<'a> {
     pub fn from_args_config(args_config: &'a ArgsConfig) -> io::Result<Attacker<'a>> {
-        // Complete any necessary stuff with the specified socket
-        let socket = UdpSocket::bind(args_config.sender)?;
-        socket.connect(args_config.receiver)?;
-        socket.set_write_timeout(args_config.send_timeout)?;
+        Attacker::with_senders(
+            args_config,
+            0,
+            &sender_addresses(args_config, 0, 1),
+            Attacker::stdin_payload(args_config)?.as_deref(),
+        )
+    }
+
+    /// Constructs the attacker of `thread`, which sends packets from all
+    /// the `senders` in turn, each bound to its own socket. The payload
+    /// read from the standard input (if any) is passed explicitly,
+    /// because it can be read only once.
+    fn with_senders(
+        args_config: &'a ArgsConfig,
+        thread: usize,
+        senders: &[SocketAddr],
+        stdin_payload: Option<&[u8]>,
+    ) -> io::Result<Attacker<'a>> {
+        let sockets = Attacker::open_sockets(args_config, senders)?;
+
+        // Each thread owns its generator, so none of them waits for another
+        let mut rng = match args_config.seed {
+            Some(seed) => thread_rng_for(seed, thread),
+            None => StdRng::from_entropy(),
+        };
+
+        // Several payload files are rotated through the buffer per packet
+        let mut payloads = Attacker::file_payloads(args_config)?;
+        let buffer = match stdin_payload {
+            Some(payload) => payload.to_vec(),
+            None => Attacker::configured_buffer(args_config, &payloads, &mut rng)?,
+        };
+        if payloads.len() < 2 {
+            payloads.clear();
+        }
+        args_config
+            .check_length(buffer.len())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        let shortest = payloads.iter().map(Vec::len).min().unwrap_or(buffer.len());
+        if args_config.sequence && shortest < SEQUENCE_NUMBER_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "The payload is {} bytes, but --sequence requires at least {} bytes",
+                    shortest, SEQUENCE_NUMBER_LENGTH
+                ),
+            ));
+        }
+
+        // The magic header is prepended, so the payload follows it in the buffer
+        let buffer = [vec![0; args_config.header_length()], buffer].concat();
+        let longest = payloads.iter().map(Vec::len).max().unwrap_or(0);
+        args_config
+            .check_datagram_size(buffer.len().max(longest + args_config.header_length()))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        let template = match args_config.template {
+            Some(ref template) => Some(
+                parse_template(template)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?,
+            ),
+            None => None,
+        };
+
+        // A single packet or a static payload is sent right from the buffer
+        let batch = if args_config.batch_size > 1 && has_varying_payload(args_config) {
+            vec![0; args_config.batch_size * buffer.len()]
+        } else {
+            Vec::new()
+        };
+
+        let csv = match args_config.csv_path {
+            Some(ref path) => Some(prepare_csv(path).map_err(|error| {
+                io::Error::new(
+                    error.kind(),
+                    ArgsConfigError::Csv(format!("{}: {}", path.display(), error)),
+                )
+            })?),
+            None => None,
+        };
 
         Ok(Attacker {
-            socket,
-            buffer: Attacker::random_buffer(args_config.length),
+            sockets,
+            senders: senders.to_vec(),
+            buffer,
+            payloads,
+            next_payload: 0,
+            rng,
+            packets: args_config.packets.get(),
+            byte_limit: args_config.byte_limit,
+            share: 1.0,
+            sequence: Arc::new(AtomicU64::new(0)),
+            template,
+            batch,
+            progress: progress_style(args_config, io::stderr().is_terminal()),
+            csv,
             args_config,
         })
     }
 
-    fn random_buffer(length: NonZeroUsize) -> Vec<u8> {
-        // Create a sending buffer without an unnecessary initialization
-        // because we'll fill this buffer with random values next.
-        let mut buffer = Vec::with_capacity(length.get());
-        unsafe {
-            buffer.set_len(length.get());
+    /// Constructs one attacker per thread, each with its own socket and
+    /// an equal share of the total packets count, bytes count, and
+    /// sending rate. The source ports are split between the threads, so
+    /// there cannot be more threads than source ports.
+    pub fn group_from_args_config(args_config: &'a ArgsConfig) -> io::Result<Vec<Attacker<'a>>> {
+        let ports = match args_config.source_ports {
+            Some((start, end)) => usize::from(end - start) + 1,
+            None => usize::MAX,
+        };
+        let threads = args_config
+            .threads
+            .get()
+            .min(args_config.packets.get())
+            .min(ports);
+        let (packets, remainder) = (
+            args_config.packets.get() / threads,
+            args_config.packets.get() % threads,
+        );
+
+        let stdin_payload = Attacker::stdin_payload(args_config)?;
+        let sequence = Arc::new(AtomicU64::new(0));
+
+        (0..threads)
+            .map(|thread| {
+                let mut attacker = Attacker::with_senders(
+                    args_config,
+                    thread,
+                    &sender_addresses(args_config, thread, threads),
+                    stdin_payload.as_deref(),
+                )?;
+                attacker.packets = packets + if thread < remainder { 1 } else { 0 };
+                attacker.byte_limit = args_config.byte_limit.map(|bytes| {
+                    let threads = threads as u64;
+                    bytes / threads
+                        + if (thread as u64) < bytes % threads {
+                            1
+                        } else {
+                            0
+                        }
+                });
+                attacker.share = 1.0 / threads as f64;
+                attacker.sequence = sequence.clone();
+                Ok(attacker)
+            })
+            .collect()
+    }
+
+    /// Returns the addresses the UDP sockets are actually bound to, so
+    /// the ephemeral ports chosen for the senders with the zero port
+    /// are known. A UNIX domain socket has no such address.
+    pub fn local_addrs(&self) -> Vec<SocketAddr> {
+        self.sockets
+            .iter()
+            .filter_map(Socket::as_udp)
+            .filter_map(|socket| socket.local_addr().ok())
+            .collect()
+    }
+
+    /// Opens a socket per each of the `senders`, or the only socket
+    /// connected to `--unix-socket` if it is specified.
+    fn open_sockets(args_config: &ArgsConfig, senders: &[SocketAddr]) -> io::Result<Vec<Socket>> {
+        if let Some(ref path) = args_config.unix_socket {
+            let socket = Socket::connect_unix(path)?;
+            socket.set_write_timeout(args_config.send_timeout)?;
+            return Ok(vec![socket]);
+        }
+
+        senders
+            .iter()
+            .map(|sender| Attacker::bind_socket(args_config, sender).map(Socket::Udp))
+            .collect()
+    }
+
+    fn bind_socket(args_config: &ArgsConfig, sender: &SocketAddr) -> io::Result<UdpSocket> {
+        // A non-local sender is the most likely reason, so it's named
+        let socket = UdpSocket::bind(sender).map_err(|error| {
+            io::Error::new(
+                error.kind(),
+                ArgsConfigError::BindFailed(*sender, error.to_string()),
+            )
+        })?;
+
+        // Complete any necessary stuff with the specified socket
+        socket.set_write_timeout(args_config.send_timeout)?;
+        if args_config.broadcast {
+            socket.set_broadcast(true)?;
+        }
+        if let Some(ttl) = args_config.ttl {
+            socket.set_ttl(ttl)?;
+        }
+        if let Some(tos) = args_config.tos {
+            set_tos(&socket, tos)?;
+        }
+        if let Some(ttl) = args_config.multicast_ttl {
+            match sender {
+                SocketAddr::V4(_) => socket.set_multicast_ttl_v4(ttl)?,
+                SocketAddr::V6(_) => set_multicast_hops_v6(&socket, ttl)?,
+            }
+        }
+        if let Some(multicast_loop) = args_config.multicast_loop {
+            match sender {
+                SocketAddr::V4(_) => socket.set_multicast_loop_v4(multicast_loop)?,
+                SocketAddr::V6(_) => socket.set_multicast_loop_v6(multicast_loop)?,
+            }
+        }
+        if let Some(ref interface) = args_config.interface {
+            bind_to_device(&socket, interface)?;
+        }
+        // The configuration warns that the priority is ignored elsewhere, and
+        // whether it requires CAP_NET_ADMIN is known only to the kernel
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(priority) = args_config.priority {
+                set_priority(&socket, priority).map_err(|error| {
+                    io::Error::new(
+                        error.kind(),
+                        ArgsConfigError::Priority(format!("cannot set {}: {}", priority, error)),
+                    )
+                })?;
+            }
+        }
+        if args_config.dont_fragment {
+            set_dont_fragment(&socket)?;
+        }
+        if let Some(size) = args_config.send_buffer {
+            info!(
+                "The socket send buffer size is set to {} bytes.",
+                set_send_buffer_size(&socket, size.get())?
+            );
+        }
+        if args_config.connect {
+            socket.connect(args_config.receiver[0].1)?;
+        }
+        Ok(socket)
+    }
+
+    /// Constructs the sending buffer from the payload options: the
+    /// loaded `payloads` of the files, a hex string, or random bytes. The
+    /// buffer fits the longest of the files. A template is rendered
+    /// into a zeroed buffer before each packet.
+    fn configured_buffer(
+        args_config: &ArgsConfig,
+        payloads: &[Vec<u8>],
+        rng: &mut StdRng,
+    ) -> io::Result<Vec<u8>> {
+        if args_config.template.is_some() {
+            let length = match args_config.length_range {
+                Some((_, max)) => max.get(),
+                None => args_config.packet_length(),
+            };
+            return Ok(vec![0; length]);
+        }
+
+        Ok(match payloads.iter().max_by_key(|payload| payload.len()) {
+            Some(payload) => payload.clone(),
+            None => match args_config.payload_hex {
+                Some(ref hex) => decode_hex(hex)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?,
+                None => {
+                    // Generate the largest packet, the others are its prefixes
+                    let length = match args_config.length_range {
+                        Some((_, max)) => max.get(),
+                        None => args_config.packet_length(),
+                    };
+                    match args_config.fill_byte {
+                        Some(byte) => vec![byte; length],
+                        None => Attacker::random_buffer(rng, length),
+                    }
+                }
+            },
+        })
+    }
+
+    /// Loads the payloads of all the `--send-file` files in their order,
+    /// each repeated up to `--length` if `--repeat-payload` is specified.
+    fn file_payloads(args_config: &ArgsConfig) -> io::Result<Vec<Vec<u8>>> {
+        args_config
+            .send_file
+            .iter()
+            .map(|path| {
+                let buffer = Attacker::file_buffer(args_config, path)?;
+
+                Ok(match args_config.length {
+                    Some(length) if args_config.repeat_payload && length > buffer.len() => {
+                        buffer.iter().cycle().take(length).cloned().collect()
+                    }
+                    Some(_) => {
+                        info!(
+                            "The packet length is derived from the file {}, so \
+                             the specified length is ignored.",
+                            path.display()
+                        );
+                        buffer
+                    }
+                    None => buffer,
+                })
+            })
+            .collect()
+    }
+
+    fn stdin_payload(args_config: &ArgsConfig) -> io::Result<Option<Vec<u8>>> {
+        if !args_config.send_stdin {
+            return Ok(None);
         }
 
-        thread_rng().fill_bytes(buffer.as_mut_slice());
+        read_payload(io::stdin().lock())
+            .map(Some)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    /// Reads the file at `path`, which must fit into the packet length
+    /// bounds on its own.
+    fn file_buffer(args_config: &ArgsConfig, path: &Path) -> io::Result<Vec<u8>> {
+        let buffer = fs::read(path)?;
+
+        // Even an empty file is sent as is under --unchecked-length
+        let checked = match args_config.unchecked_length {
+            true => check_length_overflow(buffer.len()).map(|_| ()),
+            false => check_packet_length(buffer.len()).map(|_| ()),
+        };
+        checked
+            .and_then(|_| args_config.check_length(buffer.len()))
+            .map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    PayloadFileError {
+                        path: path.to_path_buf(),
+                        error,
+                    },
+                )
+            })?;
+        Ok(buffer)
+    }
+
+    fn random_buffer(rng: &mut StdRng, length: usize) -> Vec<u8> {
+        // Create a zeroed sending buffer and fill it with random values
+        let mut buffer = vec![0; length];
+        rng.fill_bytes(buffer.as_mut_slice());
         buffer
     }
 
-    pub fn attack(&self) -> io::Result<AttackSummary> {
+    pub fn attack(&mut self) -> Result<Statistics, AttackError> {
         info!(
             "The program is starting to attack with {}.",
             self.args_config
         );
+        log_local_addrs(std::slice::from_ref(self));
+
+        thread::sleep(self.args_config.wait_time());
+        let (summary, result) = self.send_packets(&AtomicBool::new(false));
+        finish_status_line(std::slice::from_ref(self));
+        let statistics = summary.statistics();
+        if let Err(error) = result {
+            return Err(AttackError {
+                error,
+                statistics: Box::new(statistics),
+            });
+        }
+
+        info!("The total result is: {}.", statistics);
+        Ok(statistics)
+    }
+
+    /// Sends the packets until any of the stop conditions holds or an
+    /// error occurs, returning the summary of the sent packets either way.
+    fn send_packets(&mut self, interrupted: &AtomicBool) -> (AttackSummary, io::Result<()>) {
+        // The warmup isn't a part of the attack, so it isn't counted in the summary
+        sleep_unless_interrupted(self.args_config.warmup, interrupted);
 
-        thread::sleep(self.args_config.wait);
         let mut summary = AttackSummary::new();
+        let result = self.send_until_stopped(&mut summary, interrupted);
+        (summary, result)
+    }
+
+    fn send_until_stopped(
+        &mut self,
+        summary: &mut AttackSummary,
+        interrupted: &AtomicBool,
+    ) -> io::Result<()> {
+        // Distribute packets across all the receivers in turn
+        // A UNIX domain socket is connected, so its receiver is a placeholder
+        let mut receivers = WeightedReceivers::new(if self.args_config.unix_socket.is_some() {
+            &UNIX_SOCKET_RECEIVER
+        } else {
+            &self.args_config.receiver
+        });
+        let mut socket = 0;
+        let mut consecutive_errors = 0;
+        let mut error_limiter = RateLimiter::new(VERBOSE_ERRORS_PER_SEC, Duration::from_secs(1));
+        let mut last_report = (Duration::from_secs(0), 0);
+        let mut last_recreation = Duration::from_secs(0);
+        let mut planned_start = None;
 
         loop {
-            for _ in 0..self.args_config.display_periodicity.get() {
-                summary.update(self.socket.send(&self.buffer)?, 1);
+            let mut packets = 0;
+
+            while packets < self.args_config.display_periodicity.get() {
+                // A slow sending call might have reached the deadline
+                if let Some(reason) = self.stop_reason(summary, interrupted) {
+                    summary.stop(reason);
+                    return Ok(());
+                }
+
+                if let Some(periodicity) = self.args_config.recreate_every {
+                    if summary.time_passed() - last_recreation >= periodicity {
+                        if let Err(error) = self.recreate_sockets() {
+                            info!(
+                                "The program is stopping the packet sending because \
+                                 the sockets cannot be recreated. The result is: {}.",
+                                summary
+                            );
+                            return Err(error);
+                        }
+                        last_recreation = summary.time_passed();
+                    }
+                }
+
+                // Never idle past the deadline waiting for the next burst
+                if let Some(pause) = self.burst_pause(summary) {
+                    sleep_unless_interrupted(pause.min(self.remaining_time(summary)), interrupted);
+                    planned_start = None;
+                    continue;
+                }
+
+                let batch = self.next_batch(&mut receivers, summary);
+                packets += batch.len();
+
+                if let Some(planned) = planned_start {
+                    summary.record_pacing(planned, summary.time_passed());
+                }
+
+                // A failed packet is skipped, and the rest of the batch is still sent
+                let mut offset = 0;
+                while offset < batch.len() {
+                    let rest = &batch[offset..];
+
+                    // Distribute batches across all the source ports in turn as well
+                    let started = Instant::now();
+                    let (sent, result) = self.send_batch(socket, rest, interrupted);
+                    summary.record_send(started.elapsed());
+                    socket = (socket + 1) % self.sockets.len();
+
+                    summary.update(sent_bytes(rest, sent), sent);
+                    if self.args_config.receiver.len() > 1 {
+                        for &(length, receiver) in &rest[..sent] {
+                            summary.update_receiver(*receiver, length);
+                        }
+                    }
+                    if sent > 0 {
+                        consecutive_errors = 0;
+                    }
+                    offset += sent + 1;
+
+                    let error = match result {
+                        Ok(()) => break,
+                        // A packet interrupted during the teardown isn't a failure
+                        Err(_) if interrupted.load(Ordering::SeqCst) => break,
+                        Err(error) => error,
+                    };
+
+                    // The first unsent packet is the one that failed
+                    let (length, receiver) = rest[sent];
+
+                    // Only a connected socket gets the ICMP errors of its peer
+                    if self.args_config.connect && is_unreachable(&error) {
+                        summary.update_unreachable(1);
+                    } else {
+                        summary.update_failed(1);
+                    }
+                    if self.args_config.receiver.len() > 1 {
+                        summary.update_receiver_failed(*receiver);
+                    }
+                    consecutive_errors += 1;
+
+                    if probe_failed(summary, self.args_config.probe_count) {
+                        info!(
+                            "The program is stopping the packet sending because \
+                             none of the first {} packets was sent. The result is: {}.",
+                            self.args_config.probe_count, summary
+                        );
+                        return Err(io::Error::new(
+                            error.kind(),
+                            format!(
+                                "none of the first {} packets was sent, so the receiver \
+                                 is probably unreachable (check its address): {}",
+                                self.args_config.probe_count, error
+                            ),
+                        ));
+                    }
+                    if consecutive_errors > self.args_config.allowed_errors {
+                        info!(
+                            "The program is stopping the packet sending because \
+                             too many consecutive errors occurred. The result is: {}.",
+                            summary
+                        );
+                        return Err(error);
+                    }
+
+                    let receiver = self.receiver_name(receiver);
+
+                    // The verbose failures replace the warnings, but are rate-limited
+                    if self.args_config.verbose_errors {
+                        if let Some(skipped) = error_limiter.allow(summary.time_passed()) {
+                            eprintln!(
+                                "{}{}",
+                                label_prefix(self.args_config.label.as_deref()),
+                                verbose_error(&receiver, &error, skipped)
+                            );
+                        }
+                    } else if self.args_config.dont_fragment
+                        && error.raw_os_error() == Some(libc::EMSGSIZE)
+                    {
+                        // An oversized packet is the expected result of a path-MTU probe
+                        warn!(
+                            "Cannot send a packet of {} bytes to {} without \
+                             fragmenting it: {}.",
+                            length, receiver, error
+                        );
+                    } else {
+                        warn!("Cannot send a packet to {}: {}.", receiver, error);
+                    }
+                }
+
+                if self.args_config.random_payload {
+                    self.rng.fill_bytes(self.buffer.as_mut_slice());
+                }
+
+                if let Some(reason) = self.stop_reason(summary, interrupted) {
+                    summary.stop(reason);
+                    return Ok(());
+                }
+
+                // The quiet mode wins, so the progress isn't even computed
+                if self.progress != ProgressStyle::Hidden {
+                    self.report_progress(summary, &mut last_report);
+                }
+
+                // The fast path skips all the pacing, but not the stop conditions
+                if self.args_config.no_delay {
+                    continue;
+                }
+
+                // Never sleep past the deadline waiting for the next packet
+                let delay = self
+                    .send_delay(summary, batch.len())
+                    .min(self.remaining_time(summary));
+                planned_start = Some(summary.time_passed() + delay);
+                sleep_unless_interrupted(delay, interrupted);
+            }
+
+            // The status line already displays the same summary
+            if self.progress != ProgressStyle::StatusLine {
+                info!("The attack is running with {}.", summary);
+            }
+
+            if let Some(ref mut csv) = self.csv {
+                if let Err(error) = writeln!(csv, "{}", summary.to_csv_row()) {
+                    warn!("Cannot write a row into the CSV file: {}.", error);
+                }
+            }
+        }
+    }
+
+    /// Returns the name of `receiver` for the messages, which is the path
+    /// of `--unix-socket` if it is specified.
+    fn receiver_name(&self, receiver: &SocketAddr) -> String {
+        match self.args_config.unix_socket {
+            Some(ref path) => path.display().to_string(),
+            None => receiver.to_string(),
+        }
+    }
+
+    /// Closes all the sockets and binds them again to the same senders
+    /// with the same options. The old sockets are closed first, so the
+    /// fixed sender ports can be bound again.
+    fn recreate_sockets(&mut self) -> io::Result<()> {
+        self.sockets.clear();
+        self.sockets = Attacker::open_sockets(self.args_config, &self.senders)?;
+
+        info!("The sockets are recreated.");
+        Ok(())
+    }
+
+    /// Displays the attack progress if the report interval has passed
+    /// since `last_report`, which is the time passed and the packets
+    /// sent at the last report.
+    fn report_progress(&self, summary: &AttackSummary, last_report: &mut (Duration, usize)) {
+        let time_passed = summary.time_passed();
+        let since_last_report = time_passed - last_report.0;
+
+        if since_last_report < report_interval(self.args_config) {
+            return;
+        }
+
+        let remaining = match self.args_config.effective_duration() {
+            Some(duration) => format!(
+                "{} remaining",
+                format_duration(round_to_millis(
+                    duration.checked_sub(time_passed).unwrap_or_default()
+                ))
+            ),
+            None => String::from("no time limit"),
+        };
+
+        let progress = format!(
+            "The attack is running for {elapsed} ({remaining}), \
+             packets sent: {packets}, the current speed: {speed} packets/s.",
+            elapsed = format_duration(round_to_millis(time_passed)),
+            remaining = remaining,
+            packets = summary.packets_sent(),
+            speed = current_speed(summary.packets_sent() - last_report.1, since_last_report),
+        );
+
+        match self.progress {
+            // Return to the line start and erase the previous status to its end
+            ProgressStyle::StatusLine => eprint!(
+                "\r{}{}\x1b[K",
+                label_prefix(self.args_config.label.as_deref()),
+                progress
+            ),
+            _ => info!("{}", progress),
+        }
+
+        *last_report = (time_passed, summary.packets_sent());
+    }
+
+    /// Prepares the next batch of `--batch-size` packets, that is, their
+    /// lengths and receivers. A single packet is sent right from the
+    /// buffer, and so are all the packets of a static payload. Only the
+    /// payloads varying per packet are copied into the batch buffer.
+    fn next_batch(
+        &mut self,
+        receivers: &mut WeightedReceivers<'a>,
+        summary: &AttackSummary,
+    ) -> Vec<(usize, &'a SocketAddr)> {
+        // The last batch mustn't exceed the required count of packets
+        let size = self
+            .args_config
+            .batch_size
+            .min(self.packets.saturating_sub(summary.packets_sent()))
+            .max(1);
+        let mut batch = Vec::with_capacity(size);
+
+        for index in 0..size {
+            // The buffer of the last packet is refilled after sending it
+            if index > 0 && self.args_config.random_payload {
+                self.rng.fill_bytes(self.buffer.as_mut_slice());
+            }
+
+            let receiver = receivers.next();
+            let length = self.next_length();
+
+            if self.args_config.sequence {
+                let number = self.sequence.fetch_add(1, Ordering::Relaxed);
+                self.buffer[..SEQUENCE_NUMBER_LENGTH].copy_from_slice(&number.to_be_bytes());
+            }
+            if let Some(ref template) = self.template {
+                let number = self.sequence.fetch_add(1, Ordering::Relaxed);
+                render_template(template, number, unix_millis(), &mut self.buffer);
+            }
+            if let Some(magic) = self.args_config.magic {
+                write_magic_header(magic, &mut self.buffer[..length]);
+            }
+
+            if size > 1 && has_varying_payload(self.args_config) {
+                let offset = index * self.buffer.len();
+                self.batch[offset..offset + length].copy_from_slice(&self.buffer[..length]);
+            }
+            batch.push((length, receiver));
+        }
+
+        batch
+    }
+
+    /// Sends the `batch` from the `socket`-th socket and returns the count
+    /// of the sent packets, along with the error which prevented sending
+    /// the others. A partially sent batch is continued from the first
+    /// unsent packet.
+    fn send_batch(
+        &self,
+        socket: usize,
+        batch: &[(usize, &SocketAddr)],
+        interrupted: &AtomicBool,
+    ) -> (usize, io::Result<()>) {
+        if batch.len() == 1 {
+            let (length, receiver) = batch[0];
+            return match self.send_packet(socket, length, receiver, interrupted) {
+                Ok(_) => (1, Ok(())),
+                Err(error) => (0, Err(error)),
+            };
+        }
+
+        let slot = self.buffer.len();
+        let packets: Vec<(&[u8], &SocketAddr)> = batch
+            .iter()
+            .enumerate()
+            .map(|(index, &(length, receiver))| {
+                if self.batch.is_empty() {
+                    (&self.buffer[..length], receiver)
+                } else {
+                    (&self.batch[index * slot..index * slot + length], receiver)
+                }
+            })
+            .collect();
+        let (mut sent, mut attempt) = (0, 0);
+
+        while sent < packets.len() {
+            match send_batch(
+                &self.sockets[socket],
+                &packets[sent..],
+                self.args_config.connect,
+            ) {
+                Ok(count) => {
+                    sent += count;
+                    attempt = 0;
+                }
+                Err(ref error)
+                    if attempt < self.args_config.retries
+                        && is_retryable(error)
+                        && !interrupted.load(Ordering::SeqCst) =>
+                {
+                    sleep_unless_interrupted(retry_delay(attempt), interrupted);
+                    attempt += 1;
+                }
+                Err(error) => return (sent, Err(error)),
+            }
+        }
 
-                if self.check_end_cond(&summary) {
-                    return Ok(summary);
+        (sent, Ok(()))
+    }
+
+    /// Sends a packet of `length` bytes from the `socket`-th socket,
+    /// retrying transient errors up to `--retries` times.
+    fn send_packet(
+        &self,
+        socket: usize,
+        length: usize,
+        receiver: &SocketAddr,
+        interrupted: &AtomicBool,
+    ) -> io::Result<usize> {
+        let mut attempt = 0;
+
+        loop {
+            match self.sockets[socket].send_to(
+                &self.buffer[..length],
+                receiver,
+                self.args_config.connect,
+            ) {
+                Err(ref error)
+                    if attempt < self.args_config.retries
+                        && is_retryable(error)
+                        && !interrupted.load(Ordering::SeqCst) =>
+                {
+                    sleep_unless_interrupted(retry_delay(attempt), interrupted);
+                    attempt += 1;
                 }
+                result => return result,
+            }
+        }
+    }
+
+    /// Returns the length of the next datagram, including its header, and
+    /// puts the next payload into the buffer if several are rotated.
+    fn next_length(&mut self) -> usize {
+        let header = self.args_config.header_length();
+
+        if !self.payloads.is_empty() {
+            let payload = &self.payloads[self.next_payload];
+            self.next_payload = (self.next_payload + 1) % self.payloads.len();
+
+            self.buffer[header..header + payload.len()].copy_from_slice(payload);
+            return header + payload.len();
+        }
+
+        match self.args_config.length_range {
+            Some((min, max)) => {
+                header
+                    + random_length(
+                        &mut self.rng,
+                        min.get(),
+                        max.get(),
+                        self.args_config.length_distribution,
+                    )
+            }
+            None => self.buffer.len(),
+        }
+    }
+
+    /// Returns the average length of the datagrams, including their headers.
+    fn average_length(&self) -> f64 {
+        let header = self.args_config.header_length() as f64;
+
+        if !self.payloads.is_empty() {
+            let total: usize = self.payloads.iter().map(Vec::len).sum();
+            return header + total as f64 / self.payloads.len() as f64;
+        }
+
+        match self.args_config.length_range {
+            Some((min, max)) => header + (min.get() + max.get()) as f64 / 2.0,
+            None => self.buffer.len() as f64,
+        }
+    }
 
-                thread::sleep(self.args_config.send_periodicity);
+    /// Returns a time to wait after sending a batch of `packets` packets.
+    fn send_delay(&mut self, summary: &AttackSummary, packets: usize) -> Duration {
+        let rate = match (self.args_config.rate, self.args_config.bandwidth) {
+            (Some(rate), _) => rate.get() as f64,
+            // Note that the bandwidth might be less than one packet per second
+            (None, Some(bandwidth)) => bandwidth.get() as f64 / self.average_length(),
+            // The periodicity is kept between packets, not batches, on average
+            (None, None) => {
+                let (periodicity, jitter) =
+                    (self.args_config.send_periodicity, self.args_config.jitter);
+                return (0..packets)
+                    .map(|_| jittered_delay(periodicity, jitter, &mut self.rng))
+                    .sum();
             }
+        };
+
+        rate_delay(
+            rate * self.share,
+            self.args_config.ramp_up,
+            summary.packets_sent(),
+            summary.time_passed(),
+        )
+    }
+
+    /// Returns the time left until the next burst if the bursts are
+    /// configured and the attack is idling between them now.
+    fn burst_pause(&self, summary: &AttackSummary) -> Option<Duration> {
+        match (self.args_config.burst_on, self.args_config.burst_off) {
+            _ if self.args_config.no_delay => None,
+            (Some(on), Some(off)) => match burst_phase(summary.time_passed(), on, off) {
+                BurstPhase::On => None,
+                BurstPhase::Off(remaining) => Some(remaining),
+            },
+            _ => None,
+        }
+    }
 
-            info!("The attack is running with {}.", summary);
+    /// Returns the time left until the `--duration` deadline, which is
+    /// unlimited if the duration isn't specified.
+    fn remaining_time(&self, summary: &AttackSummary) -> Duration {
+        match self.args_config.effective_duration() {
+            Some(duration) => duration
+                .checked_sub(summary.time_passed())
+                .unwrap_or_default(),
+            None => Duration::MAX,
         }
     }
 
-    fn check_end_cond(&self, summary: &AttackSummary) -> bool {
-        if summary.time_passed() >= self.args_config.duration {
+    /// Returns the condition which requires to stop the packet sending,
+    /// if any of them holds.
+    fn stop_reason(&self, summary: &AttackSummary, interrupted: &AtomicBool) -> Option<StopReason> {
+        if interrupted.load(Ordering::SeqCst) {
+            info!("The program is stopping the packet sending because it was interrupted.");
+            return Some(StopReason::Interrupted);
+        }
+        let time_is_over = match self.args_config.duration {
+            Some(duration) => summary.time_passed() >= duration,
+            None => false,
+        };
+        if time_is_over {
+            info!(
+                "The program is stopping the packet sending because \
+                 the allotted time has passed."
+            );
+            return Some(StopReason::Duration);
+        }
+        let runtime_is_over = match self.args_config.max_runtime {
+            Some(max_runtime) => summary.time_passed() >= max_runtime,
+            None => false,
+        };
+        if runtime_is_over {
             info!(
                 "The program is stopping the packet sending because \
-                 the allotted time has passed. The total result is: {}.",
-                summary
+                 --max-runtime is exceeded."
             );
-            return true;
+            return Some(StopReason::MaxRuntime);
         }
-        if summary.packets_sent() == self.args_config.packets.get() {
+        if summary.packets_sent() == self.packets {
             info!(
                 "The program is stopping the packet sending because \
-                 all the required packets were sent. The total result is: {}.",
-                summary
+                 all the required packets were sent."
             );
-            return true;
+            return Some(StopReason::Packets);
+        }
+        if let Some(bytes) = self.byte_limit {
+            if summary.bytes_sent() as u64 >= bytes {
+                info!(
+                    "The program is stopping the packet sending because \
+                     all the required bytes were sent."
+                );
+                return Some(StopReason::Bytes);
+            }
         }
 
-        false
+        None
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Formats a failure printed by `--verbose-errors`, along with the count
+/// of the `skipped` failures printed too often before it.
+fn verbose_error(receiver: &str, error: &io::Error, skipped: u64) -> String {
+    let mut message = format!(
+        "Sending to {} failed ({:?}): {}",
+        receiver,
+        error.kind(),
+        error
+    );
+    if skipped > 0 {
+        message.push_str(&format!(" ({} more failures were skipped)", skipped));
+    }
+    message
+}
 
-    use std::net::SocketAddr;
+/// The most failures printed by `--verbose-errors` per second.
+const VERBOSE_ERRORS_PER_SEC: u32 = 10;
 
-    use structopt::StructOpt;
+/// Allows at most `limit` events per fixed `window`, counting the
+/// skipped events until the next allowed one.
+#[derive(Debug)]
+struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    window_start: Duration,
+    allowed: u32,
+    skipped: u64,
+}
 
-    fn default_config(receiver: SocketAddr) -> ArgsConfig {
-        // The first command-line argument doesn't have any meaning for CLAP
-        ArgsConfig::from_iter_safe(vec!["anevicon", "--receiver", &receiver.to_string()])
-            .expect("The command-line arguments are incorrectly specified")
+impl RateLimiter {
+    fn new(limit: u32, window: Duration) -> RateLimiter {
+        RateLimiter {
+            limit,
+            window,
+            window_start: Duration::from_secs(0),
+            allowed: 0,
+            skipped: 0,
+        }
     }
 
-    fn setup_attacker(args_config: &ArgsConfig) -> Attacker {
-        Attacker::from_args_config(args_config)
-            .expect("Cannot setup the testing attacker with this configuration")
+    /// Registers an event at `now` (since any fixed moment) and returns
+    /// the count of the events skipped before it if the event is
+    /// allowed, or `None` if it's skipped as well.
+    fn allow(&mut self, now: Duration) -> Option<u64> {
+        if now >= self.window_start + self.window {
+            self.window_start = now;
+            self.allowed = 0;
+        }
+
+        if self.allowed < self.limit {
+            self.allowed += 1;
+            Some(std::mem::replace(&mut self.skipped, 0))
+        } else {
+            self.skipped += 1;
+            None
+        }
+    }
+}
+
+/// The only receiver used with `--unix-socket`, whose address is ignored.
+static UNIX_SOCKET_RECEIVER: [(u32, SocketAddr); 1] = [(
+    1,
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)),
+)];
+
+/// Picks the receivers in turn, each proportionally to its weight. It is
+/// the smooth weighted round-robin, so the receivers of equal weights
+/// are simply cycled, and the heavier ones are spread evenly between the
+/// others instead of getting their packets in bursts.
+struct WeightedReceivers<'a> {
+    receivers: &'a [(u32, SocketAddr)],
+    current: Vec<i64>,
+    total: i64,
+}
+
+impl<'a> WeightedReceivers<'a> {
+    fn new(receivers: &'a [(u32, SocketAddr)]) -> WeightedReceivers<'a> {
+        WeightedReceivers {
+            receivers,
+            current: vec![0; receivers.len()],
+            total: receivers.iter().map(|&(weight, _)| i64::from(weight)).sum(),
+        }
     }
 
-    fn setup_server() -> UdpSocket {
-        UdpSocket::bind("0.0.0.0:0")
-            .expect("Cannot setup the testing server with the address 0.0.0.0:0")
+    fn next(&mut self) -> &'a SocketAddr {
+        for (current, &(weight, _)) in self.current.iter_mut().zip(self.receivers) {
+            *current += i64::from(weight);
+        }
+
+        // The first one of the equally heavy receivers is chosen
+        let (chosen, _) = self
+            .current
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|&(_, current)| *current)
+            .expect("No receivers were specified");
+        self.current[chosen] -= self.total;
+
+        &self.receivers[chosen].1
     }
+}
 
-    #[test]
-    fn generates_random_buffer() {
-        let length = unsafe { NonZeroUsize::new_unchecked(35684) };
-        let buffer = Attacker::random_buffer(length);
+/// Returns the addresses which the attacker of `thread` (out of `threads`)
+/// sends packets from: each thread takes every `threads`-th port of the
+/// source ports range of every sender, or every `threads`-th sender
+/// itself if no range is specified. The threads outnumbering the senders
+/// share them in turn.
+fn sender_addresses(args_config: &ArgsConfig, thread: usize, threads: usize) -> Vec<SocketAddr> {
+    let senders = &args_config.sender;
 
-        // Check that we've got the correctly length and capacity
-        assert_eq!(buffer.len(), length.get());
-        assert!(buffer.capacity() >= length.get());
+    match args_config.source_ports {
+        Some((start, end)) => senders
+            .iter()
+            .flat_map(|sender| (start..=end).map(move |port| SocketAddr::new(sender.ip(), port)))
+            .skip(thread)
+            .step_by(threads)
+            .collect(),
+        None if thread < senders.len() => senders
+            .iter()
+            .skip(thread)
+            .step_by(threads)
+            .cloned()
+            .collect(),
+        None => vec![senders[thread % senders.len()]],
     }
+}
 
-    #[test]
-    fn correctly_constructs_attacker() {
-        // Specify any valid-formatted addresses, this isn't essential
-        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
-        config.sender = "127.0.0.1:56978".parse().unwrap();
+/// Pins the current thread to the CPU `core`, so the scheduler never
+/// moves it to another core. It is supported only on Linux.
+#[cfg(target_os = "linux")]
+fn pin_to_core(core: usize) -> io::Result<()> {
+    if core >= libc::CPU_SETSIZE as usize {
+        return Err(io::Error::from_raw_os_error(libc::EINVAL));
+    }
 
-        // Setup our testing attacker with the previous receiver address
-        let attacker = setup_attacker(&config);
+    let result = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
 
-        assert_eq!(attacker.args_config, &config);
-        assert_eq!(
-            attacker
-                .socket
-                .write_timeout()
-                .expect("Cannot get the write timeout from the attacker"),
-            config.send_timeout
-        );
-        assert_eq!(
-            attacker
-                .socket
-                .local_addr()
-                .expect("Cannot get the attacking socket local address"),
-            config.sender
-        );
-        assert_eq!(
-            NonZeroUsize::new(attacker.buffer.len())
-                .expect("The buffer might not be generated (its length equals to zero)"),
-            config.length
-        );
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
     }
+}
 
-    #[test]
-    fn sends_all_packets() {
-        // Assign a very low required packets count to prevent our
-        // lovely Travis CI and your computer for a shameful breaking
-        const REQUIRED_PACKETS: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(25) };
+#[cfg(not(target_os = "linux"))]
+fn pin_to_core(_: usize) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "The CPU affinity isn't supported on your platform",
+    ))
+}
 
-        // Setup the testing server and modify the default config
-        let server = setup_server();
-        let mut config = default_config(
-            server
-                .local_addr()
-                .expect("Cannot get the testing server local address"),
-        );
-        config.packets = REQUIRED_PACKETS;
+/// Makes `interrupted` become `true` when the program receives SIGINT
+/// (Ctrl+C), so the attackers stop gracefully and the summary is
+/// still displayed.
+pub fn handle_interrupts(interrupted: Arc<AtomicBool>) -> Result<(), ctrlc::Error> {
+    ctrlc::set_handler(move || interrupted.store(true, Ordering::SeqCst))
+}
 
-        // Check that our attacker has successfully sent all the packets
-        assert_eq!(
-            setup_attacker(&config)
-                .attack()
-                .expect("An error occurred during the attack")
-                .packets_sent(),
-            REQUIRED_PACKETS.get()
-        );
+/// An error which stopped the attack, along with the statistics of the
+/// packets sent before it, so a failed attack still reports how far it
+/// got.
+#[derive(Debug)]
+pub struct AttackError {
+    pub error: io::Error,
+    pub statistics: Box<Statistics>,
+}
+
+impl From<io::Error> for AttackError {
+    /// Wraps an error which occurred before sending any packets.
+    fn from(error: io::Error) -> AttackError {
+        AttackError {
+            error,
+            statistics: Box::new(AttackSummary::new().statistics()),
+        }
+    }
+}
+
+impl Display for AttackError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.error.fmt(fmt)
+    }
+}
+
+impl Error for AttackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Runs all the attackers simultaneously, each in its own thread, and
+/// returns their summed statistics. The attackers stop as soon as
+/// `interrupted` becomes `true`. If any of them fails, the statistics of
+/// all of them are returned along with the first error.
+pub fn attack_in_threads(
+    attackers: &mut [Attacker],
+    interrupted: &AtomicBool,
+) -> Result<Statistics, AttackError> {
+    let args_config = match attackers.first() {
+        Some(attacker) => attacker.args_config,
+        None => return Ok(AttackSummary::new().statistics()),
+    };
+
+    info!("The program is starting to attack with {}.", args_config);
+    log_local_addrs(attackers);
+    log_plan(attackers);
+    sleep_unless_interrupted(args_config.wait_time(), interrupted);
+
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = attackers
+            .iter_mut()
+            .enumerate()
+            .map(|(thread, attacker)| {
+                // The threads are pinned to the listed cores in turn
+                let core = args_config
+                    .cores
+                    .as_ref()
+                    .map(|cores| cores[thread % cores.len()]);
+
+                scope.spawn(move || {
+                    if let Some(core) = core {
+                        if let Err(error) = pin_to_core(core) {
+                            warn!(
+                                "Cannot pin a thread to the core {}, so it stays unpinned: {}.",
+                                core, error
+                            );
+                        }
+                    }
+                    attacker.send_packets(interrupted)
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("An attacking thread has panicked"))
+            .collect::<Vec<(AttackSummary, io::Result<()>)>>()
+    });
+    finish_status_line(attackers);
+
+    let mut total = AttackSummary::new();
+    let mut failure = None;
+    for (summary, result) in results {
+        total.merge(&summary);
+        if let Err(error) = result {
+            failure.get_or_insert(error);
+        }
+    }
+
+    // The addresses are repeated next to the result, so a long log doesn't hide them
+    log_local_addrs(attackers);
+
+    // The caller prints the total result, because it's the result itself
+    let statistics = total.statistics();
+    match failure {
+        Some(error) => Err(AttackError {
+            error,
+            statistics: Box::new(statistics),
+        }),
+        None => Ok(statistics),
+    }
+}
+
+/// A phase of the bursty sending of `--burst-on` and `--burst-off`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BurstPhase {
+    On,
+    /// Idling, with the time left until the next burst.
+    Off(Duration),
+}
+
+/// Returns the phase at the `elapsed` time of an attack which repeatedly
+/// sends for the `on` time and then idles for the `off` time.
+fn burst_phase(elapsed: Duration, on: Duration, off: Duration) -> BurstPhase {
+    let period = (on + off).as_nanos();
+    if period == 0 {
+        return BurstPhase::On;
+    }
+
+    let position = elapsed.as_nanos() % period;
+    if position < on.as_nanos() {
+        BurstPhase::On
+    } else {
+        BurstPhase::Off(Duration::from_nanos((period - position) as u64))
+    }
+}
+
+/// Checks whether all the first `probe_count` packets of the attack
+/// have failed, in which case the destination is most likely unreachable.
+/// A zero `probe_count` disables the check.
+fn probe_failed(summary: &AttackSummary, probe_count: u64) -> bool {
+    let failed = summary.packets_failed() + summary.packets_unreachable();
+    probe_count > 0 && summary.packets_sent() == 0 && failed as u64 >= probe_count
+}
+
+/// The number of the sending addresses listed in the log, so a wide
+/// --source-ports range doesn't flood it.
+const LISTED_LOCAL_ADDRS: usize = 8;
+
+/// Logs the addresses the sockets of all the `attackers` are bound to.
+fn log_local_addrs(attackers: &[Attacker]) {
+    let addresses: Vec<SocketAddr> = attackers.iter().flat_map(Attacker::local_addrs).collect();
+    if !addresses.is_empty() {
+        info!("The packets are sent from {}.", format_addrs(&addresses));
+    }
+}
+
+/// Joins the first `LISTED_LOCAL_ADDRS` of the `addresses` by commas,
+/// mentioning how many of them are omitted.
+fn format_addrs(addresses: &[SocketAddr]) -> String {
+    let listed = addresses
+        .iter()
+        .take(LISTED_LOCAL_ADDRS)
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    match addresses.len().saturating_sub(LISTED_LOCAL_ADDRS) {
+        0 => listed,
+        omitted => format!("{} and {} more", listed, omitted),
+    }
+}
+
+/// Logs a concise plan of the attack (the receivers, the senders, the time
+/// limit, and the expected traffic), so a misconfigured attack can still
+/// be stopped during `--wait`. Like the other notifications, it isn't
+/// displayed in the quiet mode.
+fn log_plan(attackers: &[Attacker]) {
+    let attacker = match attackers.first() {
+        Some(attacker) => attacker,
+        None => return,
+    };
+    let args_config = attacker.args_config;
+
+    let receivers = match args_config.unix_socket {
+        Some(ref path) => path.display().to_string(),
+        None => format_addrs(
+            &args_config
+                .receiver_addresses()
+                .cloned()
+                .collect::<Vec<_>>(),
+        ),
+    };
+    let senders = match attackers
+        .iter()
+        .flat_map(Attacker::local_addrs)
+        .collect::<Vec<SocketAddr>>()
+        .as_slice()
+    {
+        [] => String::new(),
+        addresses => format!(" from {}", format_addrs(addresses)),
+    };
+    let length = match args_config.length_range {
+        _ if !attacker.payloads.is_empty() => {
+            format!("{:.0} bytes on average", attacker.average_length())
+        }
+        Some((min, max)) => format!(
+            "{} to {} bytes",
+            min.get() + args_config.header_length(),
+            max.get() + args_config.header_length()
+        ),
+        None => format!("{} bytes", attacker.buffer.len()),
+    };
+    let duration = match args_config.effective_duration() {
+        Some(duration) => format!("for {}", format_duration(duration)),
+        None => String::from("without a time limit"),
+    };
+    let traffic = match expected_packets(attacker, attackers.len()) {
+        Some(packets) => format!(
+            "about {} packets ({} bytes)",
+            packets,
+            (packets as f64 * attacker.average_length()).round() as u64
+        ),
+        None => String::from("an unlimited count of packets"),
+    };
+
+    info!(
+        "The program plans to send {} of {} to {}{} {}.",
+        traffic, length, receivers, senders, duration
+    );
+}
+
+/// Returns an estimated total count of packets sent by all the `threads`
+/// like `attacker`, which is the least of the count allowed by the pacing
+/// within the time limit, `--packets`, and `--bytes`. If none of them
+/// limits the attack, `None` is returned.
+fn expected_packets(attacker: &Attacker, threads: usize) -> Option<u64> {
+    let args_config = attacker.args_config;
+    let average_length = attacker.average_length();
+
+    let rate = match (args_config.rate, args_config.bandwidth) {
+        (Some(rate), _) => Some(rate.get() as f64),
+        (None, Some(bandwidth)) => Some(bandwidth.get() as f64 / average_length),
+        // Each thread keeps the periodicity on its own
+        (None, None) if args_config.send_periodicity > Duration::from_secs(0) => {
+            Some(threads as f64 / args_config.send_periodicity.as_secs_f64())
+        }
+        (None, None) => None,
+    };
+    let paced = match (rate, args_config.effective_duration()) {
+        (Some(rate), Some(duration)) if rate.is_finite() => {
+            let sending = match (args_config.burst_on, args_config.burst_off) {
+                (Some(on), Some(off)) => burst_sending_time(duration, on, off),
+                _ => duration,
+            };
+            Some(estimate_packets(rate, args_config.ramp_up, sending).round() as u64)
+        }
+        _ => None,
+    };
+
+    // The default --packets is the largest number, that is, no limit at all
+    let packets = match args_config.packets.get() {
+        usize::MAX => None,
+        packets => Some(packets as u64),
+    };
+    let bytes = match args_config.byte_limit {
+        Some(limit) if average_length > 0.0 => Some((limit as f64 / average_length).ceil() as u64),
+        _ => None,
+    };
+
+    [paced, packets, bytes].iter().flatten().min().cloned()
+}
+
+/// Returns the time of sending within `duration` of an attack which
+/// repeatedly sends for the `on` time and then idles for the `off` time.
+fn burst_sending_time(duration: Duration, on: Duration, off: Duration) -> Duration {
+    let period = (on + off).as_nanos();
+    if period == 0 {
+        return duration;
+    }
+
+    let cycles = duration.as_nanos() / period;
+    let rest = Duration::from_nanos((duration.as_nanos() % period) as u64);
+    Duration::from_nanos((cycles * on.as_nanos()) as u64) + rest.min(on)
+}
+
+/// How the attack progress is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProgressStyle {
+    Hidden,
+    Lines,
+    StatusLine,
+}
+
+/// Chooses how to display the attack progress. The status line can be
+/// redrawn only on a terminal (`is_tty`), so the usual lines are printed
+/// instead of it otherwise.
+fn progress_style(args_config: &ArgsConfig, is_tty: bool) -> ProgressStyle {
+    if args_config.quiet || report_interval(args_config) == Duration::from_secs(0) {
+        ProgressStyle::Hidden
+    } else if args_config.status_line && is_tty {
+        ProgressStyle::StatusLine
+    } else {
+        ProgressStyle::Lines
+    }
+}
+
+/// Returns the periodicity of displaying the attack progress, which is a
+/// second for the status line without an explicit --report-interval.
+fn report_interval(args_config: &ArgsConfig) -> Duration {
+    match args_config.report_interval {
+        interval if interval == Duration::from_secs(0) && args_config.status_line => {
+            Duration::from_secs(1)
+        }
+        interval => interval,
+    }
+}
+
+/// Moves the cursor past the status line, so the next printed line
+/// (such as the final summary) doesn't overwrite it.
+fn finish_status_line(attackers: &[Attacker]) {
+    if attackers
+        .iter()
+        .any(|attacker| attacker.progress == ProgressStyle::StatusLine)
+    {
+        eprintln!();
+    }
+}
+
+/// Sleeps for `duration`, but wakes up early if `interrupted` becomes
+/// `true`, so a long waiting doesn't delay the interruption.
+fn sleep_unless_interrupted(duration: Duration, interrupted: &AtomicBool) {
+    const SLICE: Duration = Duration::from_millis(100);
+
+    // The fast path of a zero periodicity: the next packet is sent at once
+    if duration == Duration::from_secs(0) {
+        return;
+    }
+
+    let initial_time = Instant::now();
+    while !interrupted.load(Ordering::SeqCst) {
+        let remaining = match duration.checked_sub(initial_time.elapsed()) {
+            Some(remaining) if remaining > Duration::from_secs(0) => remaining,
+            _ => return,
+        };
+        thread::sleep(remaining.min(SLICE));
+    }
+}
+
+/// Checks whether the content of the buffer changes from packet to
+/// packet, so a batch cannot reuse it for all of its packets. Note that
+/// the random lengths are prefixes of the same buffer.
+fn has_varying_payload(args_config: &ArgsConfig) -> bool {
+    args_config.random_payload
+        || args_config.sequence
+        || args_config.template.is_some()
+        || args_config.send_file.len() > 1
+        // The checksum of each random length differs
+        || (args_config.magic.is_some() && args_config.length_range.is_some())
+}
+
+/// Chooses a random packet length from the inclusive range between `min`
+/// and `max`. The normal distribution is centered on the middle of the
+/// range, which spans six standard deviations, and the lengths beyond
+/// the range are clamped to its bounds.
+fn random_length(
+    rng: &mut StdRng,
+    min: usize,
+    max: usize,
+    distribution: LengthDistribution,
+) -> usize {
+    match distribution {
+        LengthDistribution::Uniform => rng.gen_range(min, max + 1),
+        LengthDistribution::Normal => {
+            let (mean, std_dev) = ((min + max) as f64 / 2.0, (max - min) as f64 / 6.0);
+            let length = rng.sample(Normal::new(mean, std_dev)).round();
+            length.max(min as f64).min(max as f64) as usize
+        }
+    }
+}
+
+/// Returns the bytes of the first `sent` packets of the `batch`, which
+/// were sent. If the batch wasn't sent entirely, the next packet is the
+/// one that failed, and the packets after it are sent by the next call.
+fn sent_bytes(batch: &[(usize, &SocketAddr)], sent: usize) -> usize {
+    batch[..sent].iter().map(|&(length, _)| length).sum()
+}
+
+/// Checks whether a failed sending call might succeed if it's repeated:
+/// the socket buffer is full (EAGAIN or ENOBUFS), or the call was
+/// interrupted by a signal.
+fn is_retryable(error: &io::Error) -> bool {
+    match error.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted => true,
+        #[cfg(unix)]
+        _ if error.raw_os_error() == Some(libc::ENOBUFS) => true,
+        _ => false,
+    }
+}
+
+/// Checks whether a failed sending call reports an ICMP "port
+/// unreachable" response to a previous packet (ECONNREFUSED), that is,
+/// the port of the receiver is closed.
+fn is_unreachable(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::ConnectionRefused
+}
+
+/// Returns the delay before the retry after `attempt` previous ones: a
+/// millisecond doubled after each attempt, but at most 100 milliseconds.
+fn retry_delay(attempt: u32) -> Duration {
+    const MAX_DELAY: Duration = Duration::from_millis(100);
+
+    Duration::from_millis(1u64.checked_shl(attempt).unwrap_or(u64::MAX)).min(MAX_DELAY)
+}
+
+/// Writes the `magic` value into the first four bytes of the `packet`,
+/// followed by the CRC32 of the rest of it. The packet must be at least
+/// `MAGIC_HEADER_LENGTH` bytes.
+fn write_magic_header(magic: u32, packet: &mut [u8]) {
+    let checksum = crc32(&packet[MAGIC_HEADER_LENGTH..]);
+
+    packet[..4].copy_from_slice(&magic.to_be_bytes());
+    packet[4..MAGIC_HEADER_LENGTH].copy_from_slice(&checksum.to_be_bytes());
+}
+
+/// The lookup table of the CRC32 (IEEE 802.3) of every byte value.
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut byte = 0;
+
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+}
+
+/// Computes the CRC32 (IEEE 802.3) of `bytes`, the same one as computed
+/// by zlib.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| {
+        CRC32_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Writes the template into the buffer, substituting the packet index
+/// and the timestamp. The result is truncated to the buffer length, and
+/// the rest of the buffer is filled with zeros.
+fn render_template(template: &[TemplatePart], number: u64, timestamp: u128, buffer: &mut [u8]) {
+    let mut cursor = Cursor::new(&mut *buffer);
+
+    for part in template {
+        let result = match part {
+            TemplatePart::Text(text) => cursor.write_all(text.as_bytes()),
+            TemplatePart::Seq => write!(cursor, "{}", number),
+            TemplatePart::Timestamp => write!(cursor, "{}", timestamp),
+        };
+        // The buffer is full, so the rest of the template is truncated
+        if result.is_err() {
+            break;
+        }
+    }
+
+    let written = cursor.position() as usize;
+    for byte in &mut buffer[written..] {
+        *byte = 0;
+    }
+}
+
+fn unix_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or(0)
+}
+
+/// Returns the count of packets sent per second during `time_spent`.
+fn current_speed(packets_sent: usize, time_spent: Duration) -> usize {
+    let secs_spent = time_spent.as_secs_f64();
+
+    if secs_spent == 0.0 {
+        0
+    } else {
+        (packets_sent as f64 / secs_spent).round() as usize
+    }
+}
+
+fn round_to_millis(duration: Duration) -> Duration {
+    Duration::from_millis(duration.as_millis() as u64)
+}
+
+/// Returns the generator of `thread` for the `--seed` run, so every thread
+/// produces its own stream, which is the same in each run. The thread
+/// index is spread over the seed bits, so the streams of the neighbouring
+/// seeds don't coincide, and the first thread keeps the seed itself.
+fn thread_rng_for(seed: u64, thread: usize) -> StdRng {
+    StdRng::seed_from_u64(seed ^ (thread as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+}
+
+/// Returns `periodicity` shifted by a random amount within `jitter` in
+/// either direction, but never a negative delay.
+fn jittered_delay(periodicity: Duration, jitter: Duration, rng: &mut StdRng) -> Duration {
+    // Don't touch the generator, so the payloads of a seeded run stay the same
+    if jitter == Duration::from_secs(0) {
+        return periodicity;
+    }
+
+    let offset = rng.gen_range(-1.0, 1.0) * jitter.as_secs_f64();
+    Duration::from_secs_f64((periodicity.as_secs_f64() + offset).max(0.0))
+}
+
+/// Returns a time to wait before sending the next packet so that the
+/// average rate doesn't exceed `rate` (possibly fractional) packets per
+/// second. During `ramp_up`, the rate linearly grows from zero to `rate`,
+/// so the first packets are scheduled less densely. If the program is
+/// behind the schedule (the system cannot send packets that fast), the
+/// next packet must be sent immediately.
+fn rate_delay(
+    rate: f64,
+    ramp_up: Duration,
+    packets_sent: usize,
+    time_passed: Duration,
+) -> Duration {
+    let (packets, ramp_up) = (packets_sent as f64, ramp_up.as_secs_f64());
+
+    // The ramp-up sends `rate * t^2 / (2 * ramp_up)` packets by the time t,
+    // and after it the packets are sent at the full rate
+    let scheduled_secs = if packets < rate * ramp_up / 2.0 {
+        (2.0 * ramp_up * packets / rate).sqrt()
+    } else {
+        packets / rate + ramp_up / 2.0
+    };
+
+    Duration::from_secs_f64(scheduled_secs)
+        .checked_sub(time_passed)
+        .unwrap_or_else(|| Duration::from_secs(0))
+}
+
+/// Returns a count of packets sent by `rate` (possibly fractional)
+/// packets per second within `duration`, where the rate linearly grows
+/// from zero during `ramp_up`, just as `rate_delay` schedules them.
+pub fn estimate_packets(rate: f64, ramp_up: Duration, duration: Duration) -> f64 {
+    let (ramp_up, duration) = (ramp_up.as_secs_f64(), duration.as_secs_f64());
+
+    if duration < ramp_up {
+        rate * duration * duration / (2.0 * ramp_up)
+    } else {
+        rate * (duration - ramp_up / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::num::NonZeroUsize;
+    use std::path::PathBuf;
+    use std::process;
+
+    use crate::config::{
+        PacketLengthError, PayloadFileError, ABSOLUTE_MAX_PACKET_LENGTH, MAX_PACKET_LENGTH,
+    };
+    use crate::summary::CSV_HEADER;
+
+    use structopt::StructOpt;
+
+    fn default_config(receiver: SocketAddr) -> ArgsConfig {
+        // The first command-line argument doesn't have any meaning for CLAP
+        ArgsConfig::from_iter_safe(vec!["anevicon", "--receiver", &receiver.to_string()])
+            .expect("The command-line arguments are incorrectly specified")
+    }
+
+    fn setup_attacker(args_config: &ArgsConfig) -> Attacker<'_> {
+        Attacker::from_args_config(args_config)
+            .expect("Cannot setup the testing attacker with this configuration")
+    }
+
+    fn setup_server() -> UdpSocket {
+        UdpSocket::bind("127.0.0.1:0")
+            .expect("Cannot setup the testing server with the address 127.0.0.1:0")
+    }
+
+    #[test]
+    fn generates_random_buffer() {
+        let length = 35684;
+        let buffer = Attacker::random_buffer(&mut StdRng::from_entropy(), length);
+
+        // Check that we've got the correctly length and capacity
+        assert_eq!(buffer.len(), length);
+        assert!(buffer.capacity() >= length);
+    }
+
+    #[test]
+    fn same_seed_generates_same_packets() {
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.seed = Some(4298);
+
+        let mut first = setup_attacker(&config);
+        let mut second = setup_attacker(&config);
+        assert_eq!(first.buffer, second.buffer);
+
+        // The next random payloads must be identical as well
+        first.rng.fill_bytes(first.buffer.as_mut_slice());
+        second.rng.fill_bytes(second.buffer.as_mut_slice());
+        assert_eq!(first.buffer, second.buffer);
+
+        let mut other_config = default_config("127.0.0.1:53364".parse().unwrap());
+        other_config.seed = Some(4299);
+        assert_ne!(setup_attacker(&other_config).buffer, first.buffer);
+    }
+
+    #[test]
+    fn stops_after_byte_limit() {
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.byte_limit = Some(3000);
+        let attacker = setup_attacker(&config);
+        let interrupted = AtomicBool::new(false);
+
+        let mut summary = AttackSummary::new();
+        summary.update(2999, 2);
+        assert_eq!(attacker.stop_reason(&summary, &interrupted), None);
+        summary.update(1, 1);
+        assert_eq!(
+            attacker.stop_reason(&summary, &interrupted),
+            Some(StopReason::Bytes)
+        );
+
+        // The packets count stops the attack if it's hit before the bytes count
+        config.packets = NonZeroUsize::new(2).unwrap();
+        let attacker = setup_attacker(&config);
+        let mut summary = AttackSummary::new();
+        summary.update(1000, 2);
+        assert_eq!(
+            attacker.stop_reason(&summary, &interrupted),
+            Some(StopReason::Packets)
+        );
+
+        // Each thread takes an equal share of the bytes count
+        config.packets = NonZeroUsize::new(100).unwrap();
+        config.threads = NonZeroUsize::new(4).unwrap();
+        config.byte_limit = Some(4002);
+        let limits: Vec<Option<u64>> = Attacker::group_from_args_config(&config)
+            .expect("Cannot setup the testing attackers")
+            .iter()
+            .map(|attacker| attacker.byte_limit)
+            .collect();
+        assert_eq!(limits, vec![Some(1001), Some(1001), Some(1000), Some(1000)]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn classifies_retryable_errors() {
+        assert!(is_retryable(&io::Error::from(io::ErrorKind::WouldBlock)));
+        assert!(is_retryable(&io::Error::from(io::ErrorKind::Interrupted)));
+        assert!(is_retryable(&io::Error::from_raw_os_error(libc::EAGAIN)));
+        assert!(is_retryable(&io::Error::from_raw_os_error(libc::ENOBUFS)));
+
+        // The errors which a retry cannot fix must fail immediately
+        assert!(!is_retryable(&io::Error::from_raw_os_error(libc::EINVAL)));
+        assert!(!is_retryable(&io::Error::from_raw_os_error(libc::EACCES)));
+        assert!(!is_retryable(&io::Error::from_raw_os_error(
+            libc::ENETUNREACH
+        )));
+        assert!(!is_retryable(&io::Error::from_raw_os_error(libc::EMSGSIZE)));
+        assert!(!is_retryable(&io::Error::from(io::ErrorKind::InvalidInput)));
+    }
+
+    #[test]
+    fn classifies_unreachable_errors() {
+        assert!(is_unreachable(&io::Error::from_raw_os_error(
+            libc::ECONNREFUSED
+        )));
+        assert!(is_unreachable(&io::Error::from(
+            io::ErrorKind::ConnectionRefused
+        )));
+
+        // A full send buffer is a different kind of failure
+        assert!(!is_unreachable(&io::Error::from_raw_os_error(
+            libc::ENOBUFS
+        )));
+        assert!(!is_unreachable(&io::Error::from_raw_os_error(libc::EAGAIN)));
+        assert!(!is_unreachable(&io::Error::from_raw_os_error(
+            libc::ENETUNREACH
+        )));
+    }
+
+    #[test]
+    fn computes_retry_delay() {
+        assert_eq!(retry_delay(0), Duration::from_millis(1));
+        assert_eq!(retry_delay(1), Duration::from_millis(2));
+        assert_eq!(retry_delay(5), Duration::from_millis(32));
+        assert_eq!(retry_delay(7), Duration::from_millis(100));
+        assert_eq!(retry_delay(u32::MAX), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn computes_rate_delay() {
+        let rate = 100.0;
+
+        // The 50th packet at 100 pps is scheduled at the half of a second
+        assert_eq!(
+            rate_delay(rate, Duration::from_secs(0), 50, Duration::from_millis(200)),
+            Duration::from_millis(300)
+        );
+        assert_eq!(
+            rate_delay(rate, Duration::from_secs(0), 50, Duration::from_millis(500)),
+            Duration::from_secs(0)
+        );
+
+        // Being behind the schedule must not produce any delay
+        assert_eq!(
+            rate_delay(rate, Duration::from_secs(0), 50, Duration::from_secs(3)),
+            Duration::from_secs(0)
+        );
+
+        // A rate less than one packet per second must be handled as well
+        assert_eq!(
+            rate_delay(0.25, Duration::from_secs(0), 1, Duration::from_secs(1)),
+            Duration::from_secs(3)
+        );
+    }
+
+    #[test]
+    fn computes_jittered_delay() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let (periodicity, jitter) = (Duration::from_millis(100), Duration::from_millis(30));
+
+        let delays: Vec<Duration> = (0..1000)
+            .map(|_| jittered_delay(periodicity, jitter, &mut rng))
+            .collect();
+        assert!(delays
+            .iter()
+            .all(|delay| *delay >= periodicity - jitter && *delay <= periodicity + jitter));
+        assert!(delays.iter().any(|delay| *delay != periodicity));
+
+        // A jitter larger than the periodicity must not produce negative delays
+        for _ in 0..1000 {
+            assert!(
+                jittered_delay(
+                    Duration::from_millis(10),
+                    Duration::from_millis(50),
+                    &mut rng
+                ) <= Duration::from_millis(60)
+            );
+        }
+
+        assert_eq!(
+            jittered_delay(periodicity, Duration::from_secs(0), &mut rng),
+            periodicity
+        );
+    }
+
+    #[test]
+    fn computes_ramped_rate_delay() {
+        let (rate, ramp_up) = (100.0, Duration::from_secs(2));
+
+        // The first packet is sent right at the start of the ramp-up
+        assert_eq!(
+            rate_delay(rate, ramp_up, 0, Duration::from_secs(0)),
+            Duration::from_secs(0)
+        );
+
+        // At the half of the ramp-up, 25 packets are sent at 50 pps
+        assert_eq!(
+            rate_delay(rate, ramp_up, 25, Duration::from_secs(0)),
+            Duration::from_secs(1)
+        );
+        let gap = rate_delay(rate, ramp_up, 26, Duration::from_secs(1));
+        assert!(gap > Duration::from_millis(19) && gap < Duration::from_millis(21));
+
+        // After the ramp-up, the full rate is held steady
+        assert_eq!(
+            rate_delay(rate, ramp_up, 200, Duration::from_secs(0)),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            rate_delay(rate, ramp_up, 201, Duration::from_secs(3)),
+            Duration::from_millis(10)
+        );
+    }
+
+    #[test]
+    fn computes_current_speed() {
+        assert_eq!(current_speed(500, Duration::from_secs(2)), 250);
+        assert_eq!(current_speed(3, Duration::from_millis(500)), 6);
+        assert_eq!(current_speed(500, Duration::from_secs(0)), 0);
+    }
+
+    #[test]
+    fn limits_bandwidth() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(1024);
+        config.bandwidth = NonZeroUsize::new(20 * 1024);
+        config.packets = NonZeroUsize::new(10).unwrap();
+
+        // Sending 10 kilobytes at 20 KB/s must take about a half of a second
+        let summary = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert!(summary.elapsed() >= Duration::from_millis(450));
+    }
+
+    #[test]
+    fn sleeps_during_warmup() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.warmup = Duration::from_millis(300);
+        config.packets = NonZeroUsize::new(5).unwrap();
+
+        let initial_time = Instant::now();
+        let summary = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert!(initial_time.elapsed() >= Duration::from_millis(300));
+        assert_eq!(summary.packets_sent(), 5);
+    }
+
+    #[test]
+    fn limits_packets_per_second() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(64);
+        config.rate = NonZeroUsize::new(50);
+        config.packets = NonZeroUsize::new(25).unwrap();
+
+        // Sending 25 packets at 50 pps must take about a half of a second
+        let summary = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert!(summary.elapsed() >= Duration::from_millis(450));
+    }
+
+    #[test]
+    fn stops_when_interrupted() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(64);
+        config.threads = NonZeroUsize::new(2).unwrap();
+        config.send_periodicity = Duration::from_millis(10);
+
+        let interrupted = AtomicBool::new(false);
+        let mut attackers =
+            Attacker::group_from_args_config(&config).expect("Cannot setup the attackers");
+
+        // The attack is infinite, so only an interruption can stop it
+        let summary = thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(300));
+                interrupted.store(true, Ordering::SeqCst);
+            });
+            attack_in_threads(&mut attackers, &interrupted)
+        })
+        .expect("An error occurred during the attack");
+
+        assert!(summary.packets_sent() > 0);
+        assert_eq!(summary.packets_failed(), 0);
+        assert!(summary.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn interrupts_sleeping() {
+        let initial_time = Instant::now();
+        sleep_unless_interrupted(Duration::from_millis(150), &AtomicBool::new(false));
+        assert!(initial_time.elapsed() >= Duration::from_millis(150));
+
+        let initial_time = Instant::now();
+        sleep_unless_interrupted(Duration::from_secs(60), &AtomicBool::new(true));
+        assert!(initial_time.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn handles_zero_and_tiny_periodicity() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(64);
+        config.packets = NonZeroUsize::new(20).unwrap();
+
+        // A zero periodicity sends packets as fast as possible
+        config.send_periodicity = Duration::from_secs(0);
+        let summary = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(summary.packets_sent(), 20);
+        assert!(summary.elapsed() < Duration::from_secs(1));
+
+        config.send_periodicity = Duration::from_millis(1);
+        let summary = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(summary.packets_sent(), 20);
+        assert!(summary.elapsed() >= Duration::from_millis(19));
+    }
+
+    #[test]
+    fn splits_packets_between_threads() {
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.threads = NonZeroUsize::new(3).unwrap();
+        config.packets = NonZeroUsize::new(10).unwrap();
+
+        let attackers =
+            Attacker::group_from_args_config(&config).expect("Cannot setup the attackers");
+        assert_eq!(
+            attackers
+                .iter()
+                .map(|attacker| attacker.packets)
+                .collect::<Vec<usize>>(),
+            vec![4, 3, 3]
+        );
+
+        // Each thread must have its own socket
+        assert_ne!(
+            attackers[0].sockets[0]
+                .as_udp()
+                .unwrap()
+                .local_addr()
+                .unwrap(),
+            attackers[1].sockets[0]
+                .as_udp()
+                .unwrap()
+                .local_addr()
+                .unwrap()
+        );
+
+        // There cannot be more threads than packets
+        config.packets = NonZeroUsize::new(2).unwrap();
+        assert_eq!(Attacker::group_from_args_config(&config).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn sends_from_source_ports() {
+        let server = setup_server();
+        server
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.sender = vec!["127.0.0.1:0".parse().unwrap()];
+        config.length = Some(16);
+        config.packets = NonZeroUsize::new(4).unwrap();
+
+        // Find two consecutive free ports to bind the attacking sockets to
+        let start = (0..100)
+            .map(|_| {
+                UdpSocket::bind("127.0.0.1:0")
+                    .unwrap()
+                    .local_addr()
+                    .unwrap()
+                    .port()
+            })
+            .find(|&port| port < u16::MAX && UdpSocket::bind(("127.0.0.1", port + 1)).is_ok())
+            .expect("Cannot find two consecutive free ports");
+        config.source_ports = Some((start, start + 1));
+
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        let mut buffer = [0; 16];
+        let ports: Vec<u16> = (0..4)
+            .map(|_| server.recv_from(&mut buffer).unwrap().1.port())
+            .collect();
+        assert_eq!(ports, vec![start, start + 1, start, start + 1]);
+
+        // Each thread must take its own ports, and a single port cannot be shared
+        config.threads = NonZeroUsize::new(3).unwrap();
+        let attackers =
+            Attacker::group_from_args_config(&config).expect("Cannot setup the attackers");
+        assert_eq!(attackers.len(), 2);
+        assert_eq!(
+            attackers[0].sockets[0]
+                .as_udp()
+                .unwrap()
+                .local_addr()
+                .unwrap()
+                .port(),
+            start
+        );
+        assert_eq!(
+            attackers[1].sockets[0]
+                .as_udp()
+                .unwrap()
+                .local_addr()
+                .unwrap()
+                .port(),
+            start + 1
+        );
+    }
+
+    #[test]
+    fn sums_threads_summaries() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(128);
+        config.threads = NonZeroUsize::new(4).unwrap();
+        config.packets = NonZeroUsize::new(50).unwrap();
+
+        let mut attackers =
+            Attacker::group_from_args_config(&config).expect("Cannot setup the attackers");
+        let summary = attack_in_threads(&mut attackers, &AtomicBool::new(false))
+            .expect("An error occurred during the attack");
+        assert_eq!(summary.packets_sent(), 50);
+    }
+
+    #[test]
+    fn allows_consecutive_errors() {
+        // Every second packet fails because an IPv4 socket cannot send to IPv6
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.receiver.push((1, "[::1]:53364".parse().unwrap()));
+        config.sender = vec!["127.0.0.1:0".parse().unwrap()];
+        config.wait = Duration::from_secs(0);
+        config.length = Some(64);
+        config.packets = NonZeroUsize::new(5).unwrap();
+
+        // By default, the first error must stop the attack
+        assert!(setup_attacker(&config).attack().is_err());
+
+        config.allowed_errors = 1;
+        let summary = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(summary.packets_sent(), 5);
+        assert_eq!(summary.packets_failed(), 4);
+
+        // Two consecutive errors are more than allowed
+        config.receiver.push((1, "[::1]:53365".parse().unwrap()));
+        assert!(setup_attacker(&config).attack().is_err());
+    }
+
+    #[test]
+    fn sends_random_lengths() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length_range = Some((
+            NonZeroUsize::new(10).unwrap(),
+            NonZeroUsize::new(20).unwrap(),
+        ));
+        config.packets = NonZeroUsize::new(30).unwrap();
+
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        let mut buffer = [0; 64];
+        server
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        for _ in 0..30 {
+            let length = server.recv(&mut buffer).expect("A packet was lost");
+            assert!((10..=20).contains(&length));
+        }
+    }
+
+    #[test]
+    fn keeps_random_lengths_in_range() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for &distribution in &[LengthDistribution::Uniform, LengthDistribution::Normal] {
+            let lengths: Vec<usize> = (0..10_000)
+                .map(|_| random_length(&mut rng, 100, 200, distribution))
+                .collect();
+            assert!(lengths.iter().all(|length| (100..=200).contains(length)));
+
+            // Both distributions are centered on the middle of the range
+            let mean = lengths.iter().sum::<usize>() as f64 / lengths.len() as f64;
+            assert!((mean - 150.0).abs() < 2.0, "The mean is {}", mean);
+        }
+
+        // About 95% of the normal lengths are within two standard deviations
+        let central = (0..10_000)
+            .map(|_| random_length(&mut rng, 100, 200, LengthDistribution::Normal))
+            .filter(|length| (117..=183).contains(length))
+            .count();
+        assert!(central > 9_000, "Only {} central lengths", central);
+
+        assert_eq!(
+            random_length(&mut rng, 64, 64, LengthDistribution::Normal),
+            64
+        );
+    }
+
+    #[test]
+    fn appends_csv_rows() {
+        let server = setup_server();
+        let path = temp_file("rows.csv", b"");
+
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(16);
+        config.display_periodicity = NonZeroUsize::new(2).unwrap();
+        config.packets = NonZeroUsize::new(7).unwrap();
+        config.csv_path = Some(path.clone());
+
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        // A row is appended each time the summary is displayed
+        let content = fs::read_to_string(&path).unwrap();
+        let rows: Vec<&str> = content.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2].split(',').nth(1), Some("6"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn creates_csv_with_header() {
+        let server = setup_server();
+        let path = env::temp_dir().join(format!("anevicon-{}-header.csv", process::id()));
+
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.packets = NonZeroUsize::new(3).unwrap();
+        config.csv_path = Some(path.clone());
+
+        // The file appears only when the attack starts, with its header first
+        assert!(!path.exists());
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().next(), Some(CSV_HEADER));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    fn temp_file(name: &str, content: &[u8]) -> PathBuf {
+        let path = env::temp_dir().join(format!("anevicon-{}-{}", process::id(), name));
+        fs::write(&path, content).expect("Cannot write the testing file");
+        path
+    }
+
+    #[test]
+    fn reads_file_buffer() {
+        let path = temp_file("small", b"A captured malformed request");
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.send_file = vec![path.clone()];
+
+        // The file content must win over the specified length
+        config.length = Some(1000);
+        assert_eq!(setup_attacker(&config).buffer, fs::read(&path).unwrap());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn repeats_file_buffer() {
+        let path = temp_file("repeated", b"abc");
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.send_file = vec![path.clone()];
+        config.length = Some(8);
+        config.repeat_payload = true;
+
+        // The last repetition must be truncated to the specified length
+        assert_eq!(setup_attacker(&config).buffer, b"abcabcab".to_vec());
+
+        // A length shorter than the file doesn't truncate the file
+        config.length = Some(2);
+        assert_eq!(setup_attacker(&config).buffer, b"abc".to_vec());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn decodes_hex_buffer() {
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.payload_hex = Some(String::from("ca:fe ba:be"));
+        assert_eq!(setup_attacker(&config).buffer, vec![0xca, 0xfe, 0xba, 0xbe]);
+    }
+
+    #[test]
+    fn rejects_too_large_file() {
+        let path = temp_file("large", &vec![0; ABSOLUTE_MAX_PACKET_LENGTH + 1]);
+        let config = default_config("127.0.0.1:53364".parse().unwrap());
+
+        let error =
+            Attacker::file_buffer(&config, &path).expect_err("Reads a too large file correctly");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error
+                .get_ref()
+                .and_then(|error| error.downcast_ref::<PayloadFileError>()),
+            Some(&PayloadFileError {
+                path: path.clone(),
+                error: PacketLengthError::Overflow
+            })
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn checks_file_against_max_length() {
+        let path = temp_file("jumbo", &vec![0; MAX_PACKET_LENGTH + 1]);
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.send_file = vec![path.clone()];
+
+        let error = Attacker::from_args_config(&config).expect_err("Reads a too large file");
+        assert_eq!(
+            error
+                .get_ref()
+                .and_then(|error| error.downcast_ref::<PayloadFileError>())
+                .map(|error| &error.error),
+            Some(&PacketLengthError::AboveMaximum(MAX_PACKET_LENGTH))
+        );
+
+        // The file fits into an IPv6 packet, so it is allowed explicitly
+        config.max_packet_length = NonZeroUsize::new(ABSOLUTE_MAX_PACKET_LENGTH).unwrap();
+        assert_eq!(setup_attacker(&config).buffer.len(), MAX_PACKET_LENGTH + 1);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rotates_payload_files() {
+        let server = setup_server();
+        let (first, second) = (
+            temp_file("first", b"first"),
+            temp_file("second", b"a second"),
+        );
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.packets = NonZeroUsize::new(4).unwrap();
+        config.send_file = vec![first.clone(), second.clone()];
+
+        let mut attacker = setup_attacker(&config);
+        assert_eq!(attacker.average_length(), 6.5);
+        let statistics = attacker
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.bytes_sent(), 2 * (5 + 8));
+
+        // The files are sent in turn, each with its own length
+        let mut buffer = [0; 16];
+        for &expected in &[&b"first"[..], b"a second", b"first", b"a second"] {
+            let length = server.recv(&mut buffer).expect("A packet was lost");
+            assert_eq!(&buffer[..length], expected);
+        }
+
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn reports_too_large_payload_file() {
+        let small = temp_file("pool-small", b"small");
+        let large = temp_file("pool-large", &vec![0; MAX_PACKET_LENGTH + 1]);
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.send_file = vec![small.clone(), large.clone()];
+
+        // The error must name the file which doesn't fit
+        let error = Attacker::from_args_config(&config).expect_err("Reads a too large file");
+        assert_eq!(
+            error
+                .get_ref()
+                .and_then(|error| error.downcast_ref::<PayloadFileError>()),
+            Some(&PayloadFileError {
+                path: large.clone(),
+                error: PacketLengthError::AboveMaximum(MAX_PACKET_LENGTH)
+            })
+        );
+        assert!(error.to_string().contains(&large.display().to_string()));
+
+        fs::remove_file(small).unwrap();
+        fs::remove_file(large).unwrap();
+    }
+
+    #[test]
+    fn sends_payload_files_in_batches() {
+        let server = setup_server();
+        let (first, second) = (
+            temp_file("batch-first", b"1"),
+            temp_file("batch-second", b"22"),
+        );
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.packets = NonZeroUsize::new(3).unwrap();
+        config.batch_size = 3;
+        config.send_file = vec![first.clone(), second.clone()];
+
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        let mut buffer = [0; 16];
+        for &expected in &[&b"1"[..], b"22", b"1"] {
+            let length = server.recv(&mut buffer).expect("A packet was lost");
+            assert_eq!(&buffer[..length], expected);
+        }
+
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn rejects_nonexistent_file() {
+        let path = temp_file("nonexistent", b"");
+        fs::remove_file(&path).unwrap();
+        let config = default_config("127.0.0.1:53364".parse().unwrap());
+
+        assert_eq!(
+            Attacker::file_buffer(&config, &path)
+                .expect_err("Reads a nonexistent file correctly")
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn correctly_constructs_attacker() {
+        // Specify any valid-formatted addresses, this isn't essential
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.sender = vec!["127.0.0.1:56978".parse().unwrap()];
+
+        // Setup our testing attacker with the previous receiver address
+        let attacker = setup_attacker(&config);
+
+        assert_eq!(attacker.args_config, &config);
+        assert_eq!(
+            attacker.sockets[0]
+                .as_udp()
+                .unwrap()
+                .write_timeout()
+                .expect("Cannot get the write timeout from the attacker"),
+            config.send_timeout
+        );
+        assert_eq!(
+            attacker.sockets[0]
+                .as_udp()
+                .unwrap()
+                .local_addr()
+                .expect("Cannot get the attacking socket local address"),
+            config.sender[0]
+        );
+        assert_eq!(attacker.buffer.len(), config.packet_length());
+    }
+
+    #[test]
+    fn sets_socket_options() {
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.ttl = Some(3);
+        config.broadcast = true;
+
+        assert!(setup_attacker(&config).sockets[0]
+            .as_udp()
+            .unwrap()
+            .broadcast()
+            .unwrap());
+        assert_eq!(
+            setup_attacker(&config).sockets[0]
+                .as_udp()
+                .unwrap()
+                .ttl()
+                .expect("Cannot get the attacking socket TTL"),
+            3
+        );
+    }
+
+    #[test]
+    fn sets_multicast_options() {
+        let mut config = default_config("239.1.2.3:1280".parse().unwrap());
+        config.multicast_ttl = Some(4);
+        config.multicast_loop = Some(false);
+
+        let attacker = setup_attacker(&config);
+        assert_eq!(
+            attacker.sockets[0]
+                .as_udp()
+                .unwrap()
+                .multicast_ttl_v4()
+                .unwrap(),
+            4
+        );
+        assert!(!attacker.sockets[0]
+            .as_udp()
+            .unwrap()
+            .multicast_loop_v4()
+            .unwrap());
+    }
+
+    #[test]
+    fn sends_all_packets() {
+        // Assign a very low required packets count to prevent our
+        // lovely Travis CI and your computer for a shameful breaking
+        let required_packets = NonZeroUsize::new(25).unwrap();
+
+        // Setup the testing server and modify the default config
+        let server = setup_server();
+        let mut config = default_config(
+            server
+                .local_addr()
+                .expect("Cannot get the testing server local address"),
+        );
+        config.packets = required_packets;
+
+        // Check that our attacker has successfully sent all the packets
+        assert_eq!(
+            setup_attacker(&config)
+                .attack()
+                .expect("An error occurred during the attack")
+                .packets_sent(),
+            required_packets.get() as u64
+        );
+    }
+
+    #[test]
+    fn stops_on_first_end_condition() {
+        let server = setup_server();
+        let mut config = default_config(
+            server
+                .local_addr()
+                .expect("Cannot get the testing server local address"),
+        );
+        config.wait = Duration::from_secs(0);
+
+        // A small packets count must stop the attack before the duration
+        config.packets = NonZeroUsize::new(10).unwrap();
+        config.duration = Some(Duration::from_secs(60));
+        assert_eq!(
+            setup_attacker(&config)
+                .attack()
+                .expect("An error occurred during the attack")
+                .packets_sent(),
+            10
+        );
+
+        // A short duration must stop the attack before the packets count
+        config.packets = NonZeroUsize::new(usize::MAX).unwrap();
+        config.duration = Some(Duration::from_millis(300));
+        config.send_periodicity = Duration::from_millis(50);
+        let summary = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert!(summary.packets_sent() < u64::MAX);
+        assert!(summary.elapsed() >= Duration::from_millis(300));
+
+        // Without a time limit, only the packets count stops the attack
+        config.packets = NonZeroUsize::new(5).unwrap();
+        config.duration = None;
+        assert_eq!(
+            setup_attacker(&config)
+                .attack()
+                .expect("An error occurred during the attack")
+                .packets_sent(),
+            5
+        );
+    }
+
+    #[test]
+    fn stops_at_deadline_despite_long_periodicity() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(16);
+        config.packets = NonZeroUsize::new(5).unwrap();
+        config.duration = Some(Duration::from_millis(200));
+        config.send_periodicity = Duration::from_secs(10);
+
+        // The attack mustn't wait for the next packet scheduled after the deadline
+        let initial_time = Instant::now();
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert!(initial_time.elapsed() < Duration::from_secs(2));
+        assert!(statistics.elapsed() >= Duration::from_millis(200));
+        assert_eq!(statistics.packets_sent(), 1);
+    }
+
+    #[test]
+    fn honors_receiver_weights() {
+        let first: SocketAddr = "10.0.0.1:80".parse().unwrap();
+        let second: SocketAddr = "10.0.0.2:80".parse().unwrap();
+        let third: SocketAddr = "10.0.0.3:80".parse().unwrap();
+
+        let config = [(7, first), (3, second)];
+        let mut receivers = WeightedReceivers::new(&config);
+        let picked: Vec<SocketAddr> = (0..10_000).map(|_| *receivers.next()).collect();
+        assert_eq!(
+            picked.iter().filter(|&&receiver| receiver == first).count(),
+            7000
+        );
+        assert_eq!(
+            picked
+                .iter()
+                .filter(|&&receiver| receiver == second)
+                .count(),
+            3000
+        );
+
+        // A heavier receiver must not get all of its packets in a row
+        assert!(picked[..10].contains(&second));
+
+        // Equal weights must be simply cycled
+        let config = [(1, first), (1, second), (1, third)];
+        let mut receivers = WeightedReceivers::new(&config);
+        let picked: Vec<SocketAddr> = (0..6).map(|_| *receivers.next()).collect();
+        assert_eq!(picked, vec![first, second, third, first, second, third]);
+    }
+
+    #[test]
+    fn distributes_packets_across_receivers() {
+        let servers = vec![setup_server(), setup_server(), setup_server()];
+        let mut config = default_config(servers[0].local_addr().unwrap());
+        config.receiver = servers
+            .iter()
+            .map(|server| (1, server.local_addr().unwrap()))
+            .collect();
+        config.wait = Duration::from_secs(0);
+        config.length = Some(64);
+        config.packets = NonZeroUsize::new(6).unwrap();
+
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        // Each of the receivers must get exactly two packets
+        let mut buffer = [0; 64];
+        for server in &servers {
+            server
+                .set_read_timeout(Some(Duration::from_secs(1)))
+                .unwrap();
+            for _ in 0..2 {
+                assert_eq!(server.recv(&mut buffer).expect("A packet was lost"), 64);
+            }
+        }
+    }
+
+    #[test]
+    fn embeds_sequence_numbers() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(16);
+        config.packets = NonZeroUsize::new(3).unwrap();
+        config.sequence = true;
+
+        let mut attacker = setup_attacker(&config);
+        let payload = attacker.buffer[SEQUENCE_NUMBER_LENGTH..].to_vec();
+        attacker
+            .attack()
+            .expect("An error occurred during the attack");
+
+        server
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let mut buffer = [0; 16];
+        for number in 0..3u64 {
+            assert_eq!(server.recv(&mut buffer).expect("A packet was lost"), 16);
+            assert_eq!(buffer[..SEQUENCE_NUMBER_LENGTH], number.to_be_bytes());
+
+            // The rest of the packet is left untouched
+            assert_eq!(buffer[SEQUENCE_NUMBER_LENGTH..], payload[..]);
+        }
+
+        // The numbers must stay unique across all the threads
+        config.threads = NonZeroUsize::new(2).unwrap();
+        let attackers = Attacker::group_from_args_config(&config).unwrap();
+        assert!(Arc::ptr_eq(&attackers[0].sequence, &attackers[1].sequence));
+    }
+
+    #[test]
+    fn sends_through_connected_socket() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(32);
+        config.packets = NonZeroUsize::new(2).unwrap();
+        config.connect = true;
+
+        let mut attacker = setup_attacker(&config);
+        assert_eq!(
+            attacker.sockets[0].as_udp().unwrap().peer_addr().unwrap(),
+            server.local_addr().unwrap()
+        );
+        attacker
+            .attack()
+            .expect("An error occurred during the attack");
+
+        server
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let mut buffer = [0; 32];
+        for _ in 0..2 {
+            assert_eq!(server.recv(&mut buffer).expect("A packet was lost"), 32);
+        }
+    }
+
+    #[test]
+    fn renders_template() {
+        let template = parse_template("n={seq},t={timestamp}").unwrap();
+
+        let mut buffer = [0xff; 16];
+        render_template(&template, 7, 1234, &mut buffer);
+        assert_eq!(&buffer, b"n=7,t=1234\0\0\0\0\0\0");
+
+        // The template is truncated to the packet length
+        let mut buffer = [0xff; 6];
+        render_template(&template, 42, 1234, &mut buffer);
+        assert_eq!(&buffer, b"n=42,t");
+    }
+
+    #[test]
+    fn substitutes_template() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(32);
+        config.packets = NonZeroUsize::new(3).unwrap();
+        config.template = Some(String::from("GET /ping?n={seq}&t={timestamp}"));
+
+        let before = unix_millis();
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        server
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let mut buffer = [0; 32];
+        for number in 0..3 {
+            assert_eq!(server.recv(&mut buffer).expect("A packet was lost"), 32);
+
+            let packet = String::from_utf8_lossy(&buffer);
+            let prefix = format!("GET /ping?n={}&t=", number);
+            assert!(packet.starts_with(&prefix), "{}", packet);
+
+            // The rest of the packet is padded with zeros
+            let timestamp: u128 = packet[prefix.len()..]
+                .trim_end_matches('\0')
+                .parse()
+                .expect("The timestamp isn't substituted");
+            assert!(timestamp >= before && timestamp <= unix_millis());
+        }
+    }
+
+    #[test]
+    fn accounts_partial_batches() {
+        let receiver = "127.0.0.1:1280".parse().unwrap();
+        let batch = [(100, &receiver), (200, &receiver), (300, &receiver)];
+
+        assert_eq!(sent_bytes(&batch, 3), 600);
+        assert_eq!(sent_bytes(&batch, 2), 300);
+        assert_eq!(sent_bytes(&batch, 0), 0);
+
+        // An IPv4 socket cannot send the second packet, but it sends the third
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config
+            .receiver
+            .insert(1, (1, "[::1]:1280".parse().unwrap()));
+        config.wait = Duration::from_secs(0);
+        config.batch_size = 3;
+        config.packets = NonZeroUsize::new(3).unwrap();
+        config.length = Some(64);
+        config.allowed_errors = 1;
+
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        // Only the failed packets are counted, not the ones after them
+        let (sent, failed) = (
+            statistics.receivers()[&server.local_addr().unwrap()],
+            statistics.receivers()[&"[::1]:1280".parse().unwrap()],
+        );
+        assert_eq!(statistics.packets_sent(), 3);
+        assert_eq!(sent.packets_sent(), 3);
+        assert_eq!(sent.packets_failed(), 0);
+        assert_eq!(failed.packets_sent(), 0);
+        assert_eq!(statistics.packets_failed(), failed.packets_failed());
+    }
+
+    #[test]
+    fn sends_packets_in_batches() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(16);
+        config.packets = NonZeroUsize::new(10).unwrap();
+        config.batch_size = 4;
+        config.sequence = true;
+
+        // The last batch is shortened to the remaining two packets
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.packets_sent(), 10);
+        assert_eq!(statistics.bytes_sent(), 160);
+
+        server
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let mut buffer = [0; 16];
+        for number in 0..10u64 {
+            assert_eq!(server.recv(&mut buffer).expect("A packet was lost"), 16);
+            assert_eq!(buffer[..SEQUENCE_NUMBER_LENGTH], number.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn reuses_static_buffer() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(64);
+        config.packets = NonZeroUsize::new(8).unwrap();
+        config.batch_size = 4;
+
+        // A static payload is sent from the same buffer without any copies
+        let mut attacker = setup_attacker(&config);
+        let (pointer, payload) = (attacker.buffer.as_ptr(), attacker.buffer.clone());
+        attacker
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(attacker.buffer.as_ptr(), pointer);
+        assert_eq!(attacker.buffer, payload);
+        assert!(attacker.batch.is_empty());
+
+        server
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let mut buffer = [0; 64];
+        for _ in 0..8 {
+            assert_eq!(server.recv(&mut buffer).expect("A packet was lost"), 64);
+            assert_eq!(buffer[..], payload[..]);
+        }
+
+        // The varying payloads are copied into the batch buffer instead
+        config.random_payload = true;
+        let mut attacker = setup_attacker(&config);
+        let pointer = attacker.buffer.as_ptr();
+        attacker
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(attacker.buffer.as_ptr(), pointer);
+        assert_eq!(attacker.batch.len(), 4 * 64);
+    }
+
+    #[test]
+    fn chooses_progress_style() {
+        let mut config = default_config("127.0.0.1:1280".parse().unwrap());
+        assert_eq!(progress_style(&config, true), ProgressStyle::Hidden);
+
+        config.report_interval = Duration::from_millis(500);
+        assert_eq!(progress_style(&config, true), ProgressStyle::Lines);
+        assert_eq!(progress_style(&config, false), ProgressStyle::Lines);
+
+        // The status line is drawn only on a terminal
+        config.status_line = true;
+        assert_eq!(progress_style(&config, true), ProgressStyle::StatusLine);
+        assert_eq!(progress_style(&config, false), ProgressStyle::Lines);
+        assert_eq!(report_interval(&config), Duration::from_millis(500));
+
+        // The status line is redrawn each second by default
+        config.report_interval = Duration::from_secs(0);
+        assert_eq!(progress_style(&config, true), ProgressStyle::StatusLine);
+        assert_eq!(progress_style(&config, false), ProgressStyle::Lines);
+        assert_eq!(report_interval(&config), Duration::from_secs(1));
+
+        // The quiet mode hides the progress anyway
+        config.quiet = true;
+        assert_eq!(progress_style(&config, true), ProgressStyle::Hidden);
+        assert_eq!(progress_style(&config, false), ProgressStyle::Hidden);
+    }
+
+    #[test]
+    fn keeps_counters_after_recreating_sockets() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(16);
+        config.packets = NonZeroUsize::new(20).unwrap();
+        config.send_periodicity = Duration::from_millis(5);
+        config.recreate_every = Some(Duration::from_millis(20));
+        config.sequence = true;
+
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.packets_sent(), 20);
+
+        server
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let mut buffer = [0; 16];
+        let mut ports = Vec::new();
+        for number in 0..20u64 {
+            let (length, sender) = server.recv_from(&mut buffer).expect("A packet was lost");
+            assert_eq!(length, 16);
+            assert_eq!(buffer[..SEQUENCE_NUMBER_LENGTH], number.to_be_bytes());
+            ports.push(sender.port());
+        }
+
+        // Each recreated socket is bound to a new ephemeral port
+        ports.dedup();
+        assert!(ports.len() > 1);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn sends_to_unix_socket() {
+        use std::os::unix::net::UnixDatagram;
+
+        let path = std::env::temp_dir().join(format!("anevicon-{}-attack", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let server = UnixDatagram::bind(&path).expect("Cannot setup the testing server");
+
+        let config = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--unix-socket",
+            path.to_str().unwrap(),
+            "--packets",
+            "3",
+            "--length",
+            "16",
+            "--wait",
+            "0s",
+        ])
+        .expect("Cannot parse the arguments");
+        assert!(config.receiver.is_empty());
+
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.packets_sent(), 3);
+
+        let mut buffer = [0; 32];
+        for _ in 0..3 {
+            assert_eq!(server.recv(&mut buffer).expect("A packet was lost"), 16);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn counts_unreachable_destinations() {
+        // Nothing listens on the port of a closed socket
+        let receiver = setup_server().local_addr().unwrap();
+        let mut config = default_config(receiver);
+        config.wait = Duration::from_secs(0);
+        config.length = Some(16);
+        config.packets = NonZeroUsize::new(10).unwrap();
+        config.send_periodicity = Duration::from_millis(5);
+        config.allowed_errors = 10;
+        config.connect = true;
+
+        let (summary, result) = setup_attacker(&config).send_packets(&AtomicBool::new(false));
+        result.expect("An error occurred during the attack");
+        assert!(summary.packets_unreachable() > 0);
+        assert_eq!(summary.packets_failed(), 0);
+        // The refused packets are never counted as the sent ones
+        assert_eq!(summary.packets_sent(), 10);
+    }
+
+    #[test]
+    fn formats_verbose_errors() {
+        let error = io::Error::from_raw_os_error(libc::ECONNREFUSED);
+        assert_eq!(
+            verbose_error("127.0.0.1:1280", &error, 0),
+            format!(
+                "Sending to 127.0.0.1:1280 failed (ConnectionRefused): {}",
+                error
+            )
+        );
+        assert!(
+            verbose_error("127.0.0.1:1280", &error, 7).ends_with(" (7 more failures were skipped)")
+        );
+    }
+
+    #[test]
+    fn limits_event_rate() {
+        let millis = Duration::from_millis;
+        let mut limiter = RateLimiter::new(3, Duration::from_secs(1));
+
+        // Only the first events of a window are allowed
+        assert_eq!(limiter.allow(millis(0)), Some(0));
+        assert_eq!(limiter.allow(millis(10)), Some(0));
+        assert_eq!(limiter.allow(millis(20)), Some(0));
+        assert_eq!(limiter.allow(millis(30)), None);
+        assert_eq!(limiter.allow(millis(999)), None);
+
+        // The next window reports the skipped events once
+        assert_eq!(limiter.allow(millis(1000)), Some(2));
+        assert_eq!(limiter.allow(millis(1001)), Some(0));
+        assert_eq!(limiter.allow(millis(1002)), Some(0));
+        assert_eq!(limiter.allow(millis(1003)), None);
+
+        // A window starts with its first event, not on a fixed grid
+        assert_eq!(limiter.allow(millis(5500)), Some(1));
+        assert_eq!(limiter.allow(millis(6499)), Some(0));
+        assert_eq!(limiter.allow(millis(6499)), Some(0));
+        assert_eq!(limiter.allow(millis(6499)), None);
+        assert_eq!(limiter.allow(millis(6500)), Some(1));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn pins_thread_to_core() {
+        // The affinity is changed in a separate thread, so the other tests aren't affected
+        thread::spawn(|| {
+            pin_to_core(0).expect("Cannot pin a thread to the first core");
+            assert_eq!(unsafe { libc::sched_getcpu() }, 0);
+
+            assert!(pin_to_core(libc::CPU_SETSIZE as usize * 2).is_err());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn computes_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414f_a339
+        );
+    }
+
+    #[test]
+    fn writes_magic_header() {
+        let mut packet = *b"\x00\x00\x00\x00\x00\x00\x00\x00123456789";
+        write_magic_header(0xa11c_e5ed, &mut packet);
+
+        assert_eq!(packet[..4], [0xa1, 0x1c, 0xe5, 0xed]);
+        assert_eq!(packet[4..8], [0xcb, 0xf4, 0x39, 0x26]);
+        assert_eq!(&packet[8..], b"123456789");
+
+        // An empty rest of the packet has the zero checksum
+        let mut packet = [0xff; 8];
+        write_magic_header(1, &mut packet);
+        assert_eq!(packet, [0, 0, 0, 1, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn sends_magic_header() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.packets = NonZeroUsize::new(4).unwrap();
+        config.length_range = Some((
+            NonZeroUsize::new(8).unwrap(),
+            NonZeroUsize::new(64).unwrap(),
+        ));
+        config.batch_size = 2;
+        config.magic = Some(0xa11ce);
+
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        let mut buffer = [0; 128];
+        for _ in 0..4 {
+            let length = server.recv(&mut buffer).expect("A packet was lost");
+            assert!((16..=72).contains(&length));
+            assert_eq!(buffer[..4], 0xa11ce_u32.to_be_bytes());
+            assert_eq!(buffer[4..8], crc32(&buffer[8..length]).to_be_bytes());
+        }
+
+        // The header is prepended to the payload, which is sent entirely
+        config.length_range = None;
+        config.batch_size = 1;
+        config.packets = NonZeroUsize::new(1).unwrap();
+        config.payload_hex = Some(String::from("313233343536373839"));
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+
+        let length = server.recv(&mut buffer).expect("A packet was lost");
+        assert_eq!(
+            buffer[..length],
+            *b"\x00\x0a\x11\xce\xcb\xf4\x39\x26123456789"
+        );
+    }
+
+    #[test]
+    fn reports_ephemeral_sender_port() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.sender = vec!["127.0.0.1:0".parse().unwrap()];
+
+        let addresses = setup_attacker(&config).local_addrs();
+        assert_eq!(addresses.len(), 1);
+        assert_eq!(addresses[0].ip(), Ipv4Addr::LOCALHOST);
+        assert_ne!(addresses[0].port(), 0);
+    }
+
+    #[test]
+    fn formats_local_addrs() {
+        let addresses: Vec<SocketAddr> = (1..=10)
+            .map(|port| SocketAddr::from(([127, 0, 0, 1], port)))
+            .collect();
+
+        assert_eq!(format_addrs(&addresses[..2]), "127.0.0.1:1, 127.0.0.1:2");
+        assert!(format_addrs(&addresses).ends_with("127.0.0.1:8 and 2 more"));
+    }
+
+    #[test]
+    fn detects_failed_probe() {
+        let mut summary = AttackSummary::new();
+        summary.update_failed(15);
+        assert!(!probe_failed(&summary, 16));
+
+        summary.update_unreachable(1);
+        assert!(probe_failed(&summary, 16));
+        assert!(!probe_failed(&summary, 0));
+
+        // A single sent packet proves the destination reachable
+        summary.update(64, 1);
+        assert!(!probe_failed(&summary, 16));
+    }
+
+    #[test]
+    fn aborts_after_failed_probe() {
+        let mut config = default_config("[::1]:53365".parse().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.allowed_errors = 100;
+        config.probe_count = 16;
+
+        // An IPv4 socket cannot send anything to an IPv6 receiver
+        let error = setup_attacker(&config)
+            .attack()
+            .expect_err("The attack must be aborted");
+        assert!(error.to_string().contains("none of the first 16 packets"));
+
+        // Without probing, only the allowed errors are exceeded at last
+        config.probe_count = 0;
+        let error = setup_attacker(&config)
+            .attack()
+            .expect_err("The attack must be aborted");
+        assert!(!error.to_string().contains("none of the first"));
+    }
+
+    #[test]
+    fn reports_stop_reasons() {
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.packets = NonZeroUsize::new(10).unwrap();
+        let interrupted = AtomicBool::new(false);
+
+        let attacker = setup_attacker(&config);
+        let mut summary = AttackSummary::new();
+        assert_eq!(attacker.stop_reason(&summary, &interrupted), None);
+        summary.update(640, 10);
+        assert_eq!(
+            attacker.stop_reason(&summary, &interrupted),
+            Some(StopReason::Packets)
+        );
+
+        // The interruption wins over all the limits
+        interrupted.store(true, Ordering::SeqCst);
+        assert_eq!(
+            attacker.stop_reason(&summary, &interrupted),
+            Some(StopReason::Interrupted)
+        );
+        interrupted.store(false, Ordering::SeqCst);
+
+        config.duration = Some(Duration::from_secs(0));
+        assert_eq!(
+            setup_attacker(&config).stop_reason(&AttackSummary::new(), &interrupted),
+            Some(StopReason::Duration)
+        );
+
+        config.duration = None;
+        config.max_runtime = Some(Duration::from_secs(0));
+        assert_eq!(
+            setup_attacker(&config).stop_reason(&AttackSummary::new(), &interrupted),
+            Some(StopReason::MaxRuntime)
+        );
+    }
+
+    #[test]
+    fn stops_unlimited_attack_after_max_runtime() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.duration = None;
+        config.packets = NonZeroUsize::new(usize::MAX).unwrap();
+        config.send_periodicity = Duration::from_millis(10);
+        config.max_runtime = Some(Duration::from_millis(200));
+
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.stop_reason(), Some(StopReason::MaxRuntime));
+        assert!(statistics.elapsed() >= Duration::from_millis(200));
+        assert!(statistics.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn records_stop_reason() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.packets = NonZeroUsize::new(5).unwrap();
+
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.stop_reason(), Some(StopReason::Packets));
+
+        config.packets = NonZeroUsize::new(1_000_000).unwrap();
+        config.byte_limit = Some(64 * 1024);
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.stop_reason(), Some(StopReason::Bytes));
+    }
+
+    #[test]
+    fn reports_statistics_on_error() {
+        let mut config = default_config("[::1]:53365".parse().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.allowed_errors = 2;
+        config.probe_count = 0;
+
+        let error = setup_attacker(&config)
+            .attack()
+            .expect_err("The attack must be aborted");
+        assert_eq!(error.statistics.packets_sent(), 0);
+        assert_eq!(error.statistics.packets_failed(), 3);
+        assert_eq!(error.statistics.stop_reason(), None);
+        assert_eq!(error.to_string(), error.error.to_string());
+
+        let mut attackers = Attacker::group_from_args_config(&config).unwrap();
+        let error = attack_in_threads(&mut attackers, &AtomicBool::new(false))
+            .expect_err("The attack must be aborted");
+        assert_eq!(error.statistics.packets_failed(), 3);
+    }
+
+    #[test]
+    fn computes_burst_phase() {
+        let (on, off) = (Duration::from_secs(2), Duration::from_secs(3));
+        let phase = |millis| burst_phase(Duration::from_millis(millis), on, off);
+
+        assert_eq!(phase(0), BurstPhase::On);
+        assert_eq!(phase(1999), BurstPhase::On);
+        assert_eq!(phase(2000), BurstPhase::Off(Duration::from_secs(3)));
+        assert_eq!(phase(4500), BurstPhase::Off(Duration::from_millis(500)));
+        assert_eq!(phase(5000), BurstPhase::On);
+        assert_eq!(phase(12_250), BurstPhase::Off(Duration::from_millis(2750)));
+
+        // Without idling, the attack is always sending
+        assert_eq!(
+            burst_phase(Duration::from_secs(7), on, Duration::from_secs(0)),
+            BurstPhase::On
+        );
+    }
+
+    #[test]
+    fn idles_between_bursts() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.packets = NonZeroUsize::new(1_000_000).unwrap();
+        config.duration = Some(Duration::from_millis(400));
+        config.send_periodicity = Duration::from_millis(10);
+        config.burst_on = Some(Duration::from_millis(100));
+        config.burst_off = Some(Duration::from_millis(100));
+
+        // Only two bursts of about 10 packets each fit into the duration
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert!(statistics.packets_sent() <= 24, "{}", statistics);
+        assert!(statistics.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn records_pacing_deviations() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.packets = NonZeroUsize::new(10).unwrap();
+        config.send_periodicity = Duration::from_millis(5);
+
+        // The first sending call isn't planned, so it has no deviation
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.pacing().count(), 9);
+        assert!(statistics.pacing().max() >= statistics.pacing().mean());
+    }
+
+    #[test]
+    fn sends_zero_length_packets_if_unchecked() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.packets = NonZeroUsize::new(3).unwrap();
+        config.length = Some(0);
+        config.unchecked_length = true;
+
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.packets_sent(), 3);
+        assert_eq!(server.recv(&mut [0; 16]).unwrap(), 0);
+    }
+
+    #[test]
+    fn attributes_packets_to_receivers() {
+        let server = setup_server();
+        let failing = "[::1]:53364".parse().unwrap();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.receiver.push((1, failing));
+        config.sender = vec!["127.0.0.1:0".parse().unwrap()];
+        config.wait = Duration::from_secs(0);
+        config.length = Some(64);
+        config.packets = NonZeroUsize::new(5).unwrap();
+        config.allowed_errors = 1;
+
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        let receivers = statistics.receivers();
+        assert_eq!(receivers.len(), 2);
+
+        let working = &receivers[&server.local_addr().unwrap()];
+        assert_eq!(working.packets_sent(), 5);
+        assert_eq!(working.packets_failed(), 0);
+        assert_eq!(working.bytes_sent(), 5 * 64);
+        assert_eq!(receivers[&failing].packets_sent(), 0);
+        assert_eq!(receivers[&failing].packets_failed(), 4);
+
+        // A single receiver is described by the totals alone
+        config.receiver.pop();
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert!(statistics.receivers().is_empty());
+    }
+
+    #[test]
+    fn ignores_pacing_without_delay() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.no_delay = true;
+        config.wait = Duration::from_secs(60);
+        config.send_periodicity = Duration::from_secs(60);
+        config.burst_on = Some(Duration::from_millis(1));
+        config.burst_off = Some(Duration::from_secs(60));
+        config.packets = NonZeroUsize::new(20).unwrap();
+
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert_eq!(statistics.packets_sent(), 20);
+        assert_eq!(statistics.stop_reason(), Some(StopReason::Packets));
+        assert!(statistics.elapsed() < Duration::from_secs(5));
+        assert_eq!(statistics.pacing().count(), 0);
+
+        // A rate is ignored as well, but the duration still holds
+        config.send_periodicity = Duration::from_secs(0);
+        config.rate = NonZeroUsize::new(1);
+        config.packets = NonZeroUsize::new(usize::MAX).unwrap();
+        config.duration = Some(Duration::from_millis(200));
+        let statistics = setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        assert!(statistics.packets_sent() > 1);
+        assert_eq!(statistics.stop_reason(), Some(StopReason::Duration));
+    }
+
+    #[test]
+    fn sends_from_several_senders() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.sender = vec![
+            "127.0.0.1:0".parse().unwrap(),
+            "127.0.0.2:0".parse().unwrap(),
+        ];
+        config.wait = Duration::from_secs(0);
+        config.length = Some(16);
+        config.packets = NonZeroUsize::new(4).unwrap();
+
+        let mut attacker = setup_attacker(&config);
+        let addrs = attacker.local_addrs();
+        assert_eq!(addrs.len(), 2);
+        assert_eq!(addrs[0].ip(), config.sender[0].ip());
+        assert_eq!(addrs[1].ip(), config.sender[1].ip());
+
+        attacker
+            .attack()
+            .expect("An error occurred during the attack");
+        let mut sources = Vec::new();
+        for _ in 0..4 {
+            sources.push(server.recv_from(&mut [0; 16]).unwrap().1.ip());
+        }
+        sources.sort();
+        assert_eq!(
+            sources,
+            [
+                config.sender[0].ip(),
+                config.sender[0].ip(),
+                config.sender[1].ip(),
+                config.sender[1].ip()
+            ]
+        );
+
+        // The threads take the senders in turn, sharing them if there are more threads
+        assert_eq!(sender_addresses(&config, 0, 2), [config.sender[0]]);
+        assert_eq!(sender_addresses(&config, 1, 2), [config.sender[1]]);
+        assert_eq!(sender_addresses(&config, 2, 3), [config.sender[0]]);
+        assert_eq!(sender_addresses(&config, 0, 1), config.sender);
+
+        config.source_ports = Some((4000, 4001));
+        assert_eq!(
+            sender_addresses(&config, 1, 2),
+            [
+                "127.0.0.1:4001".parse::<SocketAddr>().unwrap(),
+                "127.0.0.2:4001".parse().unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn fills_payload_with_byte() {
+        let server = setup_server();
+        let mut config = default_config(server.local_addr().unwrap());
+        config.wait = Duration::from_secs(0);
+        config.length = Some(32);
+        config.fill_byte = Some(0xab);
+        config.packets = NonZeroUsize::new(1).unwrap();
+
+        setup_attacker(&config)
+            .attack()
+            .expect("An error occurred during the attack");
+        let mut buffer = [0; 64];
+        let length = server.recv(&mut buffer).unwrap();
+        assert_eq!(&buffer[..length], &[0xab; 32][..]);
+    }
+
+    #[test]
+    fn seeds_thread_rngs() {
+        let stream = |seed: u64, thread: usize| {
+            let mut buffer = [0; 64];
+            thread_rng_for(seed, thread).fill_bytes(&mut buffer);
+            buffer.to_vec()
+        };
+
+        assert_eq!(stream(4298, 0), stream(4298, 0));
+        assert_eq!(stream(4298, 1), stream(4298, 1));
+        assert_ne!(stream(4298, 0), stream(4298, 1));
+        assert_ne!(stream(4298, 1), stream(4299, 0));
+
+        // The first thread generates the same packets as a single one
+        let mut expected = [0; 64];
+        StdRng::seed_from_u64(4298).fill_bytes(&mut expected);
+        assert_eq!(stream(4298, 0), expected.to_vec());
+
+        let mut config = default_config("127.0.0.1:53364".parse().unwrap());
+        config.seed = Some(4298);
+        config.threads = NonZeroUsize::new(2).unwrap();
+        let group = Attacker::group_from_args_config(&config).unwrap();
+        assert_ne!(group[0].buffer, group[1].buffer);
+        assert_eq!(
+            group[1].buffer,
+            Attacker::group_from_args_config(&config).unwrap()[1].buffer
+        );
+    }
+
+    #[test]
+    fn estimates_packets() {
+        let secs = Duration::from_secs;
+
+        assert_eq!(estimate_packets(100.0, secs(0), secs(10)), 1000.0);
+        assert_eq!(estimate_packets(0.5, secs(0), secs(10)), 5.0);
+        assert_eq!(estimate_packets(100.0, secs(0), secs(0)), 0.0);
+
+        // The ramp-up sends a half of the packets of the full rate
+        assert_eq!(estimate_packets(100.0, secs(10), secs(10)), 500.0);
+        assert_eq!(estimate_packets(100.0, secs(10), secs(5)), 125.0);
+        assert_eq!(estimate_packets(100.0, secs(10), secs(20)), 1500.0);
+
+        assert_eq!(
+            burst_sending_time(secs(25), secs(2), secs(3)),
+            secs(10),
+            "'burst_sending_time' counts the whole bursts incorrectly"
+        );
+        assert_eq!(burst_sending_time(secs(26), secs(2), secs(3)), secs(11));
+        assert_eq!(burst_sending_time(secs(29), secs(2), secs(3)), secs(12));
+    }
+
+    #[test]
+    fn estimates_expected_packets() {
+        let mut config = default_config("127.0.0.1:53365".parse().unwrap());
+        config.length = Some(100);
+        let expected = |config: &ArgsConfig| {
+            let attacker = Attacker::from_args_config(config).unwrap();
+            expected_packets(&attacker, config.threads.get())
+        };
+
+        // Neither the pacing nor the limits bound the attack
+        assert_eq!(expected(&config), None);
+        config.duration = Some(Duration::from_secs(10));
+        assert_eq!(expected(&config), None);
+
+        config.rate = NonZeroUsize::new(50);
+        assert_eq!(expected(&config), Some(500));
+        config.rate = None;
+        config.bandwidth = NonZeroUsize::new(2000);
+        assert_eq!(expected(&config), Some(200));
+        config.bandwidth = None;
+        config.send_periodicity = Duration::from_millis(100);
+        config.threads = NonZeroUsize::new(2).unwrap();
+        assert_eq!(expected(&config), Some(200));
+
+        // The least of the limits wins
+        config.packets = NonZeroUsize::new(150).unwrap();
+        assert_eq!(expected(&config), Some(150));
+        config.byte_limit = Some(5050);
+        assert_eq!(expected(&config), Some(51));
+    }
+
+    #[test]
+    fn checks_datagram_size_of_files() {
+        let path = temp_file("datagram", &[b'x'; 65500]);
+        let mut config = default_config("127.0.0.1:53366".parse().unwrap());
+        config.send_file = vec![path.clone()];
+        assert!(Attacker::from_args_config(&config).is_ok());
+
+        // The file fits on its own, but not along with the magic header
+        config.magic = Some(1);
+        match Attacker::from_args_config(&config) {
+            Err(error) => assert_eq!(error.kind(), io::ErrorKind::InvalidData),
+            Ok(_) => panic!("The oversized datagram is accepted"),
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_non_local_sender() {
+        // 203.0.113.0/24 (TEST-NET-3) is reserved for documentation (RFC 5737)
+        let mut config = default_config("127.0.0.1:9000".parse().unwrap());
+        config.sender = vec!["203.0.113.77:5000".parse().unwrap()];
+
+        // The configuration itself is valid, only the socket cannot be bound
+        assert_eq!(config.validate(), Ok(()));
+        match Attacker::from_args_config(&config) {
+            Err(error) => assert!(error
+                .to_string()
+                .starts_with("Cannot bind the sender 203.0.113.77:5000 (")),
+            Ok(_) => panic!("The non-local sender is bound"),
+        }
+
+        // Every sender is attributed its own failure
+        config.sender = vec![
+            "127.0.0.1:0".parse().unwrap(),
+            "203.0.113.77:0".parse().unwrap(),
+        ];
+        match Attacker::from_args_config(&config) {
+            Err(error) => assert!(error.to_string().contains("203.0.113.77:0")),
+            Ok(_) => panic!("The non-local sender is bound"),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reports_priority_when_binding() {
+        let mut config = default_config("127.0.0.1:53367".parse().unwrap());
+        config.priority = Some(7);
+        assert_eq!(config.validate(), Ok(()));
+
+        // The priorities above 6 depend on the capabilities of the process
+        let probe = UdpSocket::bind("127.0.0.1:0").unwrap();
+        match (Attacker::from_args_config(&config), set_priority(&probe, 7)) {
+            (Ok(_), Ok(())) => (),
+            (Err(error), Err(_)) => assert!(error
+                .to_string()
+                .starts_with("Invalid socket priority: cannot set 7: ")),
+            (result, probe) => panic!("Unexpected results: {:?}, {:?}", result.err(), probe),
+        }
     }
 }
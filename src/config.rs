@@ -17,70 +17,356 @@
  * For more information see <https://github.com/Gymmasssorla/anevicon>.
  */
 
+use std::env;
 use std::error::Error;
+use std::ffi::OsString;
 use std::fmt::{self, Display, Formatter};
-use std::net::SocketAddr;
+use std::fs;
+use std::io::Read;
+use std::net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
 use std::num::{NonZeroUsize, ParseIntError};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::Duration;
 
-use humantime::{format_duration, parse_duration};
+use humantime::format_duration;
+use log::{warn, LevelFilter};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use structopt::clap::ArgMatches;
+
+use super::summary::{check_csv, prometheus_temp_path};
 use structopt::StructOpt;
 
-#[derive(Debug, Eq, PartialEq, StructOpt)]
+pub const MIN_PACKET_LENGTH: usize = 1;
+/// The largest UDP payload over IPv4: 65535 bytes minus the 20-byte IP
+/// header and the 8-byte UDP header. It fits into an IPv6 packet too.
+/// It is the default value of `--max-length`.
+pub const MAX_PACKET_LENGTH: usize = 65507;
+/// The largest UDP payload at all: 65535 bytes minus the 8-byte UDP
+/// header, which fits only into an IPv6 packet. Neither `--max-length`
+/// nor any length can exceed it.
+pub const ABSOLUTE_MAX_PACKET_LENGTH: usize = 65527;
+pub const DEFAULT_PACKET_LENGTH: usize = 65000;
+/// A length of the big-endian sequence number written by `--sequence`.
+pub const SEQUENCE_NUMBER_LENGTH: usize = 8;
+/// A length of the header written by `--magic`: the magic value and the
+/// CRC32 of the rest of the packet, both of four big-endian bytes.
+pub const MAGIC_HEADER_LENGTH: usize = 8;
+/// The largest count of packets sent by a single `sendmmsg` call
+/// (`UIO_MAXIOV` on Linux).
+pub const MAX_BATCH_SIZE: usize = 1024;
+
+/// The largest UDP payload which fits into a single IPv4 packet on a
+/// typical Ethernet link with the MTU of 1500 bytes.
+pub const TYPICAL_MTU_PAYLOAD: usize = 1472;
+
+/// The environment variables which the options omitted on the command
+/// line are taken from, along with the names of these options.
+const ENV_VARS: [(&str, &str); 5] = [
+    ("sender", "ANEVICON_SENDER"),
+    ("duration", "ANEVICON_DURATION"),
+    ("length", "ANEVICON_LENGTH"),
+    ("wait", "ANEVICON_WAIT"),
+    ("send_periodicity", "ANEVICON_SEND_PERIODICITY"),
+];
+
+/// A program working time, where `None` means an unlimited one. It is an
+/// alias so the argument parser passes the whole value to
+/// `parse_time_limit` instead of treating the option as an optional one.
+pub type TimeLimit = Option<Duration>;
+
+/// A list of CPU core indices. It is an alias for the same reason as
+/// `TimeLimit`, so `parse_cores` gets the whole comma-separated list.
+pub type CoreList = Vec<usize>;
+
+#[derive(Debug, PartialEq, StructOpt)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[structopt(
     author = "Temirkhan Myrzamadi <gymmasssorla@gmail.com>",
     about = "An UDP-based server stress-testing tool, written in Rust.",
-    after_help = "For more information see <https://github.com/Gymmasssorla/anevicon>.",
+    after_help = "A TIME-SPAN is specified in the humantime format (for example, '1h 30m' or \
+                  '500ms'), and a bare integer means seconds, so '30' is the same as '30s'.\n\n\
+                  The sender, duration, length, wait, and send-periodicity options can also be \
+                  specified by the ANEVICON_SENDER, ANEVICON_DURATION, ANEVICON_LENGTH, \
+                  ANEVICON_WAIT, and ANEVICON_SEND_PERIODICITY environment variables. They \
+                  override the configuration file, but not the command line. The RUST_LOG \
+                  environment variable overrides the logging level (for example, \
+                  RUST_LOG=info).\n\n\
+                  For more information see <https://github.com/Gymmasssorla/anevicon>.",
     set_term_width = 80
 )]
 pub struct ArgsConfig {
     /// A receiver of generated traffic, specified as an IP-address
-    /// and a port number, separated by the colon character.
+    /// (or a hostname) and a port number, separated by the colon
+    /// character. This option can be specified several times (or as a
+    /// comma-separated list), in which case packets are distributed
+    /// in turn. A receiver can be prefixed by its weight and a colon
+    /// (for example, 3:10.0.0.1:80), so it gets proportionally more
+    /// packets than the others, whose weight equals to one. An
+    /// IPv4-mapped IPv6 address (like [::ffff:10.0.0.1]:80) is sent to
+    /// as the IPv4 address it maps, so it's rejected along with an IPv6
+    /// sender.
     #[structopt(
         short = "r",
         long = "receiver",
         takes_value = true,
         value_name = "ADDRESS",
-        required = true
+        raw(
+            required_unless_one = r#"&["config_file", "receiver_cidr", "receivers_file", "unix_socket", "list_interfaces"]"#
+        ),
+        use_delimiter = true,
+        parse(try_from_str = "parse_weighted_receiver")
+    )]
+    pub receiver: Vec<(u32, SocketAddr)>,
+
+    /// A range of receivers, specified in the CIDR notation and
+    /// followed by a port number (for example, 10.0.0.0/29:80), so
+    /// packets are sent to every host address of the range. IPv6
+    /// ranges are enclosed in brackets, like [fd00::/120]:80. This
+    /// option can be specified several times, and a range can
+    /// contain at most 65536 addresses.
+    #[structopt(
+        long = "receiver-cidr",
+        takes_value = true,
+        value_name = "CIDR:PORT",
+        number_of_values = 1
+    )]
+    pub receiver_cidr: Vec<String>,
+
+    /// A file containing the receivers, one per line in the same format
+    /// as --receiver. Empty lines and everything after the '#' character
+    /// are ignored. The receivers of the file are merged with the ones
+    /// specified by --receiver.
+    #[structopt(
+        long = "receivers-file",
+        takes_value = true,
+        value_name = "PATH",
+        parse(from_os_str)
     )]
-    pub receiver: SocketAddr,
+    pub receivers_file: Option<PathBuf>,
 
     /// A sender of generated traffic, specified as an IP-address
-    /// and a port number, separated by the colon character.
+    /// (or a hostname) and a port number, separated by the colon
+    /// character. It must belong to the same address family as the
+    /// receivers, so IPv6 receivers are attacked from [::]:0 by
+    /// default. This option can be specified several times (or as a
+    /// comma-separated list) to spread the source addresses, in which
+    /// case a socket is bound to each of them and packets are sent from
+    /// them in turn.
     #[structopt(
         short = "s",
         long = "sender",
         takes_value = true,
         value_name = "ADDRESS",
-        default_value = "0.0.0.0:0"
+        default_value = "0.0.0.0:0",
+        use_delimiter = true,
+        parse(try_from_str = "parse_socket_addr")
+    )]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "serde_senders::deserialize")
+    )]
+    pub sender: Vec<SocketAddr>,
+
+    /// A range of source ports, specified as START:END. When
+    /// specified, packets are sent from each port of this inclusive
+    /// range in turn, instead of the port of the sender. With
+    /// several threads, the ports are split between them.
+    #[structopt(
+        long = "source-ports",
+        takes_value = true,
+        value_name = "START:END",
+        parse(try_from_str = "parse_port_range")
+    )]
+    pub source_ports: Option<(u16, u16)>,
+
+    /// A network interface to send packets through (SO_BINDTODEVICE),
+    /// specified by its name (for example, 'eth0'). This option is
+    /// supported only on Linux, and the program exits with an error
+    /// on the other platforms.
+    #[structopt(long = "interface", takes_value = true, value_name = "NAME")]
+    pub interface: Option<String>,
+
+    /// A UNIX domain datagram socket to send packets to, specified by
+    /// its path, instead of the network receivers. The receiver and the
+    /// sender aren't required then, and the network-only options are
+    /// rejected. This option is supported only on UNIX-like platforms.
+    #[structopt(
+        long = "unix-socket",
+        takes_value = true,
+        value_name = "PATH",
+        parse(from_os_str),
+        raw(
+            conflicts_with_all = r#"&["receiver", "receiver_cidr", "receivers_file", "source_ports", "interface", "broadcast", "connect", "dont_fragment", "ttl", "tos", "priority", "multicast_ttl", "multicast_loop", "send_buffer"]"#
+        )
     )]
-    pub sender: SocketAddr,
+    pub unix_socket: Option<PathBuf>,
 
     /// A program working time. The default value is too big, that
     /// is, an attack will be performed until you explicitly stop
-    /// the process.
+    /// the process. Zero means no time limit at all, so the attack
+    /// is stopped only by --packets or Ctrl+C.
     #[structopt(
         short = "d",
         long = "duration",
         takes_value = true,
         value_name = "TIME-SPAN",
         default_value = "64years 64hours 64secs",
-        parse(try_from_str = "parse_duration")
+        parse(try_from_str = "parse_time_limit")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_option_duration"))]
+    pub duration: TimeLimit,
+
+    /// A hard limit of the program working time, after which the
+    /// attack is stopped and the summary is printed regardless of the
+    /// other options. Unlike --duration, it cannot be unlimited, so it
+    /// can only shorten the attack. By default, there is no such limit.
+    #[structopt(
+        long = "max-runtime",
+        takes_value = true,
+        value_name = "TIME-SPAN",
+        parse(try_from_str = "parse_max_runtime")
     )]
-    pub duration: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "serde_option_duration"))]
+    pub max_runtime: Option<Duration>,
 
-    /// A size of each UDP-packet, specified in bytes. Note that
-    /// your system or a victim server might not be able to handle
-    /// the default value.
+    /// A size of each UDP-packet, specified in bytes with an optional
+    /// suffix: 'kb' or 'mb' (for example, '60kb'). Note that your
+    /// system or a victim server might not be able to handle the
+    /// default value, which equals to 65000 bytes.
     #[structopt(
         short = "l",
         long = "length",
         takes_value = true,
         value_name = "BYTES",
-        default_value = "65000",
-        parse(try_from_str = "parse_non_zero_usize")
+        parse(try_from_str = "parse_length")
+    )]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "serde_packet_length::deserialize")
+    )]
+    pub length: Option<usize>,
+
+    /// A range of packet lengths, specified as MIN:MAX in bytes. When
+    /// specified, the length of each UDP-packet is chosen randomly
+    /// from this inclusive range, uniformly unless another
+    /// --length-distribution is specified.
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "serde_length_range::deserialize")
+    )]
+    #[structopt(
+        long = "length-range",
+        takes_value = true,
+        value_name = "MIN:MAX",
+        raw(conflicts_with_all = r#"&["length", "send_file"]"#),
+        parse(try_from_str = "parse_length_range")
+    )]
+    pub length_range: Option<(NonZeroUsize, NonZeroUsize)>,
+
+    /// A distribution of the packet lengths chosen from --length-
+    /// range: 'uniform' or 'normal'. The normal lengths are centered
+    /// on the middle of the range, and six standard deviations span
+    /// the whole range, so the rare outliers are clamped to its
+    /// bounds.
+    #[structopt(
+        long = "length-distribution",
+        takes_value = true,
+        value_name = "DISTRIBUTION",
+        default_value = "uniform"
+    )]
+    pub length_distribution: LengthDistribution,
+
+    /// The smallest packet length accepted by the other options,
+    /// specified in the same way as --length. The payload of a file,
+    /// the standard input, or a hex string is checked as well.
+    #[structopt(
+        long = "min-length",
+        takes_value = true,
+        value_name = "BYTES",
+        default_value = "1",
+        parse(try_from_str = "parse_packet_length")
+    )]
+    pub min_packet_length: NonZeroUsize,
+
+    /// The largest packet length accepted by the other options,
+    /// specified in the same way as --length. The default value is the
+    /// largest UDP payload over IPv4, and it can be raised up to 65527
+    /// bytes for IPv6 links.
+    #[structopt(
+        long = "max-length",
+        takes_value = true,
+        value_name = "BYTES",
+        default_value = "65507",
+        parse(try_from_str = "parse_packet_length")
+    )]
+    pub max_packet_length: NonZeroUsize,
+
+    /// Don't check the packet lengths against --min-length and
+    /// --max-length, so that even zero-length or oversized packets are
+    /// passed to the system as specified. A length still cannot exceed
+    /// 65527 bytes. This is meant for experimenting with the system
+    /// behaviour, so expect the sending to fail in this mode.
+    #[structopt(long = "unchecked-length")]
+    pub unchecked_length: bool,
+
+    /// A file whose content will be sent as each UDP-packet instead
+    /// of random bytes. The packet length is derived from the file
+    /// size, so the file must fit into a single UDP-packet. This
+    /// option can be specified several times, in which case the files
+    /// are sent in turn, one per packet, each with its own length.
+    #[structopt(
+        long = "send-file",
+        takes_value = true,
+        value_name = "PATH",
+        number_of_values = 1,
+        parse(from_os_str)
+    )]
+    pub send_file: Vec<PathBuf>,
+
+    /// A hex string whose decoded bytes will be sent as each
+    /// UDP-packet instead of random bytes, optionally separated by
+    /// whitespaces or colons (for example, 'de:ad:be:ef'). The
+    /// packet length is derived from the decoded payload.
+    #[structopt(
+        long = "payload-hex",
+        takes_value = true,
+        value_name = "HEX",
+        raw(conflicts_with_all = r#"&["length", "length_range", "send_file", "random_payload"]"#)
+    )]
+    pub payload_hex: Option<String>,
+
+    /// Read the payload of each UDP-packet from the standard input
+    /// until its end, instead of sending random bytes. The packet
+    /// length is derived from the read payload.
+    #[structopt(
+        long = "send-stdin",
+        raw(
+            conflicts_with_all = r#"&["length", "length_range", "send_file", "payload_hex", "random_payload"]"#
+        )
+    )]
+    pub send_stdin: bool,
+
+    /// A template of each UDP-packet, in which '{seq}' is replaced by
+    /// the packet index and '{timestamp}' by the current Unix time in
+    /// milliseconds (for example, 'GET /ping?n={seq}'). The result is
+    /// padded with zeros or truncated to the packet length.
+    #[structopt(
+        long = "template",
+        takes_value = true,
+        value_name = "STRING",
+        raw(
+            conflicts_with_all = r#"&["send_file", "payload_hex", "send_stdin", "random_payload", "sequence"]"#
+        )
     )]
-    pub length: NonZeroUsize,
+    pub template: Option<String>,
+
+    /// Repeat the content of the file specified by --send-file
+    /// until it fills the packet length specified by --length, if
+    /// the file is shorter than that.
+    #[structopt(long = "repeat-payload")]
+    pub repeat_payload: bool,
 
     /// A waiting time before an attack execution. It is mainly
     /// used to prevent a launch of an erroneous (unwanted) attack.
@@ -92,11 +378,72 @@ pub struct ArgsConfig {
         default_value = "5secs",
         parse(try_from_str = "parse_duration")
     )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_duration"))]
     pub wait: Duration,
 
+    /// A time to sleep after the sockets are bound, but before the
+    /// first packet is sent. It gives other tools (such as a traffic
+    /// capture) a moment to prepare.
+    #[structopt(
+        long = "warmup",
+        takes_value = true,
+        value_name = "TIME-SPAN",
+        default_value = "0secs",
+        parse(try_from_str = "parse_duration")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_duration"))]
+    pub warmup: Duration,
+
+    /// Fill every UDP-packet with fresh random bytes instead of
+    /// sending the same random packet repeatedly.
+    #[structopt(long = "random-payload", conflicts_with = "send_file")]
+    pub random_payload: bool,
+
+    /// A byte (decimal or hex prefixed by 0x, for example, 0xFF) which
+    /// fills every UDP-packet instead of the random bytes, so the
+    /// packets are easy to recognize.
+    #[structopt(
+        long = "fill-byte",
+        takes_value = true,
+        value_name = "BYTE",
+        raw(
+            conflicts_with_all = r#"&["random_payload", "send_file", "payload_hex", "send_stdin", "template"]"#
+        ),
+        parse(try_from_str = "parse_fill_byte")
+    )]
+    pub fill_byte: Option<u8>,
+
+    /// Write an incrementing big-endian 64-bit number into the first
+    /// eight bytes of each UDP-packet, so a receiver can detect lost
+    /// packets. The number is unique across all the threads, and the
+    /// rest of the packet is the configured payload.
+    #[structopt(long = "sequence")]
+    pub sequence: bool,
+
+    /// Prepend a 32-bit magic value (decimal or hex prefixed by 0x) to
+    /// each UDP-packet, followed by the CRC32 of the payload, so a
+    /// receiver can verify where the packets came from. Both of them
+    /// are big-endian, and the header makes each packet eight bytes
+    /// longer than its payload.
+    #[structopt(
+        long = "magic",
+        takes_value = true,
+        value_name = "NUMBER",
+        raw(conflicts_with_all = r#"&["sequence", "template"]"#),
+        parse(try_from_str = "parse_magic")
+    )]
+    pub magic: Option<u32>,
+
+    /// A seed for the random number generators used to fill
+    /// packets. Every thread derives its own generator from the seed
+    /// and its index, so two runs with the same seed will produce
+    /// identical byte streams. By default, the seed is chosen randomly.
+    #[structopt(long = "seed", takes_value = true, value_name = "NUMBER")]
+    pub seed: Option<u64>,
+
     /// A periodicity of sending packets. The default value equals
-    /// to zero seconds, that is, all packets will be sent
-    /// momentarily.
+    /// to zero seconds, that is, all packets will be sent as fast
+    /// as possible, without any sleeping between them.
     #[structopt(
         long = "send-periodicity",
         takes_value = true,
@@ -104,8 +451,100 @@ pub struct ArgsConfig {
         default_value = "0secs",
         parse(try_from_str = "parse_duration")
     )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_duration"))]
     pub send_periodicity: Duration,
 
+    /// A maximum random deviation of the sending periodicity, so
+    /// the actual gap between packets is chosen uniformly from the
+    /// periodicity plus or minus this value. It is ignored if
+    /// --packets-per-second or --bandwidth is specified. The default
+    /// value equals to zero seconds, that is, packets are evenly
+    /// spaced.
+    #[structopt(
+        long = "jitter",
+        takes_value = true,
+        value_name = "TIME-SPAN",
+        default_value = "0secs",
+        parse(try_from_str = "parse_duration")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_duration"))]
+    pub jitter: Duration,
+
+    /// A count of packets sent per second. When specified, the
+    /// program paces itself to approximately this rate instead of
+    /// using a fixed sending periodicity. If the system cannot push
+    /// packets that fast, they are sent as fast as possible.
+    #[structopt(
+        long = "packets-per-second",
+        takes_value = true,
+        value_name = "PACKETS",
+        conflicts_with = "send_periodicity",
+        parse(try_from_str = "parse_non_zero_usize")
+    )]
+    pub rate: Option<NonZeroUsize>,
+
+    /// A maximum count of bytes sent per second, specified with an
+    /// optional suffix: 'kb', 'mb', or 'gb' (for example, '50mb').
+    /// When specified, the program computes a delay between packets
+    /// from the bandwidth and the packet length.
+    #[structopt(
+        long = "bandwidth",
+        takes_value = true,
+        value_name = "SIZE",
+        raw(conflicts_with_all = r#"&["send_periodicity", "rate"]"#),
+        parse(try_from_str = "parse_byte_size")
+    )]
+    pub bandwidth: Option<NonZeroUsize>,
+
+    /// A time during which the sending rate, specified by
+    /// --packets-per-second or --bandwidth, linearly grows from
+    /// zero to its full value. The default value equals to zero
+    /// seconds, that is, the full rate is used from the start.
+    #[structopt(
+        long = "ramp-up",
+        takes_value = true,
+        value_name = "TIME-SPAN",
+        default_value = "0secs",
+        parse(try_from_str = "parse_duration")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_duration"))]
+    pub ramp_up: Duration,
+
+    /// A time of sending packets in each burst. Along with --burst-off,
+    /// the attack alternates sending for this time and idling for the
+    /// --burst-off time, until the attack ends. Both options must be
+    /// specified together.
+    #[structopt(
+        long = "burst-on",
+        takes_value = true,
+        value_name = "TIME-SPAN",
+        parse(try_from_str = "parse_duration")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_option_duration"))]
+    pub burst_on: Option<Duration>,
+
+    /// A time of idling between the bursts of --burst-on.
+    #[structopt(
+        long = "burst-off",
+        takes_value = true,
+        value_name = "TIME-SPAN",
+        parse(try_from_str = "parse_duration")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_option_duration"))]
+    pub burst_off: Option<Duration>,
+
+    /// Send the packets in a tight loop without any sleeping, for the
+    /// maximum throughput. The pacing options, including --wait, cannot
+    /// be specified along with it, but the stop conditions (such as
+    /// --duration and --packets) still hold.
+    #[structopt(
+        long = "no-delay",
+        raw(
+            conflicts_with_all = r#"&["wait", "send_periodicity", "jitter", "rate", "bandwidth", "ramp_up", "burst_on", "burst_off"]"#
+        )
+    )]
+    pub no_delay: bool,
+
     /// A count of packets per displaying attack summaries. It is
     /// not recommended to set this option to a small value (say, 6)
     /// for the performance reasons.
@@ -118,6 +557,21 @@ pub struct ArgsConfig {
     )]
     pub display_periodicity: NonZeroUsize,
 
+    /// A periodicity of displaying the attack progress: packets
+    /// sent, the current speed, and the elapsed and remaining time.
+    /// The default value equals to zero seconds, that is, the
+    /// progress is not displayed. Unless --status-line is specified,
+    /// the progress is a notification, which is displayed with -v.
+    #[cfg_attr(feature = "serde", serde(with = "serde_duration"))]
+    #[structopt(
+        long = "report-interval",
+        takes_value = true,
+        value_name = "TIME-SPAN",
+        default_value = "0secs",
+        parse(try_from_str = "parse_duration")
+    )]
+    pub report_interval: Duration,
+
     /// A count of packets for sending. The default value equals to
     /// the largest number available for the inner data type.
     #[structopt(
@@ -130,125 +584,5864 @@ pub struct ArgsConfig {
     )]
     pub packets: NonZeroUsize,
 
-    /// If sending calls will continue longer than this timeout, the
-    /// program will exit with an error. By default, all the sending
-    /// calls will continue indefinitely.
+    /// A count of bytes for sending, specified with an optional
+    /// suffix: 'kb', 'mb', or 'gb' (for example, '10mb'). The attack
+    /// stops as soon as this count is reached, so the last packet may
+    /// exceed it. Together with --packets and --duration, the attack
+    /// ends on whichever limit is hit first.
+    #[structopt(
+        long = "bytes",
+        takes_value = true,
+        value_name = "SIZE",
+        parse(try_from_str = "parse_byte_limit")
+    )]
+    pub byte_limit: Option<u64>,
+
+    /// A count of threads sending packets simultaneously, each with
+    /// its own socket and an equal share of the packets.
+    #[structopt(
+        long = "threads",
+        takes_value = true,
+        value_name = "COUNT",
+        default_value = "1",
+        parse(try_from_str = "parse_non_zero_usize")
+    )]
+    pub threads: NonZeroUsize,
+
+    /// A count of packets sent by a single system call (sendmmsg),
+    /// which noticeably reduces the overhead of sending small packets.
+    /// It is supported only on Linux, and the packets are sent one by
+    /// one on the other platforms. The largest value is 1024.
+    #[structopt(
+        long = "batch-size",
+        takes_value = true,
+        value_name = "COUNT",
+        default_value = "1"
+    )]
+    pub batch_size: usize,
+
+    /// A comma-separated list of CPU cores (for example, 0,2,4) to pin
+    /// the threads to, in turn, for reproducible benchmarks. It is
+    /// supported only on Linux, and the threads stay unpinned on the
+    /// other platforms.
+    #[structopt(
+        long = "cores",
+        takes_value = true,
+        value_name = "LIST",
+        parse(try_from_str = "parse_cores")
+    )]
+    pub cores: Option<CoreList>,
+
+    /// A count of consecutive sending errors allowed before the
+    /// program exits with an error. The default value equals to
+    /// zero, that is, the first error stops the attack.
+    #[structopt(
+        long = "allow-errors",
+        takes_value = true,
+        value_name = "COUNT",
+        default_value = "0"
+    )]
+    pub allowed_errors: usize,
+
+    /// A count of the first packets whose sending must not all fail.
+    /// If none of them is sent, the program exits immediately, since
+    /// the receiver is most likely unreachable (mistyped, for example)
+    /// and the rest of the attack would be useless. Zero disables the
+    /// probing.
+    #[structopt(
+        long = "probe-count",
+        takes_value = true,
+        value_name = "COUNT",
+        default_value = "16",
+        parse(try_from_str = "parse_probe_count")
+    )]
+    pub probe_count: u64,
+
+    /// A count of retries of a packet whose sending failed with a
+    /// transient error, such as a full send buffer. The retries are
+    /// made after exponentially growing delays, starting from one
+    /// millisecond. Only a packet that still fails is counted as a
+    /// failed one, and the other errors are never retried.
+    #[structopt(
+        long = "retries",
+        takes_value = true,
+        value_name = "COUNT",
+        default_value = "0"
+    )]
+    pub retries: u32,
+
+    /// A minimum percentage of successfully sent packets among all the
+    /// attempted ones, specified as a number from 0 to 100. If the
+    /// attack finishes with a lower percentage, the program exits with
+    /// an error, which is useful in CI. By default, the percentage
+    /// isn't checked.
+    #[structopt(long = "fail-under", takes_value = true, value_name = "PERCENT")]
+    pub fail_under: Option<f64>,
+
+    /// Allow sending packets to a broadcast address (SO_BROADCAST).
+    /// It is required if one of the receivers is 255.255.255.255.
+    #[structopt(long = "broadcast")]
+    pub broadcast: bool,
+
+    /// Connect the socket to the receiver once and send packets
+    /// without specifying the address each time, which can be
+    /// noticeably faster. It requires exactly one receiver.
+    #[structopt(long = "connect")]
+    pub connect: bool,
+
+    /// Set the don't-fragment bit of outgoing packets, so the packets
+    /// exceeding the path MTU are dropped instead of being fragmented.
+    /// The packets exceeding the MTU of the local interface fail to be
+    /// sent, so --allow-errors might be needed to continue the attack.
+    #[structopt(long = "dont-fragment")]
+    pub dont_fragment: bool,
+
+    /// A time-to-live value of outgoing packets, from 1 to 255. By
+    /// default, the operating system default is used.
+    #[structopt(long = "ttl", takes_value = true, value_name = "HOPS")]
+    pub ttl: Option<u32>,
+
+    /// A type of service of outgoing packets (IP_TOS, or the traffic
+    /// class for IPv6), specified as a byte. The DSCP class occupies
+    /// its upper six bits, so, for example, 184 marks packets as EF.
+    /// By default, the operating system default is used.
+    #[structopt(long = "tos", takes_value = true, value_name = "BYTE")]
+    pub tos: Option<u8>,
+
+    /// A priority of the sockets (SO_PRIORITY), which the queueing
+    /// disciplines of the egress interface might take into account.
+    /// The values above 6 require the CAP_NET_ADMIN capability. This
+    /// option is supported only on Linux, and it is ignored with a
+    /// warning on the other platforms.
+    #[structopt(
+        long = "priority",
+        takes_value = true,
+        value_name = "NUMBER",
+        parse(try_from_str = "parse_priority")
+    )]
+    pub priority: Option<u32>,
+
+    /// A time-to-live value of outgoing multicast packets (the hop
+    /// limit for IPv6), from 0 to 255. It requires at least one of the receivers to
+    /// be a multicast group. By default, packets don't leave the
+    /// local network.
+    #[structopt(long = "multicast-ttl", takes_value = true, value_name = "HOPS")]
+    pub multicast_ttl: Option<u32>,
+
+    /// Whether multicast packets are looped back to the local
+    /// sockets, specified as 'true' or 'false'. It requires at least
+    /// one of the receivers to be a multicast group. By default, the
+    /// operating system default is used.
+    #[structopt(long = "multicast-loop", takes_value = true, value_name = "BOOL")]
+    pub multicast_loop: Option<bool>,
+
+    /// A size of the socket send buffer (SO_SNDBUF), specified with
+    /// an optional suffix: 'kb', 'mb', or 'gb'. The kernel might
+    /// adjust this value, so the effective size is logged.
+    #[structopt(
+        long = "send-buffer",
+        takes_value = true,
+        value_name = "SIZE",
+        parse(try_from_str = "parse_byte_size")
+    )]
+    pub send_buffer: Option<NonZeroUsize>,
+
+    /// A timeout of each sending call (SO_SNDTIMEO). A timed out call
+    /// is counted as a failed packet, so it stops the attack unless
+    /// --allow-errors permits it. By default, all the sending calls
+    /// will continue indefinitely.
     #[structopt(
         long = "send-timeout",
         takes_value = true,
         value_name = "TIME-SPAN",
         parse(try_from_str = "parse_duration")
     )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_option_duration"))]
     pub send_timeout: Option<Duration>,
 
-    /// Enable the debugging mode
+    /// A periodicity of closing the sockets and binding them again to
+    /// the sender addresses, which might recover a socket wedged by
+    /// the kernel-side buffer exhaustion. The attack summary and the
+    /// sequence numbers are kept as they are. By default, the sockets
+    /// are never recreated.
+    #[structopt(
+        long = "recreate-socket-every",
+        takes_value = true,
+        value_name = "TIME-SPAN",
+        parse(try_from_str = "parse_duration")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_option_duration"))]
+    pub recreate_every: Option<Duration>,
+
+    /// A TOML file to load the configuration from. Its keys are the
+    /// same as the long names of the options: receiver, sender,
+    /// duration, length, wait, and send-periodicity. The options
+    /// specified on the command line override the file values.
+    #[structopt(
+        long = "config",
+        takes_value = true,
+        value_name = "PATH",
+        parse(from_os_str)
+    )]
+    pub config_file: Option<PathBuf>,
+
+    /// A format of the final attack summary, which is printed to
+    /// stdout: 'human' or 'json'. In the JSON mode, the summary is
+    /// printed as a single line with the "meta" object (the program
+    /// version, the start time and the configuration) and the "result"
+    /// one (the statistics). All the other messages are always printed
+    /// to stderr.
+    #[structopt(
+        long = "output-format",
+        takes_value = true,
+        value_name = "FORMAT",
+        default_value = "human"
+    )]
+    pub output_format: OutputFormat,
+
+    /// A label of this attack, which prefixes every printed message
+    /// and the final attack summary as [LABEL], so the output of
+    /// several simultaneous attacks can be told apart. In the JSON
+    /// mode, the summary starts with it as the "label" field.
+    #[structopt(long = "label", takes_value = true, value_name = "STRING")]
+    pub label: Option<String>,
+
+    /// A CSV file to append a row with the attack summary to each
+    /// time it is displayed. The header is written only if the file
+    /// is newly created. With several threads, each of them appends
+    /// its own rows.
+    #[structopt(
+        long = "csv",
+        takes_value = true,
+        value_name = "PATH",
+        parse(from_os_str)
+    )]
+    pub csv_path: Option<PathBuf>,
+
+    /// A file to write the final attack summary to in the Prometheus
+    /// exposition format, for the textfile collector of node_exporter.
+    /// The file is replaced atomically, so a collector never reads a
+    /// partially written one.
+    #[structopt(
+        long = "prometheus-file",
+        takes_value = true,
+        value_name = "PATH",
+        parse(from_os_str)
+    )]
+    pub prometheus_path: Option<PathBuf>,
+
+    /// Receive the packets sent to a loopback receiver on the same host
+    /// and report how many of them arrived, which estimates the local
+    /// loss. The receivers must be loopback addresses not bound by any
+    /// other program, since the program binds them itself.
+    #[structopt(long = "measure-loss", conflicts_with = "unix_socket")]
+    pub measure_loss: bool,
+
+    /// Validate the configuration and print it without sending any
+    /// packets.
+    #[structopt(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Print the IP-addresses of the local network interfaces, one of
+    /// which can be specified as --sender, and exit without sending any
+    /// packets.
+    #[structopt(long = "list-interfaces")]
+    pub list_interfaces: bool,
+
+    /// Print the effective configuration as JSON to stderr before
+    /// starting the attack. Unlike --dry-run, the attack is then
+    /// performed as usual.
+    #[structopt(long = "show-config")]
+    pub show_config: bool,
+
+    /// Print only warnings, errors, and the final attack summary, so
+    /// neither the attack progress nor the other notifications are
+    /// displayed, even if --report-interval or --status-line is
+    /// specified.
+    #[structopt(long = "quiet")]
+    pub quiet: bool,
+
+    /// Redraw the attack progress in a single status line instead of
+    /// printing a new line each --report-interval (or each second if
+    /// it isn't specified). The status line is drawn only on a
+    /// terminal, so the progress is printed as usual lines otherwise.
+    #[structopt(long = "status-line")]
+    pub status_line: bool,
+
+    /// Print each failed sending call with its error kind and the
+    /// receiver, regardless of the logging level. At most 10 failures
+    /// are printed per second, and the count of the skipped ones is
+    /// printed along with the next failure.
+    #[structopt(long = "verbose-errors")]
+    pub verbose_errors: bool,
+
+    /// Print more messages: notifications (including the attack
+    /// progress) if specified once, also debugging ones if specified
+    /// twice, and also traces if specified three times. By default,
+    /// only warnings and errors are printed.
+    #[structopt(
+        short = "v",
+        long = "verbose",
+        conflicts_with = "quiet",
+        parse(from_occurrences = "verbosity_level")
+    )]
+    #[cfg_attr(feature = "serde", serde(with = "serde_level_filter"))]
+    pub log_level: LevelFilter,
+
+    /// Enable the debugging mode, the same as -vvv
     #[structopt(long = "debug")]
     pub debug: bool,
 }
 
-impl Display for ArgsConfig {
-    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        let send_timeout = match self.send_timeout {
-            Some(given_timeout) => format_duration(given_timeout).to_string(),
-            None => String::from("infinity"),
-        };
+impl ArgsConfig {
+    /// Parses the command-line arguments of the current process and
+    /// constructs a config from them. Incorrectly specified arguments
+    /// are reported by the argument parser itself, which prints the
+    /// usage and exits, just like it does for `--help`.
+    pub fn from_args() -> Result<ArgsConfig, ArgsConfigError> {
+        ArgsConfig::from_matches(&ArgsConfig::clap().get_matches())
+    }
 
-        write!(
-            fmt,
-            "receiver: {receiver}, \
-             sender: {sender}, \
-             duration: {duration}, \
-             length: {length}, \
-             wait: {wait}, \
-             send-periodicity: {send_periodicity}, \
-             display-periodicity: {display_periodicity}, \
-             packets: {packets}, \
-             send-timeout: {send_timeout}, \
-             debug: {debug}",
-            receiver = self.receiver,
-            sender = self.sender,
-            duration = format_duration(self.duration),
-            length = self.length,
-            wait = format_duration(self.wait),
-            send_periodicity = format_duration(self.send_periodicity),
-            display_periodicity = self.display_periodicity,
-            packets = self.packets,
-            send_timeout = send_timeout,
-            debug = self.debug,
-        )
+    /// The same as `from_args`, but parses `args` instead of the process
+    /// arguments, where the first one is the program name. Incorrectly
+    /// specified arguments are returned as `ArgsConfigError::Arguments`
+    /// instead of exiting.
+    pub fn from_args_iter<I, T>(args: I) -> Result<ArgsConfig, ArgsConfigError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = ArgsConfig::clap()
+            .get_matches_from_safe(args)
+            .map_err(|error| ArgsConfigError::Arguments(error.message))?;
+        ArgsConfig::from_matches(&matches)
     }
-}
 
-pub fn parse_non_zero_usize(number: &str) -> Result<NonZeroUsize, NonZeroUsizeError> {
-    let number: usize = number
-        .parse()
-        .map_err(|error| NonZeroUsizeError::InvalidFormat(error))?;
+    /// Constructs a config from the parsed command-line arguments,
+    /// applying a configuration file if it was specified, and
+    /// validates the result.
+    pub fn from_matches(matches: &ArgMatches) -> Result<ArgsConfig, ArgsConfigError> {
+        ArgsConfig::from_matches_all(matches).map_err(|mut errors| errors.remove(0))
+    }
 
-    NonZeroUsize::new(number).ok_or(NonZeroUsizeError::ZeroValue)
-}
+    /// The same as `from_matches`, but returns all the found errors
+    /// instead of only the first one, so they can be fixed at once.
+    pub fn from_matches_all(matches: &ArgMatches) -> Result<ArgsConfig, Vec<ArgsConfigError>> {
+        ArgsConfig::from_matches_with(matches, |name| env::var(name).ok())
+    }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum NonZeroUsizeError {
-    InvalidFormat(ParseIntError),
-    ZeroValue,
-}
+    /// Constructs a config, looking up the environment variables by
+    /// `lookup_env`, so the environment can be replaced in tests.
+    fn from_matches_with<F>(
+        matches: &ArgMatches,
+        lookup_env: F,
+    ) -> Result<ArgsConfig, Vec<ArgsConfigError>>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut config = ArgsConfig::from_clap(matches);
+        config.log_level = log_level(matches);
 
-impl Display for NonZeroUsizeError {
-    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        match self {
-            NonZeroUsizeError::InvalidFormat(error) => write!(fmt, "{}", error),
-            NonZeroUsizeError::ZeroValue => write!(fmt, "The value equals to zero"),
+        if let Some(path) = config.config_file.clone() {
+            config
+                .apply_file(&path, matches)
+                .map_err(|error| vec![error])?;
         }
-    }
-}
+        config
+            .apply_env(matches, lookup_env)
+            .map_err(|error| vec![error])?;
 
-impl Error for NonZeroUsizeError {}
+        if let Some(path) = config.receivers_file.clone() {
+            let receivers = read_receivers_file(&path).map_err(|error| vec![error])?;
+            config.receiver.extend(receivers);
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // The hosts are validated as any other receivers, and the invalid
+        // ranges themselves are reported by the validation
+        for cidr in &config.receiver_cidr {
+            if let Ok(receivers) = expand_cidr(cidr) {
+                config
+                    .receiver
+                    .extend(receivers.into_iter().map(|receiver| (1, receiver)));
+            }
+        }
+
+        // The mapped receivers are reachable from an IPv4 socket, so they
+        // don't make the default sender an IPv6 one
+        if config.sender.iter().all(SocketAddr::is_ipv4) {
+            for (_, receiver) in &mut config.receiver {
+                *receiver = unmap_ipv4(*receiver);
+            }
+        }
+
+        // The socket is bound to the sender address, so the default sender
+        // must match the family of IPv6 receivers
+        if matches.occurrences_of("sender") == 0
+            && config.sender == [SocketAddr::from(([0, 0, 0, 0], 0))]
+            && !config.receiver.is_empty()
+            && config.receiver_addresses().all(SocketAddr::is_ipv6)
+        {
+            config.sender = vec![SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0))];
+        }
+
+        let errors = config.validate_all();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        if let Some(warning) = config.mtu_warning() {
+            warn!("{}", warning);
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            if config.priority.is_some() {
+                warn!("--priority is supported only on Linux, so it is ignored.");
+            }
+        }
+
+        // The file is created only when the attack starts
+        if let Some(ref path) = config.csv_path {
+            check_csv(path).map_err(|error| {
+                vec![ArgsConfigError::Csv(format!(
+                    "{}: {}",
+                    path.display(),
+                    error
+                ))]
+            })?;
+        }
+
+        // The file itself is written only after the attack, but an
+        // unwritable directory is better reported before it
+        if let Some(ref path) = config.prometheus_path {
+            let temp_path = prometheus_temp_path(path);
+            fs::File::create(&temp_path)
+                .and_then(|_| fs::remove_file(&temp_path))
+                .map_err(|error| {
+                    vec![ArgsConfigError::Prometheus(format!(
+                        "{}: {}",
+                        path.display(),
+                        error
+                    ))]
+                })?;
+        }
+
+        Ok(config)
+    }
+
+    fn apply_file(&mut self, path: &Path, matches: &ArgMatches) -> Result<(), ArgsConfigError> {
+        let file_error = |message: String| {
+            ArgsConfigError::ConfigFile(format!("{}: {}", path.display(), message))
+        };
+
+        let content = fs::read_to_string(path).map_err(|error| file_error(error.to_string()))?;
+        let table = match content.parse::<toml::Value>() {
+            Ok(toml::Value::Table(table)) => table,
+            Ok(_) => return Err(file_error(String::from("Expected a table"))),
+            Err(error) => return Err(file_error(error.to_string())),
+        };
+
+        for (key, value) in table {
+            let arg_name = key.replace('-', "_");
+
+            // The command-line arguments take precedence over the file
+            if matches.occurrences_of(&arg_name) > 0 {
+                continue;
+            }
+
+            let value_error = |message: String| file_error(format!("'{}': {}", key, message));
+            let as_str = |value: &toml::Value| {
+                value
+                    .as_str()
+                    .map(str::to_owned)
+                    .ok_or_else(|| value_error(String::from("Expected a string")))
+            };
+
+            match arg_name.as_str() {
+                "receiver" => {
+                    let receivers = match value {
+                        toml::Value::Array(ref receivers) => receivers.clone(),
+                        value => vec![value],
+                    };
+
+                    // A string can hold a comma-separated list, as on the command line
+                    self.receiver = receivers
+                        .iter()
+                        .map(|receiver| {
+                            let receiver = as_str(receiver)?;
+                            parse_list_with(&receiver, parse_weighted_receiver).map_err(
+                                |(index, error)| {
+                                    value_error(format!(
+                                        "'{}': {}",
+                                        list_element(&receiver, index),
+                                        error
+                                    ))
+                                },
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                }
+                "sender" => {
+                    let senders = match value {
+                        toml::Value::Array(ref senders) => senders.clone(),
+                        value => vec![value],
+                    };
+
+                    self.sender = senders
+                        .iter()
+                        .map(|sender| {
+                            let sender = as_str(sender)?;
+                            parse_list_with(&sender, parse_socket_addr).map_err(|(index, error)| {
+                                value_error(format!(
+                                    "'{}': {}",
+                                    list_element(&sender, index),
+                                    error
+                                ))
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                    if self.sender.is_empty() {
+                        return Err(value_error(String::from("Expected at least one sender")));
+                    }
+                }
+                "duration" | "wait" | "send_periodicity" => {
+                    let duration = parse_duration(&as_str(&value)?)
+                        .map_err(|error| value_error(error.to_string()))?;
+
+                    match arg_name.as_str() {
+                        "duration" => self.duration = time_limit(duration),
+                        "wait" => self.wait = duration,
+                        _ => self.send_periodicity = duration,
+                    }
+                }
+                "length" => {
+                    let length = value
+                        .as_integer()
+                        .ok_or_else(|| value_error(String::from("Expected an integer")))?;
+                    if length < 0 {
+                        return Err(file_error(String::from("length must be positive")));
+                    }
+
+                    self.length = Some(
+                        check_length_overflow(length as usize)
+                            .map_err(|error| value_error(error.to_string()))?,
+                    );
+                }
+                _ => return Err(file_error(format!("Unknown key '{}'", key))),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the `ENV_VARS` environment variables to the options which
+    /// weren't specified on the command line.
+    fn apply_env<F>(&mut self, matches: &ArgMatches, lookup_env: F) -> Result<(), ArgsConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        for &(arg_name, variable) in ENV_VARS.iter() {
+            if matches.occurrences_of(arg_name) > 0 {
+                continue;
+            }
+            let value = match lookup_env(variable) {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let env_error = |message: String| {
+                ArgsConfigError::Environment(format!("{}: {}", variable, message))
+            };
+
+            match arg_name {
+                "sender" => {
+                    self.sender = parse_list_with(&value, parse_socket_addr)
+                        .map_err(|(_, error)| env_error(error.to_string()))?;
+                    if self.sender.is_empty() {
+                        return Err(env_error(String::from("Expected at least one sender")));
+                    }
+                }
+                "duration" => {
+                    self.duration =
+                        parse_time_limit(&value).map_err(|error| env_error(error.to_string()))?
+                }
+                "length" => {
+                    self.length =
+                        Some(parse_length(&value).map_err(|error| env_error(error.to_string()))?)
+                }
+                "wait" => {
+                    self.wait =
+                        parse_duration(&value).map_err(|error| env_error(error.to_string()))?
+                }
+                _ => {
+                    self.send_periodicity =
+                        parse_duration(&value).map_err(|error| env_error(error.to_string()))?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the constraints that cannot be expressed by the argument
+    /// parser itself.
+    pub fn validate(&self) -> Result<(), ArgsConfigError> {
+        match self.validate_all().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks the whole config and returns all the found errors instead
+    /// of only the first one.
+    pub fn validate_all(&self) -> Vec<ArgsConfigError> {
+        let mut errors = Vec::new();
+
+        if self.receiver.is_empty() && self.receiver_cidr.is_empty() && self.unix_socket.is_none() {
+            errors.push(ArgsConfigError::NoReceivers);
+        }
+
+        if let Some(ref path) = self.unix_socket {
+            if !is_socket(path) {
+                errors.push(ArgsConfigError::UnixSocket(path.clone()));
+            }
+        }
+
+        for cidr in &self.receiver_cidr {
+            if let Err(error) = expand_cidr(cidr) {
+                errors.push(ArgsConfigError::ReceiverCidr(cidr.clone(), error));
+            }
+        }
+
+        if let Some((_, receiver)) = self.receiver.iter().find(|(weight, _)| *weight == 0) {
+            errors.push(ArgsConfigError::ReceiverWeight(*receiver));
+        }
+
+        // Unlike the sender, a receiver cannot ask for an ephemeral port
+        if let Some(receiver) = self
+            .receiver_addresses()
+            .find(|receiver| receiver.port() == 0)
+        {
+            errors.push(ArgsConfigError::ZeroReceiverPort(*receiver));
+        }
+
+        // A sender is bound only once, as the socket of its own
+        for (index, sender) in self.sender.iter().enumerate() {
+            if let Some(first) = self.sender[..index]
+                .iter()
+                .position(|other| other == sender)
+            {
+                errors.push(ArgsConfigError::Sender(
+                    index + 1,
+                    format!(
+                        "{} is already specified as the sender #{}",
+                        sender,
+                        first + 1
+                    ),
+                ));
+            }
+        }
+
+        // An IPv4-mapped receiver is normalized for IPv4 senders only
+        if let (Some(receiver), Some(sender)) = (
+            self.receiver_addresses()
+                .find(|receiver| unmap_ipv4(**receiver) != **receiver),
+            self.sender.iter().find(|sender| sender.is_ipv6()),
+        ) {
+            errors.push(ArgsConfigError::MappedAddress(*receiver, *sender));
+        }
+
+        // An IPv4 socket cannot send packets to IPv6 receivers and vice versa
+        if let Some((receiver, sender)) = self.sender.iter().find_map(|sender| {
+            self.receiver_addresses()
+                .find(|receiver| receiver.is_ipv4() != sender.is_ipv4())
+                .map(|receiver| (receiver, sender))
+        }) {
+            errors.push(ArgsConfigError::FamilyMismatch(*receiver, *sender));
+        }
+
+        if let Some((start, end)) = self.source_ports {
+            if start > end {
+                errors.push(ArgsConfigError::SourcePorts(start, end));
+            }
+        }
+
+        // The socket rejects the values that don't fit into the IP header
+        if let Some(ttl) = self.ttl {
+            if ttl == 0 || ttl > 255 {
+                errors.push(ArgsConfigError::Ttl(ttl));
+            }
+        }
+
+        // An interface name must fit into IFNAMSIZ (16) bytes with the NUL
+        if let Some(ref name) = self.interface {
+            if name.is_empty()
+                || name.len() >= 16
+                || name.contains(|character: char| {
+                    character == '/' || character.is_whitespace() || character == '\0'
+                })
+            {
+                errors.push(ArgsConfigError::Interface(name.clone()));
+            }
+        }
+
+        // The socket rejects a zero timeout instead of treating it as no timeout
+        if self.send_timeout == Some(Duration::from_secs(0)) {
+            errors.push(ArgsConfigError::SendTimeout);
+        }
+        if self.recreate_every == Some(Duration::from_secs(0)) {
+            errors.push(ArgsConfigError::RecreateEvery);
+        }
+
+        if self.burst_on.is_some() != self.burst_off.is_some() {
+            errors.push(ArgsConfigError::BurstPairing);
+        }
+        if self.burst_on == Some(Duration::from_secs(0)) {
+            errors.push(ArgsConfigError::BurstOn);
+        }
+
+        // The default uniform distribution is harmless without a range
+        if self.length_distribution != LengthDistribution::Uniform && self.length_range.is_none() {
+            errors.push(ArgsConfigError::LengthDistribution(
+                self.length_distribution,
+            ));
+        }
+
+        // A NaN is never within the range either
+        if let Some(percent) = self.fail_under {
+            if !(0.0..=100.0).contains(&percent) {
+                errors.push(ArgsConfigError::FailUnder(percent));
+            }
+        }
+
+        if self.label.as_deref() == Some("") {
+            errors.push(ArgsConfigError::Label);
+        }
+
+        if self.batch_size == 0 || self.batch_size > MAX_BATCH_SIZE {
+            errors.push(ArgsConfigError::BatchSize(self.batch_size));
+        }
+
+        if let Some(ref cores) = self.cores {
+            let available = available_cores();
+            if let Some(core) = cores.iter().find(|&&core| core >= available) {
+                errors.push(ArgsConfigError::Cores(format!(
+                    "the core {} doesn't exist, there are only {} cores",
+                    core, available
+                )));
+            }
+        }
+
+        if self.repeat_payload && self.send_file.is_empty() {
+            errors.push(ArgsConfigError::RepeatWithoutFile);
+        }
+
+        if let Some(ref hex) = self.payload_hex {
+            if let Err(error) = decode_hex(hex) {
+                errors.push(ArgsConfigError::PayloadHex(error));
+            }
+        }
+
+        // Only --length can be zero, the other lengths are nonzero anyway
+        if self.length == Some(0) && !self.unchecked_length {
+            errors.push(ArgsConfigError::PacketLength(PacketLengthError::Underflow));
+        }
+
+        // The other lengths can be checked only against the valid bounds
+        let (min_length, max_length) = (self.min_packet_length, self.max_packet_length);
+        if min_length > max_length || max_length.get() > ABSOLUTE_MAX_PACKET_LENGTH {
+            errors.push(ArgsConfigError::LengthBounds(min_length, max_length));
+        } else {
+            let lengths = match (self.length_range, &self.payload_hex) {
+                (Some((min, max)), _) => vec![min.get(), max.get()],
+                (None, Some(hex)) => decode_hex(hex)
+                    .map(|payload| vec![payload.len()])
+                    .unwrap_or_default(),
+                (None, None) => self.length.into_iter().collect(),
+            };
+
+            // The file and the standard input datagrams are checked by the attacker
+            let longest = match lengths.iter().max() {
+                Some(&length) => Some(length),
+                None if self.send_file.is_empty() && !self.send_stdin => Some(self.packet_length()),
+                None => None,
+            };
+            if let Some(error) = lengths
+                .into_iter()
+                .find_map(|length| self.check_length(length).err())
+            {
+                errors.push(ArgsConfigError::PacketLength(error));
+            } else if let Some(Err(error)) =
+                longest.map(|length| self.check_datagram_size(length + self.header_length()))
+            {
+                errors.push(error);
+            }
+        }
+
+        if let Some(ref template) = self.template {
+            if let Err(error) = parse_template(template) {
+                errors.push(ArgsConfigError::Template(error));
+            }
+        }
+
+        // The file and the standard input lengths are known only by the attacker
+        let shortest = match (self.length_range, &self.payload_hex) {
+            (Some((min, _)), _) => Some(min.get()),
+            (None, Some(hex)) => decode_hex(hex).ok().map(|payload| payload.len()),
+            (None, None) if self.send_file.is_empty() && !self.send_stdin => {
+                Some(self.packet_length())
+            }
+            (None, None) => None,
+        };
+        if let Some(length) = shortest {
+            if self.sequence && length < SEQUENCE_NUMBER_LENGTH {
+                errors.push(ArgsConfigError::SequenceLength(length));
+            }
+        }
+
+        // Only the packets sent to this host itself can be received back
+        if self.measure_loss {
+            if let Some(receiver) = self
+                .receiver_addresses()
+                .find(|receiver| !receiver.ip().is_loopback())
+            {
+                errors.push(ArgsConfigError::MeasureLoss(*receiver));
+            }
+        }
+
+        // A connected socket can send packets only to its peer
+        if self.connect && self.receiver.len() > 1 {
+            errors.push(ArgsConfigError::ConnectReceivers(self.receiver.len()));
+        }
+
+        // Sending packets to the sending socket itself only floods loopback
+        if let Some(sender) = self.sender.iter().find(|sender| {
+            self.receiver_addresses()
+                .any(|receiver| receiver == *sender)
+        }) {
+            errors.push(ArgsConfigError::SameEndpoints(*sender));
+        }
+
+        if (self.multicast_ttl.is_some() || self.multicast_loop.is_some())
+            && !self.receiver_addresses().any(is_multicast)
+        {
+            errors.push(ArgsConfigError::MulticastRequired);
+        }
+        if let Some(ttl) = self.multicast_ttl {
+            if ttl > 255 {
+                errors.push(ArgsConfigError::MulticastTtl(ttl));
+            }
+        }
+
+        if !self.broadcast {
+            if let Some(receiver) = self
+                .receiver_addresses()
+                .find(|receiver| match receiver.ip() {
+                    IpAddr::V4(address) => address.is_broadcast(),
+                    IpAddr::V6(_) => false,
+                })
+            {
+                errors.push(ArgsConfigError::BroadcastRequired(*receiver));
+            }
+        }
+
+        errors
+    }
+
+    /// Returns the time after which the attack is stopped, which is the
+    /// shortest of --duration and --max-runtime.
+    pub fn effective_duration(&self) -> TimeLimit {
+        match (self.duration, self.max_runtime) {
+            (Some(duration), Some(max_runtime)) => Some(duration.min(max_runtime)),
+            (duration, max_runtime) => duration.or(max_runtime),
+        }
+    }
+
+    /// Returns the addresses of the receivers without their weights.
+    pub fn receiver_addresses(&self) -> impl Iterator<Item = &SocketAddr> + '_ {
+        self.receiver.iter().map(|(_, address)| address)
+    }
+
+    /// Returns a warning if the explicitly specified packet length exceeds
+    /// `TYPICAL_MTU_PAYLOAD`. Such packets are still sent, but they are
+    /// fragmented or even dropped on the way.
+    pub fn mtu_warning(&self) -> Option<String> {
+        let length = match (self.length, self.length_range) {
+            (Some(length), _) => length,
+            (None, Some((_, max))) => max.get(),
+            (None, None) => return None,
+        };
+
+        if length <= TYPICAL_MTU_PAYLOAD {
+            return None;
+        }
+
+        Some(format!(
+            "The packet length of {} bytes exceeds {} bytes, the payload of a single packet \
+             on a typical 1500-byte MTU link, so the packets will likely be fragmented or dropped",
+            length, TYPICAL_MTU_PAYLOAD
+        ))
+    }
+
+    /// Returns the waiting time before an attack, which is skipped
+    /// under `--no-delay`.
+    pub fn wait_time(&self) -> Duration {
+        if self.no_delay {
+            Duration::from_secs(0)
+        } else {
+            self.wait
+        }
+    }
+
+    /// Returns the specified packet length or the default one if
+    /// `--length` was omitted.
+    pub fn packet_length(&self) -> usize {
+        // The default length must fit into the overridden bounds as well
+        self.length.unwrap_or_else(|| {
+            DEFAULT_PACKET_LENGTH
+                .min(self.max_packet_length.get())
+                .max(self.min_packet_length.get())
+        })
+    }
+
+    /// Returns the first receiver, which is the only one unless several
+    /// are specified, or `None` if the packets are sent to `--unix-socket`.
+    pub fn receiver(&self) -> Option<SocketAddr> {
+        self.receiver_addresses().next().cloned()
+    }
+
+    /// Returns the first sender, which is the only one unless several
+    /// are specified.
+    pub fn sender(&self) -> SocketAddr {
+        self.sender[0]
+    }
+
+    /// Returns the specified working time, which is `None` if the attack
+    /// isn't limited in time.
+    pub fn duration(&self) -> TimeLimit {
+        self.duration
+    }
+
+    /// Returns the length of the generated packets, the same as
+    /// `packet_length`.
+    pub fn length(&self) -> usize {
+        self.packet_length()
+    }
+
+    /// Returns the waiting time before an attack, as it was specified.
+    pub fn waiting(&self) -> Duration {
+        self.wait
+    }
+
+    /// Returns the periodicity of sending packets.
+    pub fn periodicity(&self) -> Duration {
+        self.send_periodicity
+    }
+
+    /// Returns the count of the bytes prepended to each payload, which is
+    /// the magic header if `--magic` is specified. The sequence number
+    /// overwrites the beginning of the payload instead.
+    pub fn header_length(&self) -> usize {
+        match self.magic {
+            Some(_) => MAGIC_HEADER_LENGTH,
+            None => 0,
+        }
+    }
+
+    /// Returns the size of a datagram on the wire: the packet length along
+    /// with the prepended header, if any.
+    pub fn datagram_size(&self) -> usize {
+        self.packet_length() + self.header_length()
+    }
+
+    /// Checks that a datagram of `size` bytes, including its header, fits
+    /// into `--max-length`, unless `--unchecked-length` is specified.
+    pub fn check_datagram_size(&self, size: usize) -> Result<(), ArgsConfigError> {
+        if size > self.max_packet_length.get() && !self.unchecked_length {
+            return Err(ArgsConfigError::DatagramSize(
+                size,
+                self.max_packet_length.get(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that `length` is within `--min-length` and `--max-length`,
+    /// unless `--unchecked-length` is specified.
+    pub fn check_length(&self, length: usize) -> Result<(), PacketLengthError> {
+        if self.unchecked_length {
+            return Ok(());
+        }
+        if length < self.min_packet_length.get() {
+            return Err(PacketLengthError::BelowMinimum(
+                self.min_packet_length.get(),
+            ));
+        }
+        if length > self.max_packet_length.get() {
+            return Err(PacketLengthError::AboveMaximum(
+                self.max_packet_length.get(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the whole config as a pretty-printed JSON object, where
+    /// durations are humantime strings. It doesn't require the serde
+    /// support, but its output is exactly what the serde
+    /// implementation can deserialize.
+    pub fn to_json(&self) -> String {
+        let fields = self
+            .json_fields()
+            .iter()
+            .map(|(name, value)| format!("  \"{}\": {}", name, value))
+            .collect::<Vec<String>>()
+            .join(",\n");
+        format!("{{\n{}\n}}", fields)
+    }
+
+    /// The same as `to_json`, but the object takes a single line, so it
+    /// can be embedded into the JSON summary.
+    pub fn to_compact_json(&self) -> String {
+        let fields = self
+            .json_fields()
+            .iter()
+            .map(|(name, value)| format!("\"{}\":{}", name, value))
+            .collect::<Vec<String>>()
+            .join(",");
+        format!("{{{}}}", fields)
+    }
+
+    fn json_fields(&self) -> Vec<(&'static str, String)> {
+        let path = |value: &Option<PathBuf>| {
+            json_or_null(
+                value
+                    .as_ref()
+                    .map(|path| json_string(&path.to_string_lossy())),
+            )
+        };
+        let duration = |value: Duration| json_string(&format_duration(value).to_string());
+        let pair = |value: Option<(String, String)>| {
+            json_or_null(value.map(|(first, second)| format!("[{}, {}]", first, second)))
+        };
+
+        let receivers = self
+            .receiver
+            .iter()
+            .map(|(weight, receiver)| {
+                format!("[{}, {}]", weight, json_string(&receiver.to_string()))
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        vec![
+            ("receiver", format!("[{}]", receivers)),
+            (
+                "receiver_cidr",
+                format!(
+                    "[{}]",
+                    self.receiver_cidr
+                        .iter()
+                        .map(|cidr| json_string(cidr))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            ),
+            (
+                "sender",
+                format!(
+                    "[{}]",
+                    self.sender
+                        .iter()
+                        .map(|sender| json_string(&sender.to_string()))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            ),
+            (
+                "source_ports",
+                pair(
+                    self.source_ports
+                        .map(|(start, end)| (start.to_string(), end.to_string())),
+                ),
+            ),
+            (
+                "interface",
+                json_or_null(self.interface.as_deref().map(json_string)),
+            ),
+            ("unix_socket", path(&self.unix_socket)),
+            ("duration", json_or_null(self.duration.map(duration))),
+            ("max_runtime", json_or_null(self.max_runtime.map(duration))),
+            ("length", json_or_null(self.length)),
+            (
+                "length_range",
+                pair(
+                    self.length_range
+                        .map(|(min, max)| (min.to_string(), max.to_string())),
+                ),
+            ),
+            (
+                "length_distribution",
+                json_string(&self.length_distribution.to_string()),
+            ),
+            ("min_packet_length", self.min_packet_length.to_string()),
+            ("max_packet_length", self.max_packet_length.to_string()),
+            ("unchecked_length", self.unchecked_length.to_string()),
+            (
+                "send_file",
+                format!(
+                    "[{}]",
+                    self.send_file
+                        .iter()
+                        .map(|path| json_string(&path.to_string_lossy()))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            ),
+            (
+                "payload_hex",
+                json_or_null(self.payload_hex.as_deref().map(json_string)),
+            ),
+            ("send_stdin", self.send_stdin.to_string()),
+            (
+                "template",
+                json_or_null(self.template.as_deref().map(json_string)),
+            ),
+            ("repeat_payload", self.repeat_payload.to_string()),
+            ("wait", duration(self.wait)),
+            ("warmup", duration(self.warmup)),
+            ("random_payload", self.random_payload.to_string()),
+            ("fill_byte", json_or_null(self.fill_byte)),
+            ("sequence", self.sequence.to_string()),
+            ("magic", json_or_null(self.magic)),
+            ("seed", json_or_null(self.seed)),
+            ("send_periodicity", duration(self.send_periodicity)),
+            ("jitter", duration(self.jitter)),
+            ("rate", json_or_null(self.rate)),
+            ("bandwidth", json_or_null(self.bandwidth)),
+            ("ramp_up", duration(self.ramp_up)),
+            ("burst_on", json_or_null(self.burst_on.map(duration))),
+            ("burst_off", json_or_null(self.burst_off.map(duration))),
+            ("no_delay", self.no_delay.to_string()),
+            ("display_periodicity", self.display_periodicity.to_string()),
+            ("report_interval", duration(self.report_interval)),
+            ("packets", self.packets.to_string()),
+            ("byte_limit", json_or_null(self.byte_limit)),
+            ("threads", self.threads.to_string()),
+            ("batch_size", self.batch_size.to_string()),
+            (
+                "cores",
+                json_or_null(self.cores.as_ref().map(|cores| format!("{:?}", cores))),
+            ),
+            ("allowed_errors", self.allowed_errors.to_string()),
+            ("probe_count", self.probe_count.to_string()),
+            ("retries", self.retries.to_string()),
+            ("fail_under", json_or_null(self.fail_under)),
+            ("broadcast", self.broadcast.to_string()),
+            ("connect", self.connect.to_string()),
+            ("dont_fragment", self.dont_fragment.to_string()),
+            ("ttl", json_or_null(self.ttl)),
+            ("tos", json_or_null(self.tos)),
+            ("priority", json_or_null(self.priority)),
+            ("multicast_ttl", json_or_null(self.multicast_ttl)),
+            ("multicast_loop", json_or_null(self.multicast_loop)),
+            ("send_buffer", json_or_null(self.send_buffer)),
+            (
+                "send_timeout",
+                json_or_null(self.send_timeout.map(duration)),
+            ),
+            (
+                "recreate_every",
+                json_or_null(self.recreate_every.map(duration)),
+            ),
+            ("receivers_file", path(&self.receivers_file)),
+            ("config_file", path(&self.config_file)),
+            (
+                "output_format",
+                json_string(&self.output_format.to_string()),
+            ),
+            (
+                "label",
+                json_or_null(self.label.as_deref().map(json_string)),
+            ),
+            ("csv_path", path(&self.csv_path)),
+            ("prometheus_path", path(&self.prometheus_path)),
+            ("measure_loss", self.measure_loss.to_string()),
+            ("dry_run", self.dry_run.to_string()),
+            ("list_interfaces", self.list_interfaces.to_string()),
+            ("show_config", self.show_config.to_string()),
+            ("quiet", self.quiet.to_string()),
+            ("status_line", self.status_line.to_string()),
+            ("verbose_errors", self.verbose_errors.to_string()),
+            ("log_level", json_string(&self.log_level.to_string())),
+            ("debug", self.debug.to_string()),
+        ]
+    }
+}
+
+impl Display for ArgsConfig {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let send_timeout = match self.send_timeout {
+            Some(given_timeout) => format_duration(given_timeout).to_string(),
+            None => String::from("infinity"),
+        };
+        let recreate_every = match self.recreate_every {
+            Some(periodicity) => format_duration(periodicity).to_string(),
+            None => String::from("never"),
+        };
+        let burst = |value: Option<Duration>| match value {
+            Some(time) => format_duration(time).to_string(),
+            None => String::from("none"),
+        };
+        let send_buffer = match self.send_buffer {
+            Some(size) => format!("{} bytes", size),
+            None => String::from("default"),
+        };
+        let ttl = match self.ttl {
+            Some(ttl) => ttl.to_string(),
+            None => String::from("default"),
+        };
+        let tos = match self.tos {
+            Some(tos) => tos.to_string(),
+            None => String::from("default"),
+        };
+        let priority = match self.priority {
+            Some(priority) => priority.to_string(),
+            None => String::from("default"),
+        };
+        let multicast_ttl = match self.multicast_ttl {
+            Some(ttl) => ttl.to_string(),
+            None => String::from("default"),
+        };
+        let multicast_loop = match self.multicast_loop {
+            Some(multicast_loop) => multicast_loop.to_string(),
+            None => String::from("default"),
+        };
+        let rate = match self.rate {
+            Some(rate) => rate.to_string(),
+            None => String::from("unlimited"),
+        };
+        let bandwidth = match self.bandwidth {
+            Some(bandwidth) => format!("{} bytes/s", bandwidth),
+            None => String::from("unlimited"),
+        };
+        let fail_under = match self.fail_under {
+            Some(percent) => format!("{}%", percent),
+            None => String::from("none"),
+        };
+        let byte_limit = match self.byte_limit {
+            Some(bytes) => format!("{} bytes", bytes),
+            None => String::from("unlimited"),
+        };
+        let seed = match self.seed {
+            Some(seed) => seed.to_string(),
+            None => String::from("random"),
+        };
+        let length_range = match self.length_range {
+            Some((min, max)) => format!("{}:{}", min, max),
+            None => String::from("none"),
+        };
+        let csv_path = match self.csv_path {
+            Some(ref path) => path.display().to_string(),
+            None => String::from("none"),
+        };
+        let prometheus_path = match self.prometheus_path {
+            Some(ref path) => path.display().to_string(),
+            None => String::from("none"),
+        };
+        let interface = match self.interface {
+            Some(ref name) => name.clone(),
+            None => String::from("default"),
+        };
+        let magic = match self.magic {
+            Some(magic) => format!("{:#010x}", magic),
+            None => String::from("none"),
+        };
+        let fill_byte = match self.fill_byte {
+            Some(byte) => format!("{:#04x}", byte),
+            None => String::from("none"),
+        };
+        let cores = match self.cores {
+            Some(ref cores) => cores
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(","),
+            None => String::from("any"),
+        };
+        let unix_socket = match self.unix_socket {
+            Some(ref path) => path.display().to_string(),
+            None => String::from("none"),
+        };
+        let source_ports = match self.source_ports {
+            Some((start, end)) => format!("{}:{}", start, end),
+            None => String::from("none"),
+        };
+        let send_file = match self.send_file.as_slice() {
+            [] => String::from("none"),
+            paths => paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+        let receiver_cidr = match self.receiver_cidr.as_slice() {
+            [] => String::from("none"),
+            ranges => ranges.join(", "),
+        };
+        let receivers_file = match self.receivers_file {
+            Some(ref path) => path.display().to_string(),
+            None => String::from("none"),
+        };
+        let payload_hex = match self.payload_hex {
+            Some(ref hex) => hex.clone(),
+            None => String::from("none"),
+        };
+        let template = match self.template {
+            Some(ref template) => template.clone(),
+            None => String::from("none"),
+        };
+
+        let duration = match self.duration {
+            Some(duration) => format_duration(duration).to_string(),
+            None => String::from("unlimited"),
+        };
+        let max_runtime = match self.max_runtime {
+            Some(max_runtime) => format_duration(max_runtime).to_string(),
+            None => String::from("none"),
+        };
+
+        let entries = [
+            ("receiver", format_receivers(&self.receiver)),
+            (
+                "sender",
+                self.sender
+                    .iter()
+                    .map(SocketAddr::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ),
+            ("receiver-cidr", receiver_cidr),
+            ("receivers-file", receivers_file),
+            ("source-ports", source_ports),
+            ("interface", interface),
+            ("unix-socket", unix_socket),
+            ("duration", duration),
+            ("max-runtime", max_runtime),
+            ("length", self.packet_length().to_string()),
+            ("length-range", length_range),
+            ("length-distribution", self.length_distribution.to_string()),
+            ("min-length", self.min_packet_length.to_string()),
+            ("max-length", self.max_packet_length.to_string()),
+            ("unchecked-length", self.unchecked_length.to_string()),
+            ("send-file", send_file),
+            ("payload-hex", payload_hex),
+            ("send-stdin", self.send_stdin.to_string()),
+            ("template", template),
+            ("repeat-payload", self.repeat_payload.to_string()),
+            ("random-payload", self.random_payload.to_string()),
+            ("fill-byte", fill_byte),
+            ("sequence", self.sequence.to_string()),
+            ("magic", magic),
+            ("seed", seed),
+            ("wait", format_duration(self.wait).to_string()),
+            ("warmup", format_duration(self.warmup).to_string()),
+            (
+                "send-periodicity",
+                format_duration(self.send_periodicity).to_string(),
+            ),
+            ("jitter", format_duration(self.jitter).to_string()),
+            ("packets-per-second", rate),
+            ("bandwidth", bandwidth),
+            ("ramp-up", format_duration(self.ramp_up).to_string()),
+            ("burst-on", burst(self.burst_on)),
+            ("burst-off", burst(self.burst_off)),
+            ("no-delay", self.no_delay.to_string()),
+            ("display-periodicity", self.display_periodicity.to_string()),
+            (
+                "report-interval",
+                format_duration(self.report_interval).to_string(),
+            ),
+            ("packets", self.packets.to_string()),
+            ("bytes", byte_limit),
+            ("threads", self.threads.to_string()),
+            ("batch-size", self.batch_size.to_string()),
+            ("cores", cores),
+            ("allow-errors", self.allowed_errors.to_string()),
+            ("probe-count", self.probe_count.to_string()),
+            ("retries", self.retries.to_string()),
+            ("fail-under", fail_under),
+            ("broadcast", self.broadcast.to_string()),
+            ("connect", self.connect.to_string()),
+            ("dont-fragment", self.dont_fragment.to_string()),
+            ("ttl", ttl),
+            ("tos", tos),
+            ("priority", priority),
+            ("multicast-ttl", multicast_ttl),
+            ("multicast-loop", multicast_loop),
+            ("send-buffer", send_buffer),
+            ("send-timeout", send_timeout),
+            ("recreate-socket-every", recreate_every),
+            ("output-format", self.output_format.to_string()),
+            (
+                "label",
+                self.label.clone().unwrap_or_else(|| String::from("none")),
+            ),
+            ("csv", csv_path),
+            ("prometheus-file", prometheus_path),
+            ("measure-loss", self.measure_loss.to_string()),
+            ("dry-run", self.dry_run.to_string()),
+            ("list-interfaces", self.list_interfaces.to_string()),
+            ("show-config", self.show_config.to_string()),
+            ("quiet", self.quiet.to_string()),
+            ("status-line", self.status_line.to_string()),
+            ("verbose-errors", self.verbose_errors.to_string()),
+            ("log-level", self.log_level.to_string().to_lowercase()),
+            ("debug", self.debug.to_string()),
+        ];
+
+        // The alternate form (`{:#}`) puts every option on its own line
+        let separator = if fmt.alternate() { "\n" } else { ", " };
+        for (index, (name, value)) in entries.iter().enumerate() {
+            if index > 0 {
+                fmt.write_str(separator)?;
+            }
+            write!(fmt, "{}: {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts an IPv4-mapped IPv6 address (`::ffff:a.b.c.d`) to the IPv4
+/// address it maps, keeping the port. The other addresses are returned
+/// as they are.
+pub fn unmap_ipv4(address: SocketAddr) -> SocketAddr {
+    match address {
+        SocketAddr::V6(v6) => match v6.ip().to_ipv4_mapped() {
+            Some(ip) => SocketAddr::new(IpAddr::V4(ip), v6.port()),
+            None => address,
+        },
+        SocketAddr::V4(_) => address,
+    }
+}
+
+/// Checks whether `address` is a multicast group: 224.0.0.0/4 for IPv4 or
+/// ff00::/8 for IPv6.
+pub fn is_multicast(address: &SocketAddr) -> bool {
+    address.ip().is_multicast()
+}
+
+/// Checks whether `path` exists and is a UNIX domain socket, which is
+/// never true on the other platforms.
+fn is_socket(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+
+        fs::metadata(path)
+            .map(|metadata| metadata.file_type().is_socket())
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Formats the receivers in the same way as they are specified, where
+/// the default weight is omitted. Only the first receivers of a long
+/// list (such as an expanded range) are listed.
+fn format_receivers(receivers: &[(u32, SocketAddr)]) -> String {
+    const MAX_LISTED: usize = 16;
+
+    let listed = receivers
+        .iter()
+        .take(MAX_LISTED)
+        .map(|(weight, receiver)| match weight {
+            1 => receiver.to_string(),
+            weight => format!("{}:{}", weight, receiver),
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    match receivers.len() {
+        length if length > MAX_LISTED => {
+            format!("{}, and {} more", listed, length - MAX_LISTED)
+        }
+        _ => listed,
+    }
+}
+
+/// Quotes `value` as a JSON string, escaping the characters which
+/// cannot appear in it literally.
+pub(crate) fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            control if control.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", control as u32))
+            }
+            other => quoted.push(other),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats an optional JSON value, where `None` becomes `null`.
+fn json_or_null<T: Display>(value: Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => String::from("null"),
+    }
+}
+
+/// Parses a count of bytes for sending in the same way as `parse_byte_size`.
+pub fn parse_byte_limit(size: &str) -> Result<u64, ByteSizeError> {
+    parse_byte_size(size).map(|size| size.get() as u64)
+}
+
+/// Maps the count of `-v` occurrences to the logging level, where the
+/// default level prints only warnings and errors.
+fn verbosity_level(occurrences: u64) -> LevelFilter {
+    match occurrences {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Returns the logging level specified by `--quiet`, `-v`, and
+/// `--debug`. It is available before a config is constructed, so the
+/// configuration errors are logged with the right level.
+pub fn log_level(matches: &ArgMatches) -> LevelFilter {
+    if matches.is_present("quiet") {
+        LevelFilter::Warn
+    } else if matches.is_present("debug") {
+        LevelFilter::Trace
+    } else {
+        verbosity_level(matches.occurrences_of("log_level"))
+    }
+}
+
+/// Parses the hard limit of a program working time, which must be greater
+/// than zero.
+pub fn parse_max_runtime(value: &str) -> Result<Duration, ArgsConfigError> {
+    match parse_duration(value) {
+        Ok(duration) if duration == Duration::from_secs(0) => Err(ArgsConfigError::MaxRuntime(
+            String::from("it must be greater than zero"),
+        )),
+        Ok(duration) => Ok(duration),
+        Err(error) => Err(ArgsConfigError::MaxRuntime(error.to_string())),
+    }
+}
+
+/// Parses a time span in the humantime format (such as '1h 30m' or
+/// '500ms'). If it fails, a bare integer is taken as seconds, so '30'
+/// is the same as '30s'.
+pub fn parse_duration(value: &str) -> Result<Duration, ArgsConfigError> {
+    humantime::parse_duration(value).or_else(|error| {
+        value
+            .trim()
+            .parse()
+            .map(Duration::from_secs)
+            .map_err(|_| ArgsConfigError::Duration(value.to_string(), error.to_string()))
+    })
+}
+
+/// Parses a program working time, where zero means an unlimited one.
+pub fn parse_time_limit(value: &str) -> Result<TimeLimit, ArgsConfigError> {
+    parse_duration(value).map(time_limit)
+}
+
+fn time_limit(duration: Duration) -> TimeLimit {
+    if duration == Duration::from_secs(0) {
+        None
+    } else {
+        Some(duration)
+    }
+}
+
+/// Parses a receiver with an optional weight, specified as
+/// WEIGHT:ADDRESS. A receiver without a weight has the weight of one.
+pub fn parse_weighted_receiver(receiver: &str) -> Result<(u32, SocketAddr), SocketAddrError> {
+    if let Some((weight, address)) = receiver.split_once(':') {
+        // A leading number is a weight only if the rest is an address itself
+        if !weight.is_empty() && weight.chars().all(|character| character.is_ascii_digit()) {
+            if let Ok(address) = parse_socket_addr(address) {
+                return weight
+                    .parse()
+                    .map(|weight| (weight, address))
+                    .map_err(|error| SocketAddrError::InvalidWeight(receiver.to_string(), error));
+            }
+        }
+    }
+
+    parse_socket_addr(receiver).map(|address| (1, address))
+}
+
+/// Reads the receivers from the file at `path`, one per line. Empty lines
+/// and `#` comments are skipped, and an invalid receiver is reported along
+/// with the (one-based) number of its line.
+pub fn read_receivers_file(path: &Path) -> Result<Vec<(u32, SocketAddr)>, ArgsConfigError> {
+    let content = fs::read_to_string(path).map_err(|error| {
+        ArgsConfigError::ReceiversFile(path.display().to_string(), None, error.to_string())
+    })?;
+
+    let mut receivers = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let receiver = match line.split_once('#') {
+            Some((receiver, _)) => receiver.trim(),
+            None => line.trim(),
+        };
+        if receiver.is_empty() {
+            continue;
+        }
+
+        receivers.push(parse_weighted_receiver(receiver).map_err(|error| {
+            ArgsConfigError::ReceiversFile(
+                path.display().to_string(),
+                Some(index + 1),
+                error.to_string(),
+            )
+        })?);
+    }
+
+    Ok(receivers)
+}
+
+/// Parses a literal socket address or, if it fails, resolves a hostname
+/// (such as `example.com:80`) and takes the first resolved address.
+pub fn parse_socket_addr(address: &str) -> Result<SocketAddr, SocketAddrError> {
+    let literal_error = match address.parse() {
+        Ok(address) => return Ok(address),
+        Err(error) => error,
+    };
+
+    // Only a hostname followed by a valid port number can be resolved
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => (),
+        _ => {
+            return Err(SocketAddrError::InvalidFormat(
+                address.to_string(),
+                literal_error,
+            ))
+        }
+    }
+
+    let resolve_error = |message: String| SocketAddrError::Resolve(address.to_string(), message);
+    address
+        .to_socket_addrs()
+        .map_err(|error| resolve_error(error.to_string()))?
+        .next()
+        .ok_or_else(|| resolve_error(String::from("The name resolves to nothing")))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketAddrError {
+    InvalidFormat(String, AddrParseError),
+    Resolve(String, String),
+    InvalidWeight(String, ParseIntError),
+}
+
+impl Display for SocketAddrError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            SocketAddrError::InvalidFormat(address, error) => {
+                write!(fmt, "'{}' is not a valid address ({})", address, error)
+            }
+            SocketAddrError::Resolve(address, message) => {
+                write!(fmt, "Cannot resolve '{}' ({})", address, message)
+            }
+            SocketAddrError::InvalidWeight(receiver, error) => {
+                write!(fmt, "'{}' has an invalid weight ({})", receiver, error)
+            }
+        }
+    }
+}
+
+impl Error for SocketAddrError {}
+
+/// The most host bits of a receiver range, so it contains at most 65536
+/// addresses (an IPv4 range cannot be broader than /16).
+pub const MAX_CIDR_HOST_BITS: u32 = 16;
+
+/// Expands a receiver range, specified as ADDRESS/PREFIX:PORT (such as
+/// `10.0.0.0/29:80`), into all of its host addresses on that port. The
+/// network and the broadcast addresses of an IPv4 range are skipped,
+/// unless the range is too small to have them (/31 or /32).
+pub fn expand_cidr(cidr: &str) -> Result<Vec<SocketAddr>, CidrError> {
+    // The port follows the last colon, so the IPv6 colons stay intact
+    let (range, port) = cidr.rsplit_once(':').ok_or(CidrError::InvalidFormat)?;
+    let port = port
+        .parse::<u16>()
+        .ok()
+        .filter(|&port| port != 0)
+        .ok_or_else(|| CidrError::InvalidPort(String::from(port)))?;
+
+    let range = range
+        .strip_prefix('[')
+        .and_then(|range| range.strip_suffix(']'))
+        .unwrap_or(range);
+    let (address, prefix) = range.split_once('/').ok_or(CidrError::InvalidFormat)?;
+    let address: IpAddr = address.parse().map_err(CidrError::InvalidAddress)?;
+
+    let bits = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix
+        .parse::<u32>()
+        .ok()
+        .filter(|&prefix| prefix <= bits)
+        .ok_or_else(|| CidrError::InvalidPrefix(String::from(prefix)))?;
+    let host_bits = bits - prefix;
+    if host_bits > MAX_CIDR_HOST_BITS {
+        return Err(CidrError::TooBroad(prefix));
+    }
+
+    let count = 1u128 << host_bits;
+    let network = match address {
+        IpAddr::V4(address) => u128::from(u32::from(address)),
+        IpAddr::V6(address) => u128::from(address),
+    } & !(count - 1);
+    let hosts = match address {
+        IpAddr::V4(_) if host_bits >= 2 => network + 1..network + count - 1,
+        _ => network..network + count,
+    };
+
+    Ok(hosts
+        .map(|host| match address {
+            IpAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::from(host as u32)), port),
+            IpAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::from(host)), port),
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CidrError {
+    InvalidFormat,
+    InvalidAddress(AddrParseError),
+    InvalidPrefix(String),
+    InvalidPort(String),
+    TooBroad(u32),
+}
+
+impl Display for CidrError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            CidrError::InvalidFormat => write!(fmt, "Expected a range as ADDRESS/PREFIX:PORT"),
+            CidrError::InvalidAddress(error) => write!(fmt, "{}", error),
+            CidrError::InvalidPrefix(prefix) => {
+                write!(fmt, "'{}' is not a valid prefix length", prefix)
+            }
+            CidrError::InvalidPort(port) => write!(fmt, "'{}' is not a valid non-zero port", port),
+            CidrError::TooBroad(prefix) => write!(
+                fmt,
+                "The /{} range is too broad, it can contain at most {} addresses",
+                prefix,
+                1u32 << MAX_CIDR_HOST_BITS
+            ),
+        }
+    }
+}
+
+impl Error for CidrError {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum LengthDistribution {
+    #[default]
+    Uniform,
+    Normal,
+}
+
+impl FromStr for LengthDistribution {
+    type Err = LengthDistributionError;
+
+    fn from_str(distribution: &str) -> Result<LengthDistribution, LengthDistributionError> {
+        match distribution.to_lowercase().as_str() {
+            "uniform" => Ok(LengthDistribution::Uniform),
+            "normal" => Ok(LengthDistribution::Normal),
+            _ => Err(LengthDistributionError(distribution.to_string())),
+        }
+    }
+}
+
+impl Display for LengthDistribution {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            LengthDistribution::Uniform => write!(fmt, "uniform"),
+            LengthDistribution::Normal => write!(fmt, "normal"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LengthDistributionError(String);
+
+impl Display for LengthDistributionError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "Unknown length distribution '{}', expected 'uniform' or 'normal'",
+            self.0
+        )
+    }
+}
+
+impl Error for LengthDistributionError {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = OutputFormatError;
+
+    fn from_str(format: &str) -> Result<OutputFormat, OutputFormatError> {
+        match format.to_lowercase().as_str() {
+            "human" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(OutputFormatError(format.to_string())),
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            OutputFormat::Human => write!(fmt, "human"),
+            OutputFormat::Json => write!(fmt, "json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputFormatError(String);
+
+impl Display for OutputFormatError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "Unknown output format '{}', expected 'human' or 'json'",
+            self.0
+        )
+    }
+}
+
+impl Error for OutputFormatError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgsConfigError {
+    NoReceivers,
+    ReceiverCidr(String, CidrError),
+    ReceiversFile(String, Option<usize>, String),
+    ReceiverWeight(SocketAddr),
+    ZeroReceiverPort(SocketAddr),
+    ConfigFile(String),
+    FamilyMismatch(SocketAddr, SocketAddr),
+    MappedAddress(SocketAddr, SocketAddr),
+    BroadcastRequired(SocketAddr),
+    SameEndpoints(SocketAddr),
+    BindFailed(SocketAddr, String),
+    Sender(usize, String),
+    ConnectReceivers(usize),
+    PayloadHex(PayloadHexError),
+    Template(TemplateError),
+    RepeatWithoutFile,
+    SequenceLength(usize),
+    Magic(String),
+    FillByte(String),
+    LengthBounds(NonZeroUsize, NonZeroUsize),
+    PacketLength(PacketLengthError),
+    DatagramSize(usize, usize),
+    LengthDistribution(LengthDistribution),
+    SendTimeout,
+    RecreateEvery,
+    MaxRuntime(String),
+    Duration(String, String),
+    BurstPairing,
+    BurstOn,
+    BatchSize(usize),
+    Cores(String),
+    ProbeCount(String),
+    MulticastRequired,
+    MulticastTtl(u32),
+    Stdin(String),
+    Environment(String),
+    SourcePorts(u16, u16),
+    Interface(String),
+    Ttl(u32),
+    Priority(String),
+    UnixSocket(PathBuf),
+    Csv(String),
+    Prometheus(String),
+    MeasureLoss(SocketAddr),
+    FailUnder(f64),
+    Label,
+    Arguments(String),
+}
+
+impl Display for ArgsConfigError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ArgsConfigError::NoReceivers => write!(fmt, "At least one receiver must be specified"),
+            ArgsConfigError::ReceiverCidr(cidr, error) => {
+                write!(
+                    fmt,
+                    "Cannot expand the receiver range '{}': {}",
+                    cidr, error
+                )
+            }
+            ArgsConfigError::ReceiversFile(path, Some(line), message) => {
+                write!(fmt, "Invalid receiver at {}:{}: {}", path, line, message)
+            }
+            ArgsConfigError::ReceiversFile(path, None, message) => {
+                write!(fmt, "Cannot read the receivers file {}: {}", path, message)
+            }
+            ArgsConfigError::ReceiverWeight(receiver) => write!(
+                fmt,
+                "The receiver {} has the zero weight, so it would never get a packet",
+                receiver
+            ),
+            ArgsConfigError::ZeroReceiverPort(receiver) => write!(
+                fmt,
+                "The receiver {} has the zero port, specify the real port of a victim server \
+                 (for example, {})",
+                receiver,
+                SocketAddr::new(receiver.ip(), 53)
+            ),
+            ArgsConfigError::FamilyMismatch(receiver, sender) => write!(
+                fmt,
+                "The receiver {} and the sender {} belong to different address families",
+                receiver, sender
+            ),
+            ArgsConfigError::MappedAddress(receiver, sender) => write!(
+                fmt,
+                "The receiver {} is an IPv4-mapped address, which isn't sent to from the IPv6 \
+                 sender {}, specify {} as the receiver or an IPv4 sender instead",
+                receiver,
+                sender,
+                unmap_ipv4(*receiver)
+            ),
+            ArgsConfigError::BroadcastRequired(receiver) => write!(
+                fmt,
+                "The receiver {} is a broadcast address, so --broadcast must be specified",
+                receiver
+            ),
+            ArgsConfigError::SameEndpoints(address) => write!(
+                fmt,
+                "The sender and the receiver are the same address {}, so packets would be sent to \
+                 the sending socket itself",
+                address
+            ),
+            ArgsConfigError::BindFailed(sender, error) => write!(
+                fmt,
+                "Cannot bind the sender {} ({}), check that it belongs to a local interface",
+                sender, error
+            ),
+            ArgsConfigError::Sender(index, error) => {
+                write!(fmt, "Invalid sender #{}: {}", index, error)
+            }
+            ArgsConfigError::ConnectReceivers(count) => write!(
+                fmt,
+                "--connect requires exactly one receiver, but {} receivers are specified",
+                count
+            ),
+            ArgsConfigError::PayloadHex(error) => {
+                write!(fmt, "Cannot decode the hex payload: {}", error)
+            }
+            ArgsConfigError::Template(error) => {
+                write!(fmt, "Cannot parse the payload template: {}", error)
+            }
+            ArgsConfigError::SequenceLength(length) => write!(
+                fmt,
+                "--sequence requires packets of at least {} bytes, but some of them are {} bytes",
+                SEQUENCE_NUMBER_LENGTH, length
+            ),
+            ArgsConfigError::Magic(value) => write!(
+                fmt,
+                "The magic value '{}' is not a 32-bit number (decimal or prefixed by 0x)",
+                value
+            ),
+            ArgsConfigError::FillByte(value) => write!(
+                fmt,
+                "The fill byte '{}' is not a number from 0 to 255 (decimal or prefixed by 0x)",
+                value
+            ),
+            ArgsConfigError::LengthBounds(min, max) => write!(
+                fmt,
+                "--min-length must not be greater than --max-length, which must not be greater \
+                 than {} bytes, but the bounds are {} and {} bytes",
+                ABSOLUTE_MAX_PACKET_LENGTH, min, max
+            ),
+            ArgsConfigError::PacketLength(error) => write!(fmt, "{}", error),
+            ArgsConfigError::DatagramSize(size, max) => write!(
+                fmt,
+                "The datagram of {} bytes, including its header, exceeds the maximum length \
+                 of {} bytes",
+                size, max
+            ),
+            ArgsConfigError::LengthDistribution(distribution) => write!(
+                fmt,
+                "The {} length distribution requires --length-range",
+                distribution
+            ),
+            ArgsConfigError::SendTimeout => write!(
+                fmt,
+                "--send-timeout must be greater than zero, omit it to wait indefinitely"
+            ),
+            ArgsConfigError::RecreateEvery => write!(
+                fmt,
+                "--recreate-socket-every must be greater than zero, omit it to keep the sockets"
+            ),
+            ArgsConfigError::MaxRuntime(message) => {
+                write!(fmt, "Invalid --max-runtime: {}", message)
+            }
+            ArgsConfigError::Duration(value, error) => write!(
+                fmt,
+                "'{}' is not a valid time span ({}), expected something like '1h 30m', \
+                 '500ms', or bare seconds",
+                value, error
+            ),
+            ArgsConfigError::BurstPairing => {
+                write!(fmt, "--burst-on and --burst-off must be specified together")
+            }
+            ArgsConfigError::BurstOn => write!(
+                fmt,
+                "--burst-on must be greater than zero, omit both burst options to send continuously"
+            ),
+            ArgsConfigError::Cores(message) => {
+                write!(fmt, "Cannot pin the threads to the cores: {}", message)
+            }
+            ArgsConfigError::ProbeCount(count) => write!(
+                fmt,
+                "The probe count '{}' is not a non-negative number, specify 0 to disable probing",
+                count
+            ),
+            ArgsConfigError::Ttl(ttl) => write!(
+                fmt,
+                "--ttl must be between 1 and 255, but {} is specified",
+                ttl
+            ),
+            ArgsConfigError::BatchSize(size) => write!(
+                fmt,
+                "--batch-size must be between 1 and {}, but {} is specified",
+                MAX_BATCH_SIZE, size
+            ),
+            ArgsConfigError::RepeatWithoutFile => write!(
+                fmt,
+                "--repeat-payload requires a file to repeat, specified by --send-file"
+            ),
+            ArgsConfigError::SourcePorts(start, end) => write!(
+                fmt,
+                "The source ports range {}:{} is reversed, START must not be greater than END",
+                start, end
+            ),
+            ArgsConfigError::Interface(name) => {
+                write!(fmt, "'{}' is not a valid network interface name", name)
+            }
+            ArgsConfigError::Priority(message) => {
+                write!(fmt, "Invalid socket priority: {}", message)
+            }
+            ArgsConfigError::MulticastRequired => write!(
+                fmt,
+                "The multicast options require at least one receiver to be a multicast group"
+            ),
+            ArgsConfigError::MulticastTtl(ttl) => write!(
+                fmt,
+                "--multicast-ttl must be between 0 and 255, but {} is specified",
+                ttl
+            ),
+            ArgsConfigError::Environment(message) => {
+                write!(fmt, "Invalid environment variable {}", message)
+            }
+            ArgsConfigError::Stdin(message) => {
+                write!(fmt, "Cannot read the payload from stdin: {}", message)
+            }
+            ArgsConfigError::UnixSocket(path) => write!(
+                fmt,
+                "The path {} isn't a UNIX domain socket, bind a receiver to it first",
+                path.display()
+            ),
+            ArgsConfigError::Csv(message) => write!(fmt, "Cannot open the CSV file {}", message),
+            ArgsConfigError::Prometheus(message) => {
+                write!(fmt, "Cannot write the Prometheus file {}", message)
+            }
+            ArgsConfigError::MeasureLoss(receiver) => write!(
+                fmt,
+                "--measure-loss requires loopback receivers, but {} is not a loopback address",
+                receiver
+            ),
+            ArgsConfigError::FailUnder(percent) => write!(
+                fmt,
+                "--fail-under must be a percentage from 0 to 100, but {} is specified",
+                percent
+            ),
+            ArgsConfigError::Label => write!(fmt, "The label must not be empty"),
+            ArgsConfigError::Arguments(message) => write!(fmt, "{}", message),
+            ArgsConfigError::ConfigFile(message) => {
+                write!(fmt, "Cannot load the configuration file {}", message)
+            }
+        }
+    }
+}
+
+impl Error for ArgsConfigError {}
+
+/// A builder of `ArgsConfig` for using the program as a library. All the
+/// values that weren't specified are the same as the command-line
+/// defaults.
+#[derive(Debug, Clone, Default)]
+pub struct ArgsConfigBuilder {
+    receiver: Vec<SocketAddr>,
+    sender: Vec<SocketAddr>,
+    duration: Option<Duration>,
+    length: Option<usize>,
+    wait: Option<Duration>,
+    send_periodicity: Option<Duration>,
+}
+
+impl ArgsConfigBuilder {
+    pub fn new() -> ArgsConfigBuilder {
+        ArgsConfigBuilder::default()
+    }
+
+    /// Adds one more receiver, so this method can be called several
+    /// times.
+    pub fn receiver(mut self, receiver: SocketAddr) -> ArgsConfigBuilder {
+        self.receiver.push(receiver);
+        self
+    }
+
+    /// Adds one more sender, which can be called several times as well.
+    /// The specified senders replace the default one.
+    pub fn sender(mut self, sender: SocketAddr) -> ArgsConfigBuilder {
+        self.sender.push(sender);
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> ArgsConfigBuilder {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn length(mut self, length: usize) -> Result<ArgsConfigBuilder, PacketLengthError> {
+        self.length = Some(check_packet_length(length)?.get());
+        Ok(self)
+    }
+
+    pub fn wait(mut self, wait: Duration) -> ArgsConfigBuilder {
+        self.wait = Some(wait);
+        self
+    }
+
+    pub fn send_periodicity(mut self, send_periodicity: Duration) -> ArgsConfigBuilder {
+        self.send_periodicity = Some(send_periodicity);
+        self
+    }
+
+    pub fn build(self) -> Result<ArgsConfig, ArgsConfigError> {
+        let first_receiver = self.receiver.first().ok_or(ArgsConfigError::NoReceivers)?;
+
+        // Take all the defaults from the argument parser itself
+        let matches = ArgsConfig::clap()
+            .get_matches_from_safe(vec![
+                String::from("anevicon"),
+                String::from("--receiver"),
+                first_receiver.to_string(),
+            ])
+            .map_err(|error| ArgsConfigError::Arguments(error.message))?;
+        // The environment is meant for the command-line program, not the library
+        let mut config = ArgsConfig::from_matches_with(&matches, |_| None)
+            .map_err(|mut errors| errors.remove(0))?;
+
+        config.receiver = self
+            .receiver
+            .into_iter()
+            .map(|receiver| (1, receiver))
+            .collect();
+        if !self.sender.is_empty() {
+            config.sender = self.sender;
+        }
+        config.duration = self.duration.map(time_limit).unwrap_or(config.duration);
+        config.length = self.length.or(config.length);
+        config.wait = self.wait.unwrap_or(config.wait);
+        config.send_periodicity = self.send_periodicity.unwrap_or(config.send_periodicity);
+
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+pub fn parse_packet_length(length: &str) -> Result<NonZeroUsize, PacketLengthError> {
+    check_packet_length(parse_length(length)?)
+}
+
+/// Parses `--length`, which can be zero under `--unchecked-length`, so
+/// only the overflow is checked here and the rest by
+/// `ArgsConfig::validate`.
+pub fn parse_length(length: &str) -> Result<usize, PacketLengthError> {
+    let length = length.trim().to_lowercase();
+    let (number, suffix) = length.split_at(
+        length
+            .trim_end_matches(|character: char| character.is_ascii_alphabetic())
+            .len(),
+    );
+
+    let multiplier: usize = match suffix {
+        "" | "b" => 1,
+        "kb" => 1024,
+        "mb" => 1024 * 1024,
+        suffix => return Err(PacketLengthError::InvalidSuffix(suffix.to_string())),
+    };
+    let number: usize = number
+        .trim()
+        .parse()
+        .map_err(PacketLengthError::InvalidFormat)?;
+
+    // The overflowed length is still greater than ABSOLUTE_MAX_PACKET_LENGTH
+    check_length_overflow(number.saturating_mul(multiplier))
+}
+
+/// Checks that `length` doesn't exceed `ABSOLUTE_MAX_PACKET_LENGTH`,
+/// which holds even under `--unchecked-length`.
+pub fn check_length_overflow(length: usize) -> Result<usize, PacketLengthError> {
+    if length > ABSOLUTE_MAX_PACKET_LENGTH {
+        return Err(PacketLengthError::Overflow);
+    }
+    Ok(length)
+}
+
+/// Checks that `length` fits into a UDP-packet at all. The bounds of
+/// `--min-length` and `--max-length` are checked by
+/// `ArgsConfig::check_length`.
+pub fn check_packet_length(length: usize) -> Result<NonZeroUsize, PacketLengthError> {
+    if length < MIN_PACKET_LENGTH {
+        return Err(PacketLengthError::Underflow);
+    }
+    if length > ABSOLUTE_MAX_PACKET_LENGTH {
+        return Err(PacketLengthError::Overflow);
+    }
+
+    Ok(NonZeroUsize::new(length).expect("MIN_PACKET_LENGTH equals to zero"))
+}
+
+pub fn parse_length_range(range: &str) -> Result<(NonZeroUsize, NonZeroUsize), PacketLengthError> {
+    let (min, max) = range
+        .split_once(':')
+        .ok_or(PacketLengthError::InvalidRange)?;
+
+    check_length_range(parse_packet_length(min)?, parse_packet_length(max)?)
+}
+
+pub fn check_length_range(
+    min: NonZeroUsize,
+    max: NonZeroUsize,
+) -> Result<(NonZeroUsize, NonZeroUsize), PacketLengthError> {
+    check_packet_length(min.get())?;
+    check_packet_length(max.get())?;
+
+    if min > max {
+        return Err(PacketLengthError::InvalidRange);
+    }
+    Ok((min, max))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketLengthError {
+    InvalidFormat(ParseIntError),
+    InvalidSuffix(String),
+    Underflow,
+    Overflow,
+    BelowMinimum(usize),
+    AboveMaximum(usize),
+    InvalidRange,
+}
+
+impl Display for PacketLengthError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            PacketLengthError::InvalidFormat(error) => write!(fmt, "{}", error),
+            PacketLengthError::InvalidSuffix(suffix) => write!(
+                fmt,
+                "Unknown suffix '{}', expected 'b', 'kb', or 'mb'",
+                suffix
+            ),
+            PacketLengthError::Underflow => write!(
+                fmt,
+                "The packet length is less than {} bytes",
+                MIN_PACKET_LENGTH
+            ),
+            PacketLengthError::Overflow => write!(
+                fmt,
+                "The packet length is greater than {} bytes",
+                ABSOLUTE_MAX_PACKET_LENGTH
+            ),
+            PacketLengthError::BelowMinimum(min) => write!(
+                fmt,
+                "The packet length is less than --min-length, which is {} bytes",
+                min
+            ),
+            PacketLengthError::AboveMaximum(max) => write!(
+                fmt,
+                "The packet length is greater than --max-length, which is {} bytes",
+                max
+            ),
+            PacketLengthError::InvalidRange => write!(
+                fmt,
+                "The range must be specified as MIN:MAX, where MIN is not greater than MAX"
+            ),
+        }
+    }
+}
+
+impl Error for PacketLengthError {}
+
+/// Parses a range of ports specified as START:END. The order of the
+/// ports is checked during the validation of the whole config.
+pub fn parse_port_range(range: &str) -> Result<(u16, u16), PortRangeError> {
+    let (start, end) = range.split_once(':').ok_or(PortRangeError::NoColon)?;
+
+    Ok((
+        start.parse().map_err(PortRangeError::InvalidPort)?,
+        end.parse().map_err(PortRangeError::InvalidPort)?,
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortRangeError {
+    NoColon,
+    InvalidPort(ParseIntError),
+}
+
+impl Display for PortRangeError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            PortRangeError::NoColon => write!(fmt, "The range must be specified as START:END"),
+            PortRangeError::InvalidPort(error) => write!(fmt, "Invalid port number: {}", error),
+        }
+    }
+}
+
+impl Error for PortRangeError {}
+
+/// Reads a packet payload from `reader` (the standard input for
+/// --send-stdin) until its end. The payload must fit into a single
+/// UDP-packet, so nothing beyond the maximum length is read.
+pub fn read_payload<R: Read>(reader: R) -> Result<Vec<u8>, ArgsConfigError> {
+    let mut payload = Vec::new();
+    reader
+        .take(ABSOLUTE_MAX_PACKET_LENGTH as u64 + 1)
+        .read_to_end(&mut payload)
+        .map_err(|error| ArgsConfigError::Stdin(error.to_string()))?;
+
+    check_packet_length(payload.len())
+        .map_err(|error| ArgsConfigError::Stdin(error.to_string()))?;
+    Ok(payload)
+}
+
+/// Decodes a hex string, optionally separated by whitespaces or colons
+/// (such as `de:ad:be:ef`), into a packet payload.
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, PayloadHexError> {
+    let digits = hex
+        .chars()
+        .filter(|character| !character.is_whitespace() && *character != ':')
+        .map(|character| {
+            character
+                .to_digit(16)
+                .map(|digit| digit as u8)
+                .ok_or(PayloadHexError::InvalidDigit(character))
+        })
+        .collect::<Result<Vec<u8>, PayloadHexError>>()?;
+
+    if digits.len() % 2 != 0 {
+        return Err(PayloadHexError::OddLength);
+    }
+
+    let payload: Vec<u8> = digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect();
+    check_packet_length(payload.len()).map_err(PayloadHexError::InvalidLength)?;
+    Ok(payload)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadHexError {
+    InvalidDigit(char),
+    OddLength,
+    InvalidLength(PacketLengthError),
+}
+
+impl Display for PayloadHexError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            PayloadHexError::InvalidDigit(character) => {
+                write!(fmt, "'{}' is not a hex digit", character)
+            }
+            PayloadHexError::OddLength => write!(fmt, "The count of hex digits is odd"),
+            PayloadHexError::InvalidLength(error) => write!(fmt, "{}", error),
+        }
+    }
+}
+
+impl Error for PayloadHexError {}
+
+/// A payload file whose size doesn't fit into the packet length bounds.
+/// It is reported along with its path, because several payload files
+/// can be specified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadFileError {
+    pub path: PathBuf,
+    pub error: PacketLengthError,
+}
+
+impl Display for PayloadFileError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "The payload file {}: {}",
+            self.path.display(),
+            self.error
+        )
+    }
+}
+
+impl Error for PayloadFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// A part of a payload template, either a literal text or a placeholder
+/// filled for each packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    Text(String),
+    Seq,
+    Timestamp,
+}
+
+/// Splits a payload template (such as `GET /ping?n={seq}`) into literal
+/// texts and the `{seq}` and `{timestamp}` placeholders.
+pub fn parse_template(template: &str) -> Result<Vec<TemplatePart>, TemplateError> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(TemplatePart::Text(String::from(&rest[..start])));
+        }
+
+        let end = rest[start..]
+            .find('}')
+            .ok_or(TemplateError::UnclosedPlaceholder)?
+            + start;
+        parts.push(match &rest[start + 1..end] {
+            "seq" => TemplatePart::Seq,
+            "timestamp" => TemplatePart::Timestamp,
+            name => return Err(TemplateError::UnknownPlaceholder(String::from(name))),
+        });
+        rest = &rest[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(String::from(rest)));
+    }
+    Ok(parts)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    UnknownPlaceholder(String),
+    UnclosedPlaceholder,
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholder(name) => write!(
+                fmt,
+                "'{{{}}}' is not a known placeholder, only {{seq}} and {{timestamp}} are",
+                name
+            ),
+            TemplateError::UnclosedPlaceholder => write!(fmt, "A placeholder is not closed"),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+pub fn parse_byte_size(size: &str) -> Result<NonZeroUsize, ByteSizeError> {
+    let size = size.trim().to_lowercase();
+    let digits_end = size
+        .find(|character: char| !character.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, suffix) = size.split_at(digits_end);
+
+    let multiplier: usize = match suffix.trim() {
+        "" | "b" => 1,
+        "kb" => 1024,
+        "mb" => 1024 * 1024,
+        "gb" => 1024 * 1024 * 1024,
+        suffix => return Err(ByteSizeError::InvalidSuffix(suffix.to_string())),
+    };
+    let number: usize = number.parse().map_err(ByteSizeError::InvalidFormat)?;
+
+    let size = number
+        .checked_mul(multiplier)
+        .ok_or(ByteSizeError::Overflow)?;
+    NonZeroUsize::new(size).ok_or(ByteSizeError::ZeroValue)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ByteSizeError {
+    InvalidFormat(ParseIntError),
+    InvalidSuffix(String),
+    Overflow,
+    ZeroValue,
+}
+
+impl Display for ByteSizeError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ByteSizeError::InvalidFormat(error) => write!(fmt, "{}", error),
+            ByteSizeError::InvalidSuffix(suffix) => write!(
+                fmt,
+                "Unknown suffix '{}', expected 'b', 'kb', 'mb', or 'gb'",
+                suffix
+            ),
+            ByteSizeError::Overflow => write!(fmt, "The size is too large"),
+            ByteSizeError::ZeroValue => write!(fmt, "The value equals to zero"),
+        }
+    }
+}
+
+impl Error for ByteSizeError {}
+
+/// Parses a 32-bit magic value, specified either as a decimal number or
+/// as a hex one prefixed by `0x` (such as `0xA11CE`).
+pub fn parse_magic(value: &str) -> Result<u32, ArgsConfigError> {
+    let trimmed = value.trim();
+    let result = match trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => trimmed.parse(),
+    };
+
+    result.map_err(|_| ArgsConfigError::Magic(value.to_string()))
+}
+
+/// Parses a fill byte in the same way as a magic value.
+pub fn parse_fill_byte(value: &str) -> Result<u8, ArgsConfigError> {
+    let trimmed = value.trim();
+    let result = match trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+    {
+        Some(hex) => u8::from_str_radix(hex, 16),
+        None => trimmed.parse(),
+    };
+
+    result.map_err(|_| ArgsConfigError::FillByte(value.to_string()))
+}
+
+/// Parses a socket priority, which the kernel takes as a C `int`.
+pub fn parse_priority(priority: &str) -> Result<u32, ArgsConfigError> {
+    priority
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&priority: &u32| priority <= i32::MAX as u32)
+        .ok_or_else(|| {
+            ArgsConfigError::Priority(format!("'{}' is not a non-negative number", priority))
+        })
+}
+
+/// Parses a count of the probing packets, where zero disables probing.
+pub fn parse_probe_count(count: &str) -> Result<u64, ArgsConfigError> {
+    count
+        .trim()
+        .parse()
+        .map_err(|_| ArgsConfigError::ProbeCount(count.to_string()))
+}
+
+/// Parses a comma-separated list of CPU core indices (such as `0,2,4`).
+/// Whether the cores exist is checked during the validation of the whole
+/// config.
+pub fn parse_cores(list: &str) -> Result<CoreList, ArgsConfigError> {
+    let cores: CoreList = parse_list(list).map_err(|(index, _)| {
+        ArgsConfigError::Cores(format!(
+            "'{}' is not a core index",
+            list_element(list, index)
+        ))
+    })?;
+
+    if cores.is_empty() {
+        return Err(ArgsConfigError::Cores(String::from("the list is empty")));
+    }
+    Ok(cores)
+}
+
+/// Parses a comma-separated list, trimming the whitespace around each of
+/// its elements, where a blank string is an empty list. On failure, the
+/// index of the first invalid element is returned along with its error.
+pub fn parse_list<T: FromStr>(raw: &str) -> Result<Vec<T>, (usize, T::Err)> {
+    parse_list_with(raw, |element| element.parse())
+}
+
+/// The same as `parse_list`, but each element is parsed by `parse`.
+pub fn parse_list_with<T, E, F>(raw: &str, parse: F) -> Result<Vec<T>, (usize, E)>
+where
+    F: Fn(&str) -> Result<T, E>,
+{
+    if raw.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    raw.split(',')
+        .enumerate()
+        .map(|(index, element)| parse(element.trim()).map_err(|error| (index, error)))
+        .collect()
+}
+
+/// Returns the trimmed element `index` of a comma-separated list, so the
+/// failure reported by `parse_list` can name it.
+fn list_element(raw: &str, index: usize) -> &str {
+    raw.split(',').nth(index).unwrap_or_default().trim()
+}
+
+/// Returns the count of the CPU cores configured in the system, which
+/// can be more than the ones available to this process.
+pub fn available_cores() -> usize {
+    #[cfg(unix)]
+    {
+        let count = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_CONF) };
+        if count > 0 {
+            return count as usize;
+        }
+    }
+
+    std::thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+pub fn parse_non_zero_usize(number: &str) -> Result<NonZeroUsize, NonZeroUsizeError> {
+    let number: usize = number.parse().map_err(NonZeroUsizeError::InvalidFormat)?;
+
+    NonZeroUsize::new(number).ok_or(NonZeroUsizeError::ZeroValue)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonZeroUsizeError {
+    InvalidFormat(ParseIntError),
+    ZeroValue,
+}
+
+impl Display for NonZeroUsizeError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            NonZeroUsizeError::InvalidFormat(error) => write!(fmt, "{}", error),
+            NonZeroUsizeError::ZeroValue => write!(fmt, "The value equals to zero"),
+        }
+    }
+}
+
+impl Error for NonZeroUsizeError {}
+
+// Durations are serialized as humantime strings (such as "30s") to keep
+// configuration files human-editable
+#[cfg(feature = "serde")]
+mod serde_duration {
+    use std::time::Duration;
+
+    use humantime::format_duration;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_duration(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        super::parse_duration(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_option_duration {
+    use std::time::Duration;
+
+    use humantime::format_duration;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.collect_str(&format_duration(*duration)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(duration) => super::parse_duration(&duration)
+                .map(Some)
+                .map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_level_filter {
+    use log::LevelFilter;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(level: &LevelFilter, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(level)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<LevelFilter, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// A single sender is still accepted as a string, as before the
+/// senders became a list.
+#[cfg(feature = "serde")]
+mod serde_senders {
+    use std::net::SocketAddr;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Senders {
+        One(SocketAddr),
+        Many(Vec<SocketAddr>),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<SocketAddr>, D::Error> {
+        match Senders::deserialize(deserializer)? {
+            Senders::One(sender) => Ok(vec![sender]),
+            Senders::Many(ref senders) if senders.is_empty() => {
+                Err(D::Error::custom("Expected at least one sender"))
+            }
+            Senders::Many(senders) => Ok(senders),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_packet_length {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<usize>, D::Error> {
+        match Option::<usize>::deserialize(deserializer)? {
+            Some(length) => super::check_length_overflow(length)
+                .map(Some)
+                .map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_length_range {
+    use std::num::NonZeroUsize;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<(NonZeroUsize, NonZeroUsize)>, D::Error> {
+        match Option::<(NonZeroUsize, NonZeroUsize)>::deserialize(deserializer)? {
+            Some((min, max)) => super::check_length_range(min, max)
+                .map(Some)
+                .map_err(D::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_non_zero_usize() {
+        unsafe {
+            // Check that ordinary values are parsed correctly
+            assert_eq!(
+                parse_non_zero_usize("1"),
+                Ok(NonZeroUsize::new_unchecked(1))
+            );
+            assert_eq!(
+                parse_non_zero_usize("3"),
+                Ok(NonZeroUsize::new_unchecked(3))
+            );
+            assert_eq!(
+                parse_non_zero_usize("26655"),
+                Ok(NonZeroUsize::new_unchecked(26655))
+            );
+            assert_eq!(
+                parse_non_zero_usize("+75"),
+                Ok(NonZeroUsize::new_unchecked(75))
+            );
+        }
+    }
+
+    #[test]
+    fn parses_invalid_non_zero_usize() {
+        let panic_if_invalid = |string| {
+            if parse_non_zero_usize(string).is_ok() {
+                panic!("Parses invalid formatted usize correctly");
+            }
+        };
+
+        // Invalid numbers must produce the invalid format error
+        panic_if_invalid("   ");
+
+        panic_if_invalid("abc5653odr!");
+        panic_if_invalid("6485&02hde");
+
+        panic_if_invalid("-565642");
+        panic_if_invalid(&"2178".repeat(50));
+
+        // Check that the zero value is not allowed
+        assert_eq!(parse_non_zero_usize("0"), Err(NonZeroUsizeError::ZeroValue));
+    }
+
+    #[test]
+    fn reads_all_fields() {
+        let config = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--sender",
+            "127.0.0.1:5684",
+            "--duration",
+            "3min",
+            "--length",
+            "1472",
+            "--wait",
+            "2s",
+            "--send-periodicity",
+            "10ms",
+            "--display-periodicity",
+            "150",
+            "--packets",
+            "6000",
+            "--send-timeout",
+            "5s",
+            "--seed",
+            "42",
+            "--random-payload",
+            "--debug",
+        ])
+        .expect("Cannot parse valid arguments");
+
+        let secs = Duration::from_secs;
+        assert_eq!(
+            config.receiver,
+            vec![(1, "127.0.0.1:1280".parse().unwrap())]
+        );
+        assert_eq!(config.sender, ["127.0.0.1:5684".parse().unwrap()]);
+        assert_eq!(config.duration, Some(secs(180)));
+        assert_eq!(config.length, Some(1472));
+        assert_eq!(config.packet_length(), 1472);
+        assert_eq!(config.wait, secs(2));
+        assert_eq!(config.warmup, secs(0));
+        assert_eq!(config.send_periodicity, Duration::from_millis(10));
+        assert_eq!(config.display_periodicity.get(), 150);
+        assert_eq!(config.packets.get(), 6000);
+        assert_eq!(config.send_timeout, Some(secs(5)));
+        assert_eq!(config.seed, Some(42));
+        assert!(config.send_file.is_empty());
+        assert_eq!(config.payload_hex, None);
+        assert!(!config.repeat_payload);
+        assert_eq!(config.rate, None);
+        assert_eq!(config.bandwidth, None);
+        assert_eq!(config.ramp_up, secs(0));
+        assert_eq!(config.jitter, secs(0));
+        assert!(config.random_payload);
+        assert!(config.debug);
+    }
+
+    #[test]
+    fn builds_valid_config() {
+        let config = ArgsConfigBuilder::new()
+            .receiver("127.0.0.1:1280".parse().unwrap())
+            .receiver("127.0.0.1:1281".parse().unwrap())
+            .sender("127.0.0.1:5684".parse().unwrap())
+            .duration(Duration::from_secs(30))
+            .length(1472)
+            .expect("Cannot set a valid length")
+            .wait(Duration::from_secs(0))
+            .send_periodicity(Duration::from_millis(5))
+            .build()
+            .expect("Cannot build a valid config");
+
+        assert_eq!(
+            config
+                .receiver_addresses()
+                .cloned()
+                .collect::<Vec<SocketAddr>>(),
+            vec![
+                "127.0.0.1:1280".parse::<SocketAddr>().unwrap(),
+                "127.0.0.1:1281".parse().unwrap()
+            ]
+        );
+        assert_eq!(config.sender, ["127.0.0.1:5684".parse().unwrap()]);
+        assert_eq!(config.duration, Some(Duration::from_secs(30)));
+        assert_eq!(config.packet_length(), 1472);
+        assert_eq!(config.wait, Duration::from_secs(0));
+        assert_eq!(config.send_periodicity, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn builds_config_with_defaults() {
+        let receiver = "127.0.0.1:1280";
+        let config = ArgsConfigBuilder::new()
+            .receiver(receiver.parse().unwrap())
+            .sender("127.0.0.1:5684".parse().unwrap())
+            .build()
+            .expect("Cannot build a config with defaults");
+
+        let mut expected = ArgsConfig::from_iter_safe(vec!["anevicon", "-r", receiver]).unwrap();
+        expected.sender = vec!["127.0.0.1:5684".parse().unwrap()];
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn builds_invalid_config() {
+        assert_eq!(
+            ArgsConfigBuilder::new()
+                .length(ABSOLUTE_MAX_PACKET_LENGTH + 1)
+                .expect_err("Sets an out-of-range length"),
+            PacketLengthError::Overflow
+        );
+        assert_eq!(
+            ArgsConfigBuilder::new().length(0).unwrap_err(),
+            PacketLengthError::Underflow
+        );
+
+        assert_eq!(
+            ArgsConfigBuilder::new().build(),
+            Err(ArgsConfigError::NoReceivers)
+        );
+        assert!(ArgsConfigBuilder::new()
+            .receiver("[::1]:1280".parse().unwrap())
+            .sender("127.0.0.1:5684".parse().unwrap())
+            .build()
+            .is_err());
+    }
+
+    fn config_file(name: &str, content: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("anevicon-{}-{}.toml", std::process::id(), name));
+        fs::write(&path, content).expect("Cannot write the testing config file");
+        path
+    }
+
+    fn from_args(args: Vec<&str>) -> Result<ArgsConfig, ArgsConfigError> {
+        ArgsConfig::from_matches(
+            &ArgsConfig::clap()
+                .get_matches_from_safe(args)
+                .expect("The command-line arguments are incorrectly specified"),
+        )
+    }
+
+    #[test]
+    fn loads_full_config_file() {
+        let path = config_file(
+            "full",
+            r#"
+                receiver = ["127.0.0.1:1280", "127.0.0.1:1281"]
+                sender = "127.0.0.1:5684"
+                duration = "3min"
+                length = 1472
+                wait = "0s"
+                send-periodicity = "10ms"
+            "#,
+        );
+
+        let config = from_args(vec!["anevicon", "--config", path.to_str().unwrap()])
+            .expect("Cannot load a valid config file");
+        assert_eq!(
+            config
+                .receiver_addresses()
+                .cloned()
+                .collect::<Vec<SocketAddr>>(),
+            vec![
+                "127.0.0.1:1280".parse::<SocketAddr>().unwrap(),
+                "127.0.0.1:1281".parse().unwrap()
+            ]
+        );
+        assert_eq!(config.sender, ["127.0.0.1:5684".parse().unwrap()]);
+        assert_eq!(config.duration, Some(Duration::from_secs(180)));
+        assert_eq!(config.length, Some(1472));
+        assert_eq!(config.wait, Duration::from_secs(0));
+        assert_eq!(config.send_periodicity, Duration::from_millis(10));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn loads_invalid_config_file() {
+        // The receiver is required either in the file or on the command line
+        let path = config_file("missing", r#"sender = "127.0.0.1:5684""#);
+        assert_eq!(
+            from_args(vec!["anevicon", "--config", path.to_str().unwrap()]),
+            Err(ArgsConfigError::NoReceivers)
+        );
+        fs::remove_file(path).unwrap();
+
+        for (name, content) in &[
+            ("length", "receiver = \"127.0.0.1:1280\"\nlength = 65528"),
+            ("address", "receiver = \"127.0.0.300:1280\""),
+            ("key", "receiver = \"127.0.0.1:1280\"\nlenght = 1472"),
+            ("syntax", "receiver = "),
+        ] {
+            let path = config_file(name, content);
+            match from_args(vec!["anevicon", "--config", path.to_str().unwrap()]) {
+                Err(ArgsConfigError::ConfigFile(_)) => (),
+                result => panic!("Loads an invalid config file '{}': {:?}", name, result),
+            }
+            fs::remove_file(path).unwrap();
+        }
+
+        let path = config_file("negative", "receiver = \"127.0.0.1:1280\"\nlength = -5");
+        match from_args(vec!["anevicon", "--config", path.to_str().unwrap()]) {
+            Err(ArgsConfigError::ConfigFile(message)) => {
+                assert!(
+                    message.ends_with(": length must be positive"),
+                    "{}",
+                    message
+                )
+            }
+            result => panic!("Loads a negative length: {:?}", result),
+        }
+        fs::remove_file(path).unwrap();
+
+        match from_args(vec!["anevicon", "--config", "/nonexistent/anevicon.toml"]) {
+            Err(ArgsConfigError::ConfigFile(_)) => (),
+            result => panic!("Loads a nonexistent config file: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn overrides_config_file() {
+        let path = config_file(
+            "override",
+            r#"
+                receiver = "127.0.0.1:1280"
+                duration = "3min"
+                length = 1472
+            "#,
+        );
+
+        let config = from_args(vec![
+            "anevicon",
+            "--config",
+            path.to_str().unwrap(),
+            "--receiver",
+            "127.0.0.1:9000",
+            "--length",
+            "512",
+        ])
+        .expect("Cannot load a valid config file");
+
+        // The command-line values win, the others are taken from the file
+        assert_eq!(
+            config.receiver,
+            vec![(1, "127.0.0.1:9000".parse().unwrap())]
+        );
+        assert_eq!(config.length, Some(512));
+        assert_eq!(config.duration, Some(Duration::from_secs(180)));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parses_packet_length() {
+        assert_eq!(parse_packet_length("1"), Ok(NonZeroUsize::new(1).unwrap()));
+        assert_eq!(
+            parse_packet_length("65507"),
+            Ok(NonZeroUsize::new(MAX_PACKET_LENGTH).unwrap())
+        );
+
+        assert_eq!(parse_packet_length("0"), Err(PacketLengthError::Underflow));
+        assert_eq!(
+            parse_packet_length("65528"),
+            Err(PacketLengthError::Overflow)
+        );
+        assert_eq!(
+            PacketLengthError::Overflow.to_string(),
+            "The packet length is greater than 65527 bytes"
+        );
+        assert!(parse_packet_length("abc").is_err());
+
+        // A bare integer is specified in bytes, as well as the one with a suffix
+        assert_eq!(
+            parse_packet_length("1472"),
+            Ok(NonZeroUsize::new(1472).unwrap())
+        );
+        assert_eq!(
+            parse_packet_length("1kb"),
+            Ok(NonZeroUsize::new(1024).unwrap())
+        );
+        assert_eq!(
+            parse_packet_length(" 60 KB "),
+            Ok(NonZeroUsize::new(60 * 1024).unwrap())
+        );
+        assert_eq!(
+            parse_packet_length("512b"),
+            Ok(NonZeroUsize::new(512).unwrap())
+        );
+        assert_eq!(
+            parse_packet_length("64kb"),
+            Err(PacketLengthError::Overflow)
+        );
+        assert_eq!(parse_packet_length("1mb"), Err(PacketLengthError::Overflow));
+        assert_eq!(
+            parse_packet_length("0kb"),
+            Err(PacketLengthError::Underflow)
+        );
+        assert_eq!(
+            parse_packet_length("nonsense"),
+            Err(PacketLengthError::InvalidSuffix(String::from("nonsense")))
+        );
+        assert_eq!(
+            parse_packet_length("10tb"),
+            Err(PacketLengthError::InvalidSuffix(String::from("tb")))
+        );
+        assert!(parse_packet_length("kb").is_err());
+    }
+
+    #[test]
+    fn parses_seed() {
+        let config = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--seed",
+            "18446744073709551615",
+        ])
+        .expect("Cannot parse a valid seed");
+        assert_eq!(config.seed, Some(u64::MAX));
+
+        for seed in &["abc", "-5", "18446744073709551616"] {
+            assert!(
+                ArgsConfig::from_iter_safe(vec![
+                    "anevicon",
+                    "--receiver",
+                    "127.0.0.1:1280",
+                    "--seed",
+                    seed,
+                ])
+                .is_err(),
+                "Parses an invalid seed '{}' correctly",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn parses_threads() {
+        let parse_threads = |args: Vec<&str>| {
+            ArgsConfig::from_iter_safe(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args),
+            )
+        };
+
+        assert_eq!(parse_threads(vec![]).unwrap().threads.get(), 1);
+        assert_eq!(
+            parse_threads(vec!["--threads", "8"]).unwrap().threads.get(),
+            8
+        );
+        assert!(parse_threads(vec!["--threads", "0"]).is_err());
+        assert!(parse_threads(vec!["--threads", "many"]).is_err());
+    }
+
+    #[test]
+    fn parses_allowed_errors() {
+        let parse_errors = |args: Vec<&str>| {
+            ArgsConfig::from_iter_safe(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args),
+            )
+        };
+
+        assert_eq!(parse_errors(vec![]).unwrap().allowed_errors, 0);
+        assert_eq!(
+            parse_errors(vec!["--allow-errors", "100"])
+                .unwrap()
+                .allowed_errors,
+            100
+        );
+        assert!(parse_errors(vec!["--allow-errors", "-1"]).is_err());
+        assert!(parse_errors(vec!["--allow-errors", "some"]).is_err());
+    }
+
+    #[test]
+    fn parses_probe_count() {
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        assert_eq!(config.probe_count, 16);
+
+        assert_eq!(parse_probe_count("0"), Ok(0));
+        assert_eq!(parse_probe_count("100"), Ok(100));
+        assert_eq!(
+            parse_probe_count("-1"),
+            Err(ArgsConfigError::ProbeCount(String::from("-1")))
+        );
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--probe-count",
+            "some",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_retries() {
+        let parse_retries = |args: Vec<&str>| {
+            ArgsConfig::from_iter_safe(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args),
+            )
+        };
+
+        assert_eq!(parse_retries(vec![]).unwrap().retries, 0);
+        assert_eq!(parse_retries(vec!["--retries", "5"]).unwrap().retries, 5);
+        assert!(parse_retries(vec!["--retries", "-1"]).is_err());
+        assert!(parse_retries(vec!["--retries", "4294967296"]).is_err());
+    }
+
+    #[test]
+    fn parses_batch_size() {
+        let parse_batch_size = |size: &str| {
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--batch-size",
+                size,
+            ])
+        };
+
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        assert_eq!(config.batch_size, 1);
+        assert_eq!(parse_batch_size("64").unwrap().batch_size, 64);
+        assert_eq!(parse_batch_size("1024").unwrap().batch_size, 1024);
+
+        assert_eq!(parse_batch_size("0"), Err(ArgsConfigError::BatchSize(0)));
+        assert_eq!(
+            parse_batch_size("1025"),
+            Err(ArgsConfigError::BatchSize(1025))
+        );
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--batch-size",
+            "-1"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_fail_under() {
+        let parse_fail_under = |percent: &str| {
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--fail-under",
+                percent,
+            ])
+        };
+
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        assert_eq!(config.fail_under, None);
+        assert_eq!(parse_fail_under("99.5").unwrap().fail_under, Some(99.5));
+        assert_eq!(parse_fail_under("0").unwrap().fail_under, Some(0.0));
+        assert_eq!(parse_fail_under("100").unwrap().fail_under, Some(100.0));
+
+        assert_eq!(
+            parse_fail_under("100.1"),
+            Err(ArgsConfigError::FailUnder(100.1))
+        );
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--fail-under=-1"
+            ]),
+            Err(ArgsConfigError::FailUnder(-1.0))
+        );
+        assert!(matches!(
+            parse_fail_under("NaN"),
+            Err(ArgsConfigError::FailUnder(percent)) if percent.is_nan()
+        ));
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--fail-under",
+            "most"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_label() {
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        assert_eq!(config.label, None);
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--label",
+            "nightly",
+        ])
+        .unwrap();
+        assert_eq!(config.label, Some(String::from("nightly")));
+
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--label",
+                ""
+            ]),
+            Err(ArgsConfigError::Label)
+        );
+    }
+
+    #[test]
+    fn parses_broadcast() {
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        assert!(!config.broadcast);
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "255.255.255.255:1280",
+            "--broadcast",
+        ])
+        .expect("Cannot parse a broadcast receiver");
+        assert!(config.broadcast);
+
+        assert_eq!(
+            from_args(vec!["anevicon", "--receiver", "255.255.255.255:1280"]),
+            Err(ArgsConfigError::BroadcastRequired(
+                "255.255.255.255:1280".parse().unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_length_distribution() {
+        assert_eq!("uniform".parse(), Ok(LengthDistribution::Uniform));
+        assert_eq!("Normal".parse(), Ok(LengthDistribution::Normal));
+        assert_eq!(
+            "gauss".parse::<LengthDistribution>(),
+            Err(LengthDistributionError(String::from("gauss")))
+        );
+
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        assert_eq!(config.length_distribution, LengthDistribution::Uniform);
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length-range",
+            "10:20",
+            "--length-distribution",
+            "normal",
+        ])
+        .unwrap();
+        assert_eq!(config.length_distribution, LengthDistribution::Normal);
+
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length-distribution",
+            "gauss"
+        ])
+        .is_err());
+
+        // Only the random lengths can be distributed
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--length-distribution",
+                "normal"
+            ]),
+            Err(ArgsConfigError::LengthDistribution(
+                LengthDistribution::Normal
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_output_format() {
+        assert_eq!("human".parse(), Ok(OutputFormat::Human));
+        assert_eq!("json".parse(), Ok(OutputFormat::Json));
+        assert_eq!("JSON".parse(), Ok(OutputFormat::Json));
+        assert_eq!(
+            "jsno".parse::<OutputFormat>(),
+            Err(OutputFormatError(String::from("jsno")))
+        );
+
+        let config = ArgsConfig::from_iter_safe(vec!["anevicon", "-r", "127.0.0.1:1280"]).unwrap();
+        assert_eq!(config.output_format, OutputFormat::Human);
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "-r",
+            "127.0.0.1:1280",
+            "--output-format",
+            "xml",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_csv_path() {
+        let path = std::env::temp_dir().join(format!("anevicon-{}-config.csv", std::process::id()));
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--csv",
+            path.to_str().unwrap(),
+        ])
+        .expect("Cannot parse a valid CSV path");
+        assert_eq!(config.csv_path, Some(path.clone()));
+        assert!(!path.exists());
+
+        match from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--csv",
+            "/nonexistent/anevicon.csv",
+        ]) {
+            Err(ArgsConfigError::Csv(_)) => (),
+            result => panic!("Opens a CSV file in a nonexistent directory: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parses_report_interval() {
+        let parse_interval = |args: Vec<&str>| {
+            ArgsConfig::from_iter_safe(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args),
+            )
+        };
+
+        assert_eq!(
+            parse_interval(vec![]).unwrap().report_interval,
+            Duration::from_secs(0)
+        );
+        assert_eq!(
+            parse_interval(vec!["--report-interval", "10s"])
+                .unwrap()
+                .report_interval,
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            parse_interval(vec!["--report-interval", "0s"])
+                .unwrap()
+                .report_interval,
+            Duration::from_secs(0)
+        );
+        assert!(parse_interval(vec!["--report-interval", "often"]).is_err());
+    }
+
+    #[test]
+    fn parses_max_runtime() {
+        assert_eq!(parse_max_runtime("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(
+            parse_max_runtime("0s"),
+            Err(ArgsConfigError::MaxRuntime(String::from(
+                "it must be greater than zero"
+            )))
+        );
+        match parse_max_runtime("forever") {
+            Err(ArgsConfigError::MaxRuntime(_)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--duration",
+            "0s",
+            "--max-runtime",
+            "10min",
+        ])
+        .unwrap();
+        assert_eq!(config.max_runtime, Some(Duration::from_secs(600)));
+        assert!(config.to_string().contains("max-runtime: 10m,"));
+
+        // The hard limit only shortens the attack
+        assert_eq!(config.effective_duration(), Some(Duration::from_secs(600)));
+        let config = ArgsConfig {
+            duration: Some(Duration::from_secs(60)),
+            ..config
+        };
+        assert_eq!(config.effective_duration(), Some(Duration::from_secs(60)));
+        let config = ArgsConfig {
+            max_runtime: None,
+            ..config
+        };
+        assert_eq!(config.effective_duration(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn requires_burst_pair() {
+        let parse_bursts = |args: Vec<&str>| {
+            from_args(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args)
+                    .collect(),
+            )
+        };
+
+        let config = parse_bursts(vec![]).unwrap();
+        assert_eq!((config.burst_on, config.burst_off), (None, None));
+
+        let config = parse_bursts(vec!["--burst-on", "2s", "--burst-off", "3s"]).unwrap();
+        assert_eq!(config.burst_on, Some(Duration::from_secs(2)));
+        assert_eq!(config.burst_off, Some(Duration::from_secs(3)));
+        assert!(config.to_string().contains("burst-on: 2s, burst-off: 3s,"));
+
+        assert_eq!(
+            parse_bursts(vec!["--burst-on", "2s"]),
+            Err(ArgsConfigError::BurstPairing)
+        );
+        assert_eq!(
+            parse_bursts(vec!["--burst-off", "3s"]),
+            Err(ArgsConfigError::BurstPairing)
+        );
+        assert_eq!(
+            parse_bursts(vec!["--burst-on", "0s", "--burst-off", "3s"]),
+            Err(ArgsConfigError::BurstOn)
+        );
+        assert!(parse_bursts(vec!["--burst-on", "2s", "--burst-off", "0s"]).is_ok());
+    }
+
+    #[test]
+    fn parses_recreate_every() {
+        let parse_recreate_every = |args: Vec<&str>| {
+            from_args(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args)
+                    .collect(),
+            )
+        };
+
+        assert_eq!(parse_recreate_every(vec![]).unwrap().recreate_every, None);
+        assert_eq!(
+            parse_recreate_every(vec!["--recreate-socket-every", "5min"])
+                .unwrap()
+                .recreate_every,
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            parse_recreate_every(vec!["--recreate-socket-every", "0s"]),
+            Err(ArgsConfigError::RecreateEvery)
+        );
+
+        // A malformed periodicity is rejected by the argument parser itself
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--recreate-socket-every",
+            "often"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_send_timeout() {
+        let parse_timeout = |args: Vec<&str>| {
+            from_args(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args)
+                    .collect(),
+            )
+        };
+
+        assert_eq!(parse_timeout(vec![]).unwrap().send_timeout, None);
+        assert_eq!(
+            parse_timeout(vec!["--send-timeout", "1s 500ms"])
+                .unwrap()
+                .send_timeout,
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            parse_timeout(vec!["--send-timeout", "0s"]),
+            Err(ArgsConfigError::SendTimeout)
+        );
+
+        // A malformed timeout is rejected by the argument parser itself
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--send-timeout",
+            "soon",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_ttl() {
+        let parse_ttl = |args: Vec<&str>| {
+            ArgsConfig::from_iter_safe(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args),
+            )
+        };
+
+        assert_eq!(parse_ttl(vec![]).unwrap().ttl, None);
+        assert_eq!(parse_ttl(vec!["--ttl", "64"]).unwrap().ttl, Some(64));
+        assert!(parse_ttl(vec!["--ttl", "4294967296"]).is_err());
+        assert!(parse_ttl(vec!["--ttl", "hops"]).is_err());
+    }
+
+    #[test]
+    fn rejects_ttl_out_of_range() {
+        let parse_ttl = |ttl| {
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--ttl",
+                ttl,
+            ])
+        };
+
+        assert_eq!(parse_ttl("1").unwrap().ttl, Some(1));
+        assert_eq!(parse_ttl("255").unwrap().ttl, Some(255));
+        assert_eq!(parse_ttl("0"), Err(ArgsConfigError::Ttl(0)));
+        assert_eq!(parse_ttl("256"), Err(ArgsConfigError::Ttl(256)));
+    }
+
+    #[test]
+    fn parses_send_buffer() {
+        let parse_buffer = |size| {
+            ArgsConfig::from_iter_safe(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--send-buffer",
+                size,
+            ])
+        };
+
+        assert_eq!(
+            parse_buffer("212992").unwrap().send_buffer,
+            NonZeroUsize::new(212_992)
+        );
+        assert_eq!(
+            parse_buffer("4MB").unwrap().send_buffer,
+            NonZeroUsize::new(4 * 1024 * 1024)
+        );
+        assert!(parse_buffer("4XB").is_err());
+    }
+
+    #[test]
+    fn parses_byte_limit() {
+        let parse_limit = |args: Vec<&str>| {
+            ArgsConfig::from_iter_safe(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args),
+            )
+        };
+
+        assert_eq!(parse_limit(vec![]).unwrap().byte_limit, None);
+        assert_eq!(
+            parse_limit(vec!["--bytes", "10MB"]).unwrap().byte_limit,
+            Some(10 * 1024 * 1024)
+        );
+        assert_eq!(
+            parse_limit(vec!["--bytes", "1472"]).unwrap().byte_limit,
+            Some(1472)
+        );
+        assert!(parse_limit(vec!["--bytes", "0"]).is_err());
+        assert!(parse_limit(vec!["--bytes", "10tb"]).is_err());
+    }
+
+    #[test]
+    fn parses_packets_per_second() {
+        let parse_rate = |rate| {
+            ArgsConfig::from_iter_safe(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--packets-per-second",
+                rate,
+            ])
+        };
+
+        assert_eq!(
+            parse_rate("1500").expect("Cannot parse a valid rate").rate,
+            NonZeroUsize::new(1500)
+        );
+        assert!(parse_rate("0").is_err());
+        assert!(parse_rate("fast").is_err());
+
+        // The rate and the sending periodicity cannot be used together
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--packets-per-second",
+            "1500",
+            "--send-periodicity",
+            "1s",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_length_range() {
+        let length = |length| NonZeroUsize::new(length).unwrap();
+
+        assert_eq!(
+            parse_length_range("64:1472"),
+            Ok((length(64), length(1472)))
+        );
+        assert_eq!(
+            parse_length_range("100:100"),
+            Ok((length(100), length(100)))
+        );
+
+        assert_eq!(
+            parse_length_range("1472:64"),
+            Err(PacketLengthError::InvalidRange)
+        );
+        assert_eq!(
+            parse_length_range("64"),
+            Err(PacketLengthError::InvalidRange)
+        );
+        assert_eq!(
+            parse_length_range("0:64"),
+            Err(PacketLengthError::Underflow)
+        );
+        assert_eq!(
+            parse_length_range("64:65528"),
+            Err(PacketLengthError::Overflow)
+        );
+
+        // A fixed length cannot be specified together with a range
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length-range",
+            "64:1472",
+            "--length",
+            "100",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_valid_byte_size() {
+        let size = |size| NonZeroUsize::new(size).unwrap();
+
+        assert_eq!(parse_byte_size("1472"), Ok(size(1472)));
+        assert_eq!(parse_byte_size("512b"), Ok(size(512)));
+        assert_eq!(parse_byte_size("10kb"), Ok(size(10 * 1024)));
+        assert_eq!(parse_byte_size("50MB"), Ok(size(50 * 1024 * 1024)));
+        assert_eq!(parse_byte_size("1GB"), Ok(size(1024 * 1024 * 1024)));
+        assert_eq!(parse_byte_size(" 3 Mb "), Ok(size(3 * 1024 * 1024)));
+    }
+
+    #[test]
+    fn parses_invalid_byte_size() {
+        assert_eq!(
+            parse_byte_size("10tb"),
+            Err(ByteSizeError::InvalidSuffix(String::from("tb")))
+        );
+        assert_eq!(parse_byte_size("0kb"), Err(ByteSizeError::ZeroValue));
+        assert_eq!(
+            parse_byte_size(&format!("{}gb", usize::MAX)),
+            Err(ByteSizeError::Overflow)
+        );
+
+        match parse_byte_size("mb") {
+            Err(ByteSizeError::InvalidFormat(_)) => (),
+            result => panic!("Parses a size without a number: {:?}", result),
+        }
+        assert!(parse_byte_size("nonsense").is_err());
+    }
+
+    #[test]
+    fn parses_valid_receivers() {
+        let parse_receivers = |args: Vec<&str>| {
+            ArgsConfig::from_iter_safe(args)
+                .expect("Cannot parse valid receivers")
+                .receiver_addresses()
+                .cloned()
+                .collect::<Vec<SocketAddr>>()
+        };
+
+        assert_eq!(
+            parse_receivers(vec!["anevicon", "--receiver", "127.0.0.1:1280"]),
+            vec!["127.0.0.1:1280".parse::<SocketAddr>().unwrap()]
+        );
+
+        let expected: Vec<SocketAddr> = vec![
+            "127.0.0.1:1280".parse().unwrap(),
+            "10.0.0.2:53".parse().unwrap(),
+            "[::1]:8080".parse().unwrap(),
+        ];
+
+        // Both repeated occurrences and comma-separated lists are allowed
+        assert_eq!(
+            parse_receivers(vec![
+                "anevicon",
+                "-r",
+                "127.0.0.1:1280",
+                "-r",
+                "10.0.0.2:53",
+                "-r",
+                "[::1]:8080",
+            ]),
+            expected
+        );
+        assert_eq!(
+            parse_receivers(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280,10.0.0.2:53,[::1]:8080",
+            ]),
+            expected
+        );
+    }
+
+    #[test]
+    fn parses_weighted_receivers() {
+        let address = |address: &str| address.parse::<SocketAddr>().unwrap();
+
+        assert_eq!(
+            parse_weighted_receiver("3:10.0.0.1:80"),
+            Ok((3, address("10.0.0.1:80")))
+        );
+        assert_eq!(
+            parse_weighted_receiver("10.0.0.1:80"),
+            Ok((1, address("10.0.0.1:80")))
+        );
+        assert_eq!(
+            parse_weighted_receiver("2:[::1]:53"),
+            Ok((2, address("[::1]:53")))
+        );
+        assert!(parse_weighted_receiver("2:localhost:53").is_ok());
+        match parse_weighted_receiver("4294967296:10.0.0.1:80") {
+            Err(SocketAddrError::InvalidWeight(receiver, _)) => {
+                assert_eq!(receiver, "4294967296:10.0.0.1:80")
+            }
+            result => panic!("Parses an overflowed weight: {:?}", result),
+        }
+        assert!(parse_weighted_receiver("heavy:10.0.0.1:80").is_err());
+        assert!(parse_weighted_receiver("-1:10.0.0.1:80").is_err());
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "3:10.0.0.1:80",
+            "--receiver",
+            "10.0.0.2:80",
+        ])
+        .expect("Cannot parse weighted receivers");
+        assert_eq!(
+            config.receiver,
+            vec![(3, address("10.0.0.1:80")), (1, address("10.0.0.2:80"))]
+        );
+        assert!(config
+            .to_string()
+            .starts_with("receiver: 3:10.0.0.1:80, 10.0.0.2:80,"));
+
+        assert_eq!(
+            from_args(vec!["anevicon", "--receiver", "0:10.0.0.1:80"]),
+            Err(ArgsConfigError::ReceiverWeight(address("10.0.0.1:80")))
+        );
+    }
+
+    #[test]
+    fn parses_invalid_receivers() {
+        let error = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280,10.0.0.300:53",
+        ])
+        .expect_err("Parses a malformed receiver correctly");
+        assert!(error.message.contains("10.0.0.300:53"));
+
+        // At least one receiver must be specified
+        assert!(ArgsConfig::from_iter_safe(vec!["anevicon"]).is_err());
+        assert!(ArgsConfig::from_iter_safe(vec!["anevicon", "--receiver", ""]).is_err());
+    }
+
+    #[test]
+    fn expands_receiver_cidr() {
+        let hosts = |addresses: &[&str]| {
+            addresses
+                .iter()
+                .map(|address| address.parse().unwrap())
+                .collect::<Vec<SocketAddr>>()
+        };
+
+        // The network and the broadcast addresses aren't hosts
+        assert_eq!(
+            expand_cidr("10.0.0.0/30:80"),
+            Ok(hosts(&["10.0.0.1:80", "10.0.0.2:80"]))
+        );
+        assert_eq!(
+            expand_cidr("10.0.0.7/31:80"),
+            Ok(hosts(&["10.0.0.6:80", "10.0.0.7:80"]))
+        );
+        assert_eq!(expand_cidr("10.0.0.5/32:53"), Ok(hosts(&["10.0.0.5:53"])));
+        assert_eq!(
+            expand_cidr("[fd00::/127]:80"),
+            Ok(hosts(&["[fd00::]:80", "[fd00::1]:80"]))
+        );
+        assert_eq!(expand_cidr("10.0.0.0/16:80").unwrap().len(), 65534);
+
+        // A long list of receivers is displayed only partially
+        let config = from_args(vec!["anevicon", "--receiver-cidr", "10.0.0.0/16:80"]).unwrap();
+        assert!(config
+            .to_string()
+            .starts_with("receiver: 10.0.0.1:80, 10.0.0.2:80, "));
+        assert!(config.to_string().contains(", and 65518 more, "));
+
+        // The ranges are merged with the explicit receivers
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--receiver-cidr",
+            "10.0.0.0/30:80",
+        ])
+        .expect("Cannot parse a valid receiver range");
+        assert_eq!(
+            config.receiver_addresses().cloned().collect::<Vec<_>>(),
+            hosts(&["127.0.0.1:1280", "10.0.0.1:80", "10.0.0.2:80"])
+        );
+
+        // A range alone is enough to specify the receivers
+        let config = from_args(vec!["anevicon", "--receiver-cidr", "10.0.0.0/30:80"]).unwrap();
+        assert_eq!(config.receiver.len(), 2);
+    }
+
+    #[test]
+    fn rejects_invalid_receiver_cidr() {
+        assert_eq!(expand_cidr("10.0.0.0/30"), Err(CidrError::InvalidFormat));
+        assert_eq!(expand_cidr("10.0.0.0:80"), Err(CidrError::InvalidFormat));
+        assert_eq!(
+            expand_cidr("10.0.0.0/33:80"),
+            Err(CidrError::InvalidPrefix(String::from("33")))
+        );
+        assert_eq!(
+            expand_cidr("10.0.0.0/29:0"),
+            Err(CidrError::InvalidPort(String::from("0")))
+        );
+        assert!(matches!(
+            expand_cidr("10.0.300.0/29:80"),
+            Err(CidrError::InvalidAddress(_))
+        ));
+
+        // Over-broad ranges are rejected before they are expanded
+        assert_eq!(expand_cidr("10.0.0.0/15:80"), Err(CidrError::TooBroad(15)));
+        assert_eq!(expand_cidr("[fd00::/64]:80"), Err(CidrError::TooBroad(64)));
+
+        assert_eq!(
+            from_args(vec!["anevicon", "--receiver-cidr", "10.0.0.0/8:80"]),
+            Err(ArgsConfigError::ReceiverCidr(
+                String::from("10.0.0.0/8:80"),
+                CidrError::TooBroad(8)
+            ))
+        );
+    }
+
+    #[test]
+    fn resolves_hostnames() {
+        let address = parse_socket_addr("localhost:8080").expect("Cannot resolve localhost");
+        assert!(address.ip().is_loopback());
+        assert_eq!(address.port(), 8080);
+
+        // The same resolution must be applied to the sender as well
+        let config = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "localhost:8080",
+            "--sender",
+            "localhost:0",
+        ])
+        .expect("Cannot parse hostnames");
+        assert!(config.receiver[0].1.ip().is_loopback());
+        assert!(config.sender[0].ip().is_loopback());
+    }
+
+    #[test]
+    fn distinguishes_resolution_failures() {
+        // The .invalid top-level domain is guaranteed not to resolve
+        match parse_socket_addr("anevicon.invalid:80") {
+            Err(SocketAddrError::Resolve(address, _)) => assert_eq!(address, "anevicon.invalid:80"),
+            result => panic!("Resolves an invalid hostname: {:?}", result),
+        }
+
+        for address in &["localhost", "localhost:port", ":80", "10.0.0.1:65536"] {
+            match parse_socket_addr(address) {
+                Err(SocketAddrError::InvalidFormat(_, _)) => (),
+                result => panic!("Parses a malformed address '{}': {:?}", address, result),
+            }
+        }
+    }
+
+    #[test]
+    fn validates_address_families() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "[::1]:9000",
+            "--sender",
+            "[::1]:9001",
+        ])
+        .expect("Cannot parse an IPv6 pair");
+        assert_eq!(config.receiver, vec![(1, "[::1]:9000".parse().unwrap())]);
+        assert_eq!(config.sender, ["[::1]:9001".parse().unwrap()]);
+
+        assert!(from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:9000",
+            "--sender",
+            "127.0.0.1:9001",
+        ])
+        .is_ok());
+
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:9000,[::1]:9000",
+                "--sender",
+                "127.0.0.1:9001",
+            ]),
+            Err(ArgsConfigError::FamilyMismatch(
+                "[::1]:9000".parse().unwrap(),
+                "127.0.0.1:9001".parse().unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_template() {
+        assert_eq!(
+            parse_template("GET /ping?t={timestamp}&n={seq}"),
+            Ok(vec![
+                TemplatePart::Text(String::from("GET /ping?t=")),
+                TemplatePart::Timestamp,
+                TemplatePart::Text(String::from("&n=")),
+                TemplatePart::Seq,
+            ])
+        );
+        assert_eq!(parse_template(""), Ok(vec![]));
+        assert_eq!(
+            parse_template("{seq"),
+            Err(TemplateError::UnclosedPlaceholder)
+        );
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--template",
+            "n={seq}",
+        ])
+        .expect("Cannot parse a valid template");
+        assert_eq!(config.template, Some(String::from("n={seq}")));
+
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--template",
+                "n={seq}&h={host}"
+            ]),
+            Err(ArgsConfigError::Template(
+                TemplateError::UnknownPlaceholder(String::from("host"))
+            ))
+        );
+    }
+
+    #[test]
+    fn decodes_payload_hex() {
+        assert_eq!(decode_hex("deadBEEF"), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(decode_hex("de:ad be\tef"), Ok(vec![0xde, 0xad, 0xbe, 0xef]));
+
+        assert_eq!(decode_hex("dea"), Err(PayloadHexError::OddLength));
+        assert_eq!(decode_hex("de:a"), Err(PayloadHexError::OddLength));
+        assert_eq!(decode_hex("dexd"), Err(PayloadHexError::InvalidDigit('x')));
+        assert_eq!(
+            decode_hex(" : "),
+            Err(PayloadHexError::InvalidLength(PacketLengthError::Underflow))
+        );
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--payload-hex",
+            "01:02",
+        ])
+        .expect("Cannot parse a valid hex payload");
+        assert_eq!(config.payload_hex, Some(String::from("01:02")));
+
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--payload-hex",
+                "0g"
+            ]),
+            Err(ArgsConfigError::PayloadHex(PayloadHexError::InvalidDigit(
+                'g'
+            )))
+        );
+
+        // Only one payload source can be specified
+        for conflicting in &[
+            vec!["--send-file", "payload.bin"],
+            vec!["--random-payload"],
+            vec!["--length", "100"],
+        ] {
+            let mut args = vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--payload-hex",
+                "01",
+            ];
+            args.extend(conflicting);
+            assert!(ArgsConfig::from_iter_safe(args).is_err());
+        }
+    }
+
+    #[test]
+    fn parses_warmup() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--warmup",
+            "1m 30s",
+        ])
+        .expect("Cannot parse a warmup");
+        assert_eq!(config.warmup, Duration::from_secs(90));
+
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--warmup",
+            "soon",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_source_ports() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--source-ports",
+            "4000:4010",
+        ])
+        .expect("Cannot parse a valid source ports range");
+        assert_eq!(config.source_ports, Some((4000, 4010)));
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--source-ports",
+            "4000:4000",
+        ])
+        .expect("Cannot parse a single-port range");
+        assert_eq!(config.source_ports, Some((4000, 4000)));
+
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--source-ports",
+                "4010:4000"
+            ]),
+            Err(ArgsConfigError::SourcePorts(4010, 4000))
+        );
+
+        assert_eq!(parse_port_range("4000"), Err(PortRangeError::NoColon));
+        assert!(parse_port_range("4000:70000").is_err());
+        assert!(parse_port_range("abc:4000").is_err());
+    }
+
+    #[test]
+    fn parses_interface() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--interface",
+            "eth0",
+        ])
+        .expect("Cannot parse a valid interface");
+        assert_eq!(config.interface, Some(String::from("eth0")));
+
+        for name in &["", "a-very-long-interface", "eth 0", "../eth0"] {
+            assert_eq!(
+                from_args(vec![
+                    "anevicon",
+                    "--receiver",
+                    "127.0.0.1:1280",
+                    "--interface",
+                    name
+                ]),
+                Err(ArgsConfigError::Interface(name.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn detects_multicast() {
+        for address in &["224.0.0.1:1280", "239.255.255.250:1900", "[ff02::1]:1280"] {
+            assert!(is_multicast(&address.parse().unwrap()), "{}", address);
+        }
+        for address in &["223.255.255.255:1280", "240.0.0.1:1280", "[fe80::1]:1280"] {
+            assert!(!is_multicast(&address.parse().unwrap()), "{}", address);
+        }
+    }
+
+    #[test]
+    fn parses_multicast_options() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "239.1.2.3:1280",
+            "--multicast-ttl",
+            "4",
+            "--multicast-loop",
+            "false",
+        ])
+        .expect("Cannot parse valid multicast options");
+        assert_eq!(config.multicast_ttl, Some(4));
+        assert_eq!(config.multicast_loop, Some(false));
+
+        let config = from_args(vec!["anevicon", "--receiver", "239.1.2.3:1280"])
+            .expect("Cannot parse the default multicast options");
+        assert_eq!(config.multicast_ttl, None);
+        assert_eq!(config.multicast_loop, None);
+
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--multicast-ttl",
+                "4"
+            ]),
+            Err(ArgsConfigError::MulticastRequired)
+        );
+
+        let parse_multicast_ttl = |ttl| {
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "239.1.2.3:1280",
+                "--multicast-ttl",
+                ttl,
+            ])
+        };
+        assert_eq!(parse_multicast_ttl("0").unwrap().multicast_ttl, Some(0));
+        assert_eq!(parse_multicast_ttl("255").unwrap().multicast_ttl, Some(255));
+        assert_eq!(
+            parse_multicast_ttl("256"),
+            Err(ArgsConfigError::MulticastTtl(256))
+        );
+        assert_eq!(
+            parse_multicast_ttl("300"),
+            Err(ArgsConfigError::MulticastTtl(300))
+        );
+
+        for (option, value) in &[("--multicast-ttl", "-1"), ("--multicast-loop", "maybe")] {
+            assert!(ArgsConfig::from_iter_safe(vec![
+                "anevicon",
+                "--receiver",
+                "239.1.2.3:1280",
+                option,
+                value,
+            ])
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn parses_tos() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--tos",
+            "184",
+        ])
+        .expect("Cannot parse a valid ToS");
+        assert_eq!(config.tos, Some(184));
+
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"])
+            .expect("Cannot parse the default ToS");
+        assert_eq!(config.tos, None);
+
+        // A value must fit into a single byte
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--tos",
+            "256",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_priority() {
+        assert_eq!(parse_priority("0"), Ok(0));
+        assert_eq!(parse_priority("6"), Ok(6));
+        assert_eq!(
+            parse_priority("-1"),
+            Err(ArgsConfigError::Priority(String::from(
+                "'-1' is not a non-negative number"
+            )))
+        );
+        assert!(parse_priority("2147483648").is_err());
+
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        assert_eq!(config.priority, None);
+
+        // The unprivileged priorities are always permitted
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--priority",
+            "5",
+        ])
+        .expect("Cannot parse a valid priority");
+        assert_eq!(config.priority, Some(5));
+        assert!(config.to_string().contains("priority: 5,"));
+        assert!(ArgsConfig::from_args_iter(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--priority",
+            "high",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn displays_config() {
+        let args = vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--sender",
+            "127.0.0.1:5684",
+            "--duration",
+            "3m",
+            "--length",
+            "1472",
+            "--wait",
+            "2s",
+            "--send-periodicity",
+            "10ms",
+        ];
+        let config = from_args(args.clone()).expect("Cannot parse valid arguments");
+        assert_eq!(config, from_args(args).unwrap());
+
+        let summary = format!("{:#}", config);
+        for line in &[
+            "receiver: 127.0.0.1:1280",
+            "sender: 127.0.0.1:5684",
+            "duration: 3m",
+            "length: 1472",
+            "wait: 2s",
+            "send-periodicity: 10ms",
+        ] {
+            assert!(summary.lines().any(|entry| entry == *line), "No '{}'", line);
+        }
+
+        // The ordinary form stays on a single line for the logs
+        assert!(!config.to_string().contains('\n'));
+        assert!(config
+            .to_string()
+            .starts_with("receiver: 127.0.0.1:1280, sender: "));
+    }
 
     #[test]
-    fn parses_valid_non_zero_usize() {
-        unsafe {
-            // Check that ordinary values are parsed correctly
-            assert_eq!(
-                parse_non_zero_usize("1"),
-                Ok(NonZeroUsize::new_unchecked(1))
+    fn collects_all_errors() {
+        let args = vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--source-ports",
+            "5000:4000",
+            "--interface",
+            "eth 0",
+            "--repeat-payload",
+        ];
+
+        assert_eq!(
+            ArgsConfig::from_matches_all(&ArgsConfig::clap().get_matches_from(args.clone())),
+            Err(vec![
+                ArgsConfigError::SourcePorts(5000, 4000),
+                ArgsConfigError::Interface(String::from("eth 0")),
+                ArgsConfigError::RepeatWithoutFile,
+            ])
+        );
+
+        // The single-error method returns only the first one
+        assert_eq!(
+            from_args(args),
+            Err(ArgsConfigError::SourcePorts(5000, 4000))
+        );
+    }
+
+    #[test]
+    fn requires_file_to_repeat() {
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--repeat-payload"
+            ]),
+            Err(ArgsConfigError::RepeatWithoutFile)
+        );
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--send-file",
+            "payload.bin",
+            "--repeat-payload",
+        ])
+        .expect("Cannot parse a repeated file payload");
+        assert!(config.repeat_payload);
+    }
+
+    #[test]
+    fn reads_stdin_payload() {
+        let payload = read_payload(&b"A payload piped from another tool"[..])
+            .expect("Cannot read a valid payload");
+        assert_eq!(payload.len(), 33);
+
+        assert_eq!(
+            read_payload(&b""[..]),
+            Err(ArgsConfigError::Stdin(
+                PacketLengthError::Underflow.to_string()
+            ))
+        );
+        assert_eq!(
+            read_payload(&vec![0; ABSOLUTE_MAX_PACKET_LENGTH + 10][..]),
+            Err(ArgsConfigError::Stdin(
+                PacketLengthError::Overflow.to_string()
+            ))
+        );
+
+        // Only one payload source can be specified
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--send-stdin",
+            "--payload-hex",
+            "01",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn takes_options_from_env() {
+        let lookup = |name: &str| match name {
+            "ANEVICON_DURATION" => Some(String::from("2m")),
+            "ANEVICON_LENGTH" => Some(String::from("512")),
+            _ => None,
+        };
+        let from_env = |args: Vec<&str>| {
+            ArgsConfig::from_matches_with(&ArgsConfig::clap().get_matches_from(args), lookup)
+        };
+
+        let config = from_env(vec!["anevicon", "--receiver", "127.0.0.1:1280"])
+            .expect("Cannot take the options from the environment");
+        assert_eq!(config.duration, Some(Duration::from_secs(120)));
+        assert_eq!(config.length, Some(512));
+        assert_eq!(config.wait, Duration::from_secs(5));
+
+        // The command-line arguments take precedence over the environment
+        let config = from_env(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--duration",
+            "5s",
+            "--length",
+            "64",
+        ])
+        .expect("Cannot parse the command-line arguments");
+        assert_eq!(config.duration, Some(Duration::from_secs(5)));
+        assert_eq!(config.length, Some(64));
+
+        let invalid = ArgsConfig::from_matches_with(
+            &ArgsConfig::clap().get_matches_from(vec!["anevicon", "--receiver", "127.0.0.1:1280"]),
+            |name| match name {
+                "ANEVICON_LENGTH" => Some(String::from("70000")),
+                _ => None,
+            },
+        );
+        assert_eq!(
+            invalid,
+            Err(vec![ArgsConfigError::Environment(format!(
+                "ANEVICON_LENGTH: {}",
+                PacketLengthError::Overflow
+            ))])
+        );
+    }
+
+    #[test]
+    fn parses_unlimited_duration() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--duration",
+            "0s",
+        ])
+        .expect("Cannot parse a zero duration");
+        assert_eq!(config.duration, None);
+
+        // The default duration must remain finite
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"])
+            .expect("Cannot parse the default duration");
+        assert!(config.duration.is_some());
+
+        assert_eq!(parse_time_limit("1m"), Ok(Some(Duration::from_secs(60))));
+        assert!(parse_time_limit("zero").is_err());
+    }
+
+    #[test]
+    fn warns_about_exceeding_mtu() {
+        let length = (TYPICAL_MTU_PAYLOAD + 1).to_string();
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length",
+            &length,
+        ])
+        .expect("Cannot parse a length above the MTU");
+        assert!(config
+            .mtu_warning()
+            .expect("No warning above the MTU")
+            .contains("fragmented"));
+
+        let range = format!("64:{}", TYPICAL_MTU_PAYLOAD + 1);
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length-range",
+            &range,
+        ])
+        .expect("Cannot parse a length range above the MTU");
+        assert!(config.mtu_warning().is_some());
+
+        let length = TYPICAL_MTU_PAYLOAD.to_string();
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length",
+            &length,
+        ])
+        .expect("Cannot parse a length equal to the MTU");
+        assert_eq!(config.mtu_warning(), None);
+    }
+
+    #[test]
+    fn checks_sequence_length() {
+        let parse_sequence = |args: Vec<&str>| {
+            from_args(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280", "--sequence"]
+                    .into_iter()
+                    .chain(args)
+                    .collect(),
+            )
+        };
+
+        assert!(parse_sequence(vec![]).unwrap().sequence);
+        assert!(parse_sequence(vec!["--length", "8"]).is_ok());
+        assert_eq!(
+            parse_sequence(vec!["--length", "7"]),
+            Err(ArgsConfigError::SequenceLength(7))
+        );
+        assert_eq!(
+            parse_sequence(vec!["--length-range", "4:1472"]),
+            Err(ArgsConfigError::SequenceLength(4))
+        );
+        assert_eq!(
+            parse_sequence(vec!["--payload-hex", "de:ad:be:ef"]),
+            Err(ArgsConfigError::SequenceLength(4))
+        );
+    }
+
+    #[test]
+    fn constructs_from_args_iter() {
+        let config = ArgsConfig::from_args_iter(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length",
+            "1472",
+        ])
+        .expect("Cannot parse valid arguments");
+        assert_eq!(
+            config.receiver,
+            vec![(1, "127.0.0.1:1280".parse().unwrap())]
+        );
+        assert_eq!(config.length, Some(1472));
+
+        // Incorrectly specified arguments are returned instead of exiting
+        match ArgsConfig::from_args_iter(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length",
+            "65528",
+        ]) {
+            Err(ArgsConfigError::Arguments(message)) => assert!(message.contains("--length")),
+            result => panic!("Parses an invalid length: {:?}", result),
+        }
+
+        // The constraints are validated as well
+        assert_eq!(
+            ArgsConfig::from_args_iter(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--length",
+                "4",
+                "--sequence",
+            ]),
+            Err(ArgsConfigError::SequenceLength(4))
+        );
+    }
+
+    #[test]
+    fn parses_log_level() {
+        let parse_level = |args: Vec<&str>| {
+            let matches = ArgsConfig::clap()
+                .get_matches_from_safe(
+                    vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                        .into_iter()
+                        .chain(args),
+                )
+                .expect("The command-line arguments are incorrectly specified");
+            ArgsConfig::from_matches(&matches).unwrap().log_level
+        };
+
+        assert_eq!(parse_level(vec![]), LevelFilter::Warn);
+        assert_eq!(parse_level(vec!["-v"]), LevelFilter::Info);
+        assert_eq!(parse_level(vec!["-vv"]), LevelFilter::Debug);
+        assert_eq!(parse_level(vec!["-vvv"]), LevelFilter::Trace);
+        assert_eq!(
+            parse_level(vec!["--verbose", "--verbose", "--verbose", "--verbose"]),
+            LevelFilter::Trace
+        );
+        assert_eq!(parse_level(vec!["--quiet"]), LevelFilter::Warn);
+        assert_eq!(parse_level(vec!["--debug"]), LevelFilter::Trace);
+
+        assert!(ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--quiet",
+            "-v",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_dont_fragment() {
+        assert!(
+            !from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"])
+                .unwrap()
+                .dont_fragment
+        );
+        assert!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--dont-fragment",
+            ])
+            .unwrap()
+            .dont_fragment
+        );
+    }
+
+    #[test]
+    fn parses_connect() {
+        assert!(
+            !from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"])
+                .unwrap()
+                .connect
+        );
+        assert!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--connect"
+            ])
+            .unwrap()
+            .connect
+        );
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280,127.0.0.1:1281",
+                "--connect",
+            ]),
+            Err(ArgsConfigError::ConnectReceivers(2))
+        );
+    }
+
+    #[test]
+    fn rejects_zero_receiver_port() {
+        assert_eq!(
+            from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280,10.0.0.1:0"]),
+            Err(ArgsConfigError::ZeroReceiverPort(
+                "10.0.0.1:0".parse().unwrap()
+            ))
+        );
+
+        // The zero sender port only requests an ephemeral local port
+        assert!(from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--sender",
+            "127.0.0.1:0",
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn rejects_same_endpoints() {
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:9000",
+                "--sender",
+                "127.0.0.1:9000",
+            ]),
+            Err(ArgsConfigError::SameEndpoints(
+                "127.0.0.1:9000".parse().unwrap()
+            ))
+        );
+
+        // The same IP-address with a different port is a legitimate setup
+        assert!(from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:9000",
+            "--sender",
+            "127.0.0.1:9001",
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn matches_default_sender_family() {
+        let config = from_args(vec!["anevicon", "--receiver", "[::1]:9000"])
+            .expect("Cannot parse an IPv6 receiver");
+        assert_eq!(config.sender, ["[::]:0".parse().unwrap()]);
+
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:9000"])
+            .expect("Cannot parse an IPv4 receiver");
+        assert_eq!(config.sender, ["0.0.0.0:0".parse().unwrap()]);
+
+        // An explicitly specified sender must never be changed
+        assert!(from_args(vec![
+            "anevicon",
+            "--receiver",
+            "[::1]:9000",
+            "--sender",
+            "0.0.0.0:0",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parses_packets_count() {
+        let config = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--packets",
+            "1000000",
+        ])
+        .expect("Cannot parse a valid packets count");
+        assert_eq!(config.packets.get(), 1_000_000);
+
+        // The default value must be the largest number available
+        let config = ArgsConfig::from_iter_safe(vec!["anevicon", "--receiver", "127.0.0.1:1280"])
+            .expect("Cannot parse the default packets count");
+        assert_eq!(config.packets.get(), usize::MAX);
+    }
+
+    #[test]
+    fn rejects_invalid_packets_count() {
+        for packets in &["abc", "-5", "0"] {
+            assert!(
+                ArgsConfig::from_iter_safe(vec![
+                    "anevicon",
+                    "--receiver",
+                    "127.0.0.1:1280",
+                    "--packets",
+                    packets,
+                ])
+                .is_err(),
+                "Parses an invalid packets count '{}' correctly",
+                packets
             );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_config_round_trip() {
+        let configs = vec![
+            ArgsConfig::from_iter_safe(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap(),
+            ArgsConfig::from_iter_safe(vec![
+                "anevicon",
+                "-r",
+                "127.0.0.1:1280,[::1]:53",
+                "--duration",
+                "1h 30m",
+                "--length",
+                "1472",
+                "--send-timeout",
+                "250ms",
+                "--send-file",
+                "packet.bin",
+                "--seed",
+                "42",
+            ])
+            .unwrap(),
+        ];
+
+        for config in configs {
+            let json = serde_json::to_string(&config).expect("Cannot serialize a config");
             assert_eq!(
-                parse_non_zero_usize("3"),
-                Ok(NonZeroUsize::new_unchecked(3))
+                serde_json::from_str::<ArgsConfig>(&json).expect("Cannot deserialize a config"),
+                config
             );
+        }
+    }
+
+    #[test]
+    fn formats_config_as_json() {
+        let config = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--wait",
+            "30s",
+            "--interface",
+            "eth\"0",
+        ])
+        .unwrap();
+
+        let json = config.to_json();
+        assert!(json.starts_with("{\n  \"receiver\": [[1, \"127.0.0.1:1280\"]],\n"));
+        assert!(json.contains("  \"wait\": \"30s\",\n"));
+        assert!(json.contains("  \"interface\": \"eth\\\"0\",\n"));
+        assert!(json.contains("  \"length\": null,\n"));
+        assert!(json.ends_with("  \"debug\": false\n}"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn parses_printed_json_back() {
+        let configs = vec![
+            ArgsConfig::from_iter_safe(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap(),
+            ArgsConfig::from_iter_safe(vec![
+                "anevicon",
+                "-r",
+                "127.0.0.1:1280,[::1]:53",
+                "--duration",
+                "0s",
+                "--length-range",
+                "64:1472",
+                "--source-ports",
+                "4000:4010",
+                "--send-timeout",
+                "250ms",
+                "--csv",
+                "dir/packet \"1\".bin",
+                "--multicast-loop",
+                "false",
+                "--output-format",
+                "json",
+                "--show-config",
+            ])
+            .unwrap(),
+        ];
+
+        for config in configs {
             assert_eq!(
-                parse_non_zero_usize("26655"),
-                Ok(NonZeroUsize::new_unchecked(26655))
+                serde_json::from_str::<ArgsConfig>(&config.to_json())
+                    .expect("Cannot parse the printed JSON"),
+                config
             );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_durations_as_humantime() {
+        let config = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--wait",
+            "30s",
+        ])
+        .unwrap();
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["wait"], "30s");
+        assert_eq!(json["send_timeout"], serde_json::Value::Null);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializes_invalid_config() {
+        let config =
+            ArgsConfig::from_iter_safe(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        let json = serde_json::to_value(&config).unwrap();
+
+        let mut invalid = json.clone();
+        invalid["length"] = serde_json::json!(65528);
+        assert!(serde_json::from_value::<ArgsConfig>(invalid).is_err());
+
+        let mut invalid = json.clone();
+        invalid["receiver"] = serde_json::json!([[1, "127.0.0.300:1280"]]);
+        assert!(serde_json::from_value::<ArgsConfig>(invalid).is_err());
+
+        let mut invalid = json;
+        invalid["duration"] = serde_json::json!("forever");
+        assert!(serde_json::from_value::<ArgsConfig>(invalid).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn validates_unix_socket() {
+        use std::os::unix::net::UnixDatagram;
+
+        let path = std::env::temp_dir().join(format!("anevicon-{}-config", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        // Neither a receiver nor a sender is required
+        let args = vec!["anevicon", "--unix-socket", path.to_str().unwrap()];
+        assert_eq!(
+            from_args(args.clone()),
+            Err(ArgsConfigError::UnixSocket(path.clone()))
+        );
+
+        // A regular file isn't a socket either
+        fs::write(&path, "not a socket").unwrap();
+        assert_eq!(
+            from_args(args.clone()),
+            Err(ArgsConfigError::UnixSocket(path.clone()))
+        );
+        fs::remove_file(&path).unwrap();
+
+        let _server = UnixDatagram::bind(&path).unwrap();
+        let config = from_args(args).unwrap();
+        assert_eq!(config.unix_socket, Some(path.clone()));
+        assert!(config.receiver.is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_network_options_with_unix_socket() {
+        for option in &[
+            vec!["--receiver", "127.0.0.1:1280"],
+            vec!["--receiver-cidr", "10.0.0.0/30:80"],
+            vec!["--broadcast"],
+            vec!["--connect"],
+            vec!["--ttl", "4"],
+        ] {
+            let mut args = vec!["anevicon", "--unix-socket", "/tmp/anevicon.sock"];
+            args.extend(option);
+
+            match ArgsConfig::from_args_iter(args) {
+                Err(ArgsConfigError::Arguments(message)) => {
+                    assert!(message.contains("cannot be used with"), "{}", message)
+                }
+                result => panic!("{:?} is accepted with --unix-socket: {:?}", option, result),
+            }
+        }
+    }
+
+    #[test]
+    fn overrides_length_bounds() {
+        let args = vec!["anevicon", "--receiver", "[::1]:1280", "--length", "65520"];
+        assert_eq!(
+            from_args(args.clone()),
+            Err(ArgsConfigError::PacketLength(
+                PacketLengthError::AboveMaximum(MAX_PACKET_LENGTH)
+            ))
+        );
+
+        // An IPv6 link can carry a larger payload
+        let config = from_args([&args[..], &["--max-length", "65527"]].concat()).unwrap();
+        assert_eq!(config.length, Some(65520));
+        assert!(ArgsConfig::from_args_iter(vec![
+            "anevicon",
+            "--receiver",
+            "[::1]:1280",
+            "--max-length",
+            "65528",
+        ])
+        .is_err());
+
+        // The default length follows the bounds, and the other lengths are checked
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--max-length",
+            "1472",
+        ])
+        .unwrap();
+        assert_eq!(config.packet_length(), 1472);
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--min-length",
+                "64",
+                "--length-range",
+                "16:128",
+            ]),
+            Err(ArgsConfigError::PacketLength(
+                PacketLengthError::BelowMinimum(64)
+            ))
+        );
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--min-length",
+                "8",
+                "--payload-hex",
+                "cafe",
+            ]),
+            Err(ArgsConfigError::PacketLength(
+                PacketLengthError::BelowMinimum(8)
+            ))
+        );
+    }
+
+    #[test]
+    fn rejects_inverted_length_bounds() {
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--min-length",
+                "1500",
+                "--max-length",
+                "1472",
+            ]),
+            Err(ArgsConfigError::LengthBounds(
+                NonZeroUsize::new(1500).unwrap(),
+                NonZeroUsize::new(1472).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_several_payload_files() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--send-file",
+            "first.bin",
+            "--send-file",
+            "second.bin",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.send_file,
+            vec![PathBuf::from("first.bin"), PathBuf::from("second.bin")]
+        );
+        assert!(config
+            .to_string()
+            .contains("send-file: first.bin, second.bin,"));
+    }
+
+    #[test]
+    fn parses_lists() {
+        assert_eq!(parse_list::<u16>("80, 443,8080"), Ok(vec![80, 443, 8080]));
+        assert_eq!(parse_list::<u16>(""), Ok(vec![]));
+        assert_eq!(parse_list::<u16>("  "), Ok(vec![]));
+
+        match parse_list::<u16>("80,http,8080") {
+            Err((1, _)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        match parse_list::<u16>("80,") {
+            Err((1, _)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert_eq!(list_element("80, http ,8080", 1), "http");
+
+        assert_eq!(
+            parse_list_with("1.1.1.1:53, 2:8.8.8.8:53", parse_weighted_receiver),
+            Ok(vec![
+                (1, "1.1.1.1:53".parse().unwrap()),
+                (2, "8.8.8.8:53".parse().unwrap())
+            ])
+        );
+    }
+
+    #[test]
+    fn loads_receiver_list_from_config_file() {
+        let path = config_file(
+            "receiver-list",
+            r#"receiver = ["127.0.0.1:1280, 2:127.0.0.1:1281", "127.0.0.1:1282"]"#,
+        );
+        let config = from_args(vec!["anevicon", "--config", path.to_str().unwrap()])
+            .expect("Cannot load a list of receivers");
+        assert_eq!(
+            config.receiver,
+            vec![
+                (1, "127.0.0.1:1280".parse().unwrap()),
+                (2, "127.0.0.1:1281".parse().unwrap()),
+                (1, "127.0.0.1:1282".parse().unwrap())
+            ]
+        );
+        fs::remove_file(path).unwrap();
+
+        let path = config_file(
+            "receiver-list-invalid",
+            r#"receiver = "127.0.0.1:1280, 127.0.0.300:1281""#,
+        );
+        match from_args(vec!["anevicon", "--config", path.to_str().unwrap()]) {
+            Err(ArgsConfigError::ConfigFile(message)) => {
+                assert!(message.contains("'127.0.0.300:1281'"), "{}", message)
+            }
+            result => panic!("Loads an invalid receiver: {:?}", result),
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parses_cores() {
+        assert_eq!(parse_cores("0,2,4"), Ok(vec![0, 2, 4]));
+        assert_eq!(parse_cores(" 1 , 3"), Ok(vec![1, 3]));
+        assert_eq!(
+            parse_cores("a,b"),
+            Err(ArgsConfigError::Cores(String::from(
+                "'a' is not a core index"
+            )))
+        );
+        assert!(parse_cores("0,,1").is_err());
+        assert!(parse_cores("-1").is_err());
+        assert_eq!(
+            parse_cores(" "),
+            Err(ArgsConfigError::Cores(String::from("the list is empty")))
+        );
+
+        let config = from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        assert_eq!(config.cores, None);
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--cores",
+            "0",
+        ])
+        .unwrap();
+        assert_eq!(config.cores, Some(vec![0]));
+        assert!(ArgsConfig::from_args_iter(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--cores",
+            "a,b",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_nonexistent_cores() {
+        let available = available_cores();
+        assert!(available >= 1);
+
+        let last = (available - 1).to_string();
+        let beyond = available.to_string();
+        let args = |cores: &str| {
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--cores",
+                &format!("0,{}", cores),
+            ])
+        };
+
+        assert!(args(&last).is_ok());
+        assert_eq!(
+            args(&beyond),
+            Err(ArgsConfigError::Cores(format!(
+                "the core {} doesn't exist, there are only {} cores",
+                available, available
+            )))
+        );
+    }
+
+    #[test]
+    fn parses_magic() {
+        assert_eq!(parse_magic("1094861636"), Ok(0x4142_4344));
+        assert_eq!(parse_magic("0xA11CE"), Ok(0xa_11ce));
+        assert_eq!(parse_magic("0XFFFFFFFF"), Ok(u32::MAX));
+        assert_eq!(
+            parse_magic("0x100000000"),
+            Err(ArgsConfigError::Magic(String::from("0x100000000")))
+        );
+        assert_eq!(
+            parse_magic("magic"),
+            Err(ArgsConfigError::Magic(String::from("magic")))
+        );
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--magic",
+            "0xA11CE",
+        ])
+        .unwrap();
+        assert_eq!(config.magic, Some(0xa_11ce));
+        assert!(config.to_string().contains("magic: 0x000a11ce, "));
+    }
+
+    #[test]
+    fn prepends_magic_header() {
+        let args = |length: &str| {
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--magic",
+                "1",
+                "--length",
+                length,
+            ])
+        };
+
+        // The header is prepended, so even a single-byte payload is enough
+        assert_eq!(args("8").unwrap().header_length(), MAGIC_HEADER_LENGTH);
+        assert_eq!(args("1").unwrap().header_length(), MAGIC_HEADER_LENGTH);
+        assert_eq!(
+            from_args(vec!["anevicon", "--receiver", "127.0.0.1:1280"])
+                .unwrap()
+                .header_length(),
+            0
+        );
+        assert!(ArgsConfig::from_args_iter(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--magic",
+            "1",
+            "--sequence",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn keeps_datagram_size_with_headers() {
+        let parse = |args: Vec<&str>| {
+            from_args(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args)
+                    .collect(),
+            )
+        };
+
+        // The sequence number overwrites the beginning of the payload
+        let config = parse(vec!["--sequence", "--length", "65507"]).unwrap();
+        assert_eq!(config.length(), MAX_PACKET_LENGTH);
+        assert_eq!(config.datagram_size(), MAX_PACKET_LENGTH);
+        assert_eq!(
+            parse(vec!["--sequence", "--length", "65508"]),
+            Err(ArgsConfigError::PacketLength(
+                PacketLengthError::AboveMaximum(MAX_PACKET_LENGTH)
+            ))
+        );
+
+        // The magic header is prepended, so it adds eight bytes to the datagram
+        let config = parse(vec!["--magic=1", "--length", "1000"]).unwrap();
+        assert_eq!(config.length(), 1000);
+        assert_eq!(config.datagram_size(), 1008);
+
+        let config = parse(vec!["--magic=1", "--length", "65499"]).unwrap();
+        assert_eq!(config.datagram_size(), MAX_PACKET_LENGTH);
+        assert_eq!(
+            parse(vec!["--magic=1", "--length", "65500"]),
+            Err(ArgsConfigError::DatagramSize(65508, MAX_PACKET_LENGTH))
+        );
+        assert_eq!(
+            parse(vec!["--magic=1", "--length-range", "100:65500"]),
+            Err(ArgsConfigError::DatagramSize(65508, MAX_PACKET_LENGTH))
+        );
+        assert!(ArgsConfigError::DatagramSize(65508, MAX_PACKET_LENGTH)
+            .to_string()
+            .contains("65508 bytes"));
+
+        // A larger datagram can still be sent on purpose
+        assert!(parse(vec!["--magic=1", "--length", "65500", "--unchecked-length"]).is_ok());
+    }
+
+    #[test]
+    fn normalizes_mapped_receiver_for_ipv4_sender() {
+        assert_eq!(
+            unmap_ipv4("[::ffff:10.0.0.1]:80".parse().unwrap()),
+            "10.0.0.1:80".parse().unwrap()
+        );
+        assert_eq!(
+            unmap_ipv4("[::1]:80".parse().unwrap()),
+            "[::1]:80".parse().unwrap()
+        );
+
+        // The default sender stays an IPv4 one
+        let config = from_args(vec!["anevicon", "--receiver", "[::ffff:127.0.0.1]:1280"])
+            .expect("Cannot normalize a mapped receiver");
+        assert_eq!(
+            config.receiver,
+            vec![(1, "127.0.0.1:1280".parse().unwrap())]
+        );
+        assert!(config.sender[0].is_ipv4());
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "2:[::ffff:127.0.0.1]:1280",
+            "--sender",
+            "127.0.0.1:0",
+        ])
+        .expect("Cannot normalize a mapped receiver");
+        assert_eq!(
+            config.receiver,
+            vec![(2, "127.0.0.1:1280".parse().unwrap())]
+        );
+    }
+
+    #[test]
+    fn rejects_mapped_receiver_for_ipv6_sender() {
+        let receiver: SocketAddr = "[::ffff:127.0.0.1]:1280".parse().unwrap();
+        let sender: SocketAddr = "[::1]:0".parse().unwrap();
+
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "[::ffff:127.0.0.1]:1280",
+                "--sender",
+                "[::1]:0",
+            ]),
+            Err(ArgsConfigError::MappedAddress(receiver, sender))
+        );
+        assert!(ArgsConfigError::MappedAddress(receiver, sender)
+            .to_string()
+            .contains("specify 127.0.0.1:1280 as the receiver"));
+    }
+
+    #[test]
+    fn bypasses_length_checks_if_unchecked() {
+        let parse = |args: Vec<&str>| {
+            from_args(
+                vec!["anevicon", "--receiver", "127.0.0.1:1280"]
+                    .into_iter()
+                    .chain(args)
+                    .collect(),
+            )
+        };
+
+        assert_eq!(
+            parse(vec!["--length", "0"]),
+            Err(ArgsConfigError::PacketLength(PacketLengthError::Underflow))
+        );
+        assert_eq!(
+            parse(vec!["--length", "0", "--unchecked-length"])
+                .unwrap()
+                .packet_length(),
+            0
+        );
+
+        // The bounds are bypassed, but not the size of the UDP header field
+        assert_eq!(
+            parse(vec!["--length", "65527", "--unchecked-length"])
+                .unwrap()
+                .packet_length(),
+            ABSOLUTE_MAX_PACKET_LENGTH
+        );
+        assert!(ArgsConfig::clap()
+            .get_matches_from_safe(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--length",
+                "65528",
+                "--unchecked-length",
+            ])
+            .is_err());
+
+        // The headers still need their place in a packet
+        assert_eq!(
+            parse(vec!["--length", "0", "--unchecked-length", "--sequence"]),
+            Err(ArgsConfigError::SequenceLength(0))
+        );
+    }
+
+    #[test]
+    fn conflicts_no_delay_with_pacing() {
+        let matches = |args: &[&str]| {
+            ArgsConfig::clap().get_matches_from_safe(
+                ["anevicon", "--receiver", "127.0.0.1:1280", "--no-delay"]
+                    .iter()
+                    .chain(args),
+            )
+        };
+
+        // The default values of the pacing options don't conflict
+        let config = ArgsConfig::from_matches(&matches(&[]).unwrap()).unwrap();
+        assert!(config.no_delay);
+        assert_eq!(config.wait_time(), Duration::from_secs(0));
+
+        for args in &[
+            &["--wait", "1s"][..],
+            &["--send-periodicity", "1ms"],
+            &["--jitter", "1ms"],
+            &["--packets-per-second", "100"],
+            &["--bandwidth", "1mb"],
+            &["--ramp-up", "1s"],
+            &["--burst-on", "1s", "--burst-off", "1s"],
+        ] {
+            assert!(matches(args).is_err(), "{:?} must conflict", args);
+        }
+    }
+
+    #[test]
+    fn parses_several_senders() {
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--sender",
+            "127.0.0.1:0",
+            "--sender",
+            "127.0.0.2:0",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.sender,
+            [
+                "127.0.0.1:0".parse::<SocketAddr>().unwrap(),
+                "127.0.0.2:0".parse().unwrap()
+            ]
+        );
+        assert!(config
+            .to_string()
+            .contains("sender: 127.0.0.1:0, 127.0.0.2:0, "));
+        assert!(config
+            .to_json()
+            .contains("  \"sender\": [\"127.0.0.1:0\", \"127.0.0.2:0\"],\n"));
+
+        // A comma-separated list is the same
+        let listed = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--sender",
+            "127.0.0.1:0,127.0.0.2:0",
+        ])
+        .unwrap();
+        assert_eq!(listed.sender, config.sender);
+    }
+
+    #[test]
+    fn rejects_invalid_senders() {
+        let error = ArgsConfig::from_args_iter(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--sender",
+            "127.0.0.1:0",
+            "--sender",
+            "127.0.0.300:0",
+        ])
+        .unwrap_err();
+        match error {
+            ArgsConfigError::Arguments(message) => assert!(message.contains("127.0.0.300:0")),
+            error => panic!("Unexpected error: {}", error),
+        }
+
+        // The same sender cannot be bound twice
+        assert_eq!(
+            from_args(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--sender",
+                "127.0.0.1:0,127.0.0.2:0,127.0.0.1:0",
+            ]),
+            Err(ArgsConfigError::Sender(
+                3,
+                String::from("127.0.0.1:0 is already specified as the sender #1")
+            ))
+        );
+        assert_eq!(
+            ArgsConfigError::Sender(2, String::from("error")).to_string(),
+            "Invalid sender #2: error"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializes_single_sender() {
+        let config =
+            ArgsConfig::from_iter_safe(vec!["anevicon", "--receiver", "127.0.0.1:1280"]).unwrap();
+        let mut json = serde_json::to_value(&config).unwrap();
+
+        json["sender"] = serde_json::json!("127.0.0.1:5684");
+        assert_eq!(
+            serde_json::from_value::<ArgsConfig>(json.clone())
+                .unwrap()
+                .sender,
+            ["127.0.0.1:5684".parse::<SocketAddr>().unwrap()]
+        );
+
+        json["sender"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<ArgsConfig>(json).is_err());
+    }
+
+    #[test]
+    fn parses_fill_byte() {
+        assert_eq!(parse_fill_byte("0"), Ok(0));
+        assert_eq!(parse_fill_byte("171"), Ok(0xab));
+        assert_eq!(parse_fill_byte("0xFF"), Ok(u8::MAX));
+        assert_eq!(parse_fill_byte("0Xa"), Ok(10));
+        for value in &["256", "0x100", "-1", "byte", ""] {
             assert_eq!(
-                parse_non_zero_usize("+75"),
-                Ok(NonZeroUsize::new_unchecked(75))
+                parse_fill_byte(value),
+                Err(ArgsConfigError::FillByte(value.to_string()))
             );
         }
+
+        let args = |extra: &[&'static str]| {
+            let mut args = vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--fill-byte",
+                "0xAB",
+            ];
+            args.extend_from_slice(extra);
+            ArgsConfig::clap().get_matches_from_safe(args)
+        };
+        let config = ArgsConfig::from_matches(&args(&[]).unwrap()).unwrap();
+        assert_eq!(config.fill_byte, Some(0xab));
+        assert!(config.to_string().contains("fill-byte: 0xab, "));
+
+        assert!(args(&["--random-payload"]).is_err());
+        assert!(args(&["--send-file", "packet.bin"]).is_err());
     }
 
     #[test]
-    fn parses_invalid_non_zero_usize() {
-        let panic_if_invalid = |string| {
-            if let Ok(_) = parse_non_zero_usize(string) {
-                panic!("Parses invalid formatted usize correctly");
+    fn parses_prometheus_path() {
+        let path =
+            std::env::temp_dir().join(format!("anevicon-{}-config.prom", std::process::id()));
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--prometheus-file",
+            path.to_str().unwrap(),
+        ])
+        .expect("Cannot parse a valid Prometheus path");
+        assert_eq!(config.prometheus_path, Some(path.clone()));
+        assert!(!path.exists() && !prometheus_temp_path(&path).exists());
+
+        match from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--prometheus-file",
+            "/nonexistent/anevicon.prom",
+        ]) {
+            Err(ArgsConfigError::Prometheus(message)) => {
+                assert!(message.starts_with("/nonexistent/anevicon.prom: "))
             }
+            result => panic!("Accepts a file in a nonexistent directory: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parses_bare_seconds() {
+        assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration(" 0 "), Ok(Duration::from_secs(0)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        match parse_duration("garbage") {
+            Err(ArgsConfigError::Duration(value, _)) => assert_eq!(value, "garbage"),
+            result => panic!("Parses an invalid time span: {:?}", result),
+        }
+        assert!(parse_duration("-30").is_err());
+
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--duration",
+            "30",
+            "--wait",
+            "2",
+            "--send-periodicity",
+            "1",
+        ])
+        .unwrap();
+        assert_eq!(config.duration, Some(Duration::from_secs(30)));
+        assert_eq!(config.wait, Duration::from_secs(2));
+        assert_eq!(config.send_periodicity, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn requires_loopback_receivers_to_measure_loss() {
+        let args = |receiver: &'static str| {
+            from_args(vec!["anevicon", "--receiver", receiver, "--measure-loss"])
         };
 
-        // Invalid numbers must produce the invalid format error
-        panic_if_invalid("   ");
+        assert!(args("127.0.0.1:1280").unwrap().measure_loss);
+        assert!(args("[::1]:1280").unwrap().measure_loss);
 
-        panic_if_invalid("abc5653odr!");
-        panic_if_invalid("6485&02hde");
+        let receiver = "203.0.113.77:1280".parse().unwrap();
+        assert_eq!(
+            args("203.0.113.77:1280"),
+            Err(ArgsConfigError::MeasureLoss(receiver))
+        );
+        assert!(ArgsConfigError::MeasureLoss(receiver)
+            .to_string()
+            .contains("203.0.113.77:1280 is not a loopback address"));
+    }
 
-        panic_if_invalid("-565642");
-        panic_if_invalid(&"2178".repeat(50));
+    #[test]
+    fn reads_receivers_file() {
+        let path = |name: &str| {
+            std::env::temp_dir().join(format!(
+                "anevicon-{}-{}.receivers",
+                std::process::id(),
+                name
+            ))
+        };
 
-        // Check that the zero value is not allowed
-        assert_eq!(parse_non_zero_usize("0"), Err(NonZeroUsizeError::ZeroValue));
+        let plain = path("plain");
+        fs::write(&plain, "127.0.0.1:1280\n3:127.0.0.2:1281\n").unwrap();
+        assert_eq!(
+            read_receivers_file(&plain),
+            Ok(vec![
+                (1, "127.0.0.1:1280".parse().unwrap()),
+                (3, "127.0.0.2:1281".parse().unwrap())
+            ])
+        );
+
+        // The file alone is enough, and it's merged with --receiver
+        let config = from_args(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.3:1282",
+            "--receivers-file",
+            plain.to_str().unwrap(),
+        ])
+        .expect("Cannot parse a valid receivers file");
+        assert_eq!(config.receiver.len(), 3);
+        assert_eq!(config.receiver[0].1, "127.0.0.3:1282".parse().unwrap());
+        assert!(from_args(vec![
+            "anevicon",
+            "--receivers-file",
+            plain.to_str().unwrap()
+        ])
+        .is_ok());
+        fs::remove_file(plain).unwrap();
+
+        let commented = path("commented");
+        fs::write(
+            &commented,
+            "# The testing receivers\n\n  127.0.0.1:1280  \n\t\n[::1]:1281 # IPv6\n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_receivers_file(&commented),
+            Ok(vec![
+                (1, "127.0.0.1:1280".parse().unwrap()),
+                (1, "[::1]:1281".parse().unwrap())
+            ])
+        );
+        fs::remove_file(commented).unwrap();
+    }
+
+    #[test]
+    fn reports_invalid_receivers_file() {
+        let path =
+            std::env::temp_dir().join(format!("anevicon-{}-invalid.receivers", std::process::id()));
+        fs::write(&path, "127.0.0.1:1280\n\n# A comment\n127.0.0.1\n").unwrap();
+
+        match from_args(vec!["anevicon", "--receivers-file", path.to_str().unwrap()]) {
+            Err(error @ ArgsConfigError::ReceiversFile(_, Some(4), _)) => {
+                assert!(error.to_string().contains(".receivers:4: "))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            read_receivers_file(&path),
+            Err(ArgsConfigError::ReceiversFile(_, None, _))
+        ));
+    }
+
+    #[test]
+    fn reads_all_getters() {
+        let matches = ArgsConfig::clap()
+            .get_matches_from_safe(vec![
+                "anevicon",
+                "--receiver",
+                "127.0.0.1:1280",
+                "--sender",
+                "127.0.0.1:5684",
+                "--duration",
+                "3min",
+                "--length",
+                "1472",
+                "--wait",
+                "2s",
+                "--send-periodicity",
+                "10ms",
+            ])
+            .expect("Cannot parse valid arguments");
+        let config = ArgsConfig::from_matches(&matches).expect("Cannot construct a valid config");
+
+        assert_eq!(config.receiver(), Some("127.0.0.1:1280".parse().unwrap()));
+        assert_eq!(config.sender(), "127.0.0.1:5684".parse().unwrap());
+        assert_eq!(config.duration(), Some(Duration::from_secs(180)));
+        assert_eq!(config.length(), 1472);
+        assert_eq!(config.waiting(), Duration::from_secs(2));
+        assert_eq!(config.periodicity(), Duration::from_millis(10));
     }
 }
@@ -0,0 +1,122 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::net::IpAddr;
+
+#[cfg(unix)]
+use std::ffi::CStr;
+#[cfg(unix)]
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// An IP-address assigned to a local network interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    pub name: String,
+    pub address: IpAddr,
+}
+
+impl Display for Interface {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "{}: {}", self.name, self.address)
+    }
+}
+
+/// Returns every IP-address of every local network interface (as
+/// `getifaddrs` reports them), in the system order. An interface with
+/// several addresses appears once per address, and the interfaces
+/// without IP-addresses are skipped.
+#[cfg(unix)]
+pub fn list_interfaces() -> io::Result<Vec<Interface>> {
+    let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut list) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut interfaces = Vec::new();
+    let mut current = list;
+    while let Some(entry) = unsafe { current.as_ref() } {
+        if let Some(address) = unsafe { ip_address(entry.ifa_addr) } {
+            interfaces.push(Interface {
+                name: unsafe { CStr::from_ptr(entry.ifa_name) }
+                    .to_string_lossy()
+                    .into_owned(),
+                address,
+            });
+        }
+        current = entry.ifa_next;
+    }
+
+    unsafe { libc::freeifaddrs(list) };
+    Ok(interfaces)
+}
+
+/// Extracts the IP-address of an interface, if it has one. `address` must
+/// be either null or point to a valid `sockaddr` of its family.
+#[cfg(unix)]
+unsafe fn ip_address(address: *const libc::sockaddr) -> Option<IpAddr> {
+    match address
+        .as_ref()
+        .map(|address| libc::c_int::from(address.sa_family))
+    {
+        Some(libc::AF_INET) => {
+            let raw = &*(address as *const libc::sockaddr_in);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(
+                raw.sin_addr.s_addr,
+            ))))
+        }
+        Some(libc::AF_INET6) => {
+            let raw = &*(address as *const libc::sockaddr_in6);
+            Some(IpAddr::V6(Ipv6Addr::from(raw.sin6_addr.s6_addr)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(not(unix))]
+pub fn list_interfaces() -> io::Result<Vec<Interface>> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "Listing the network interfaces isn't supported on your platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_loopback() {
+        let interfaces = list_interfaces().expect("Cannot list the interfaces");
+
+        assert!(interfaces
+            .iter()
+            .any(|interface| interface.address == IpAddr::from([127, 0, 0, 1])));
+    }
+
+    #[test]
+    fn formats_interface() {
+        let interface = Interface {
+            name: String::from("lo"),
+            address: IpAddr::from([127, 0, 0, 1]),
+        };
+        assert_eq!(interface.to_string(), "lo: 127.0.0.1");
+    }
+}
@@ -0,0 +1,63 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+pub mod attack;
+pub mod config;
+pub mod interfaces;
+pub mod logging;
+pub mod loss;
+pub mod socket;
+pub mod summary;
+
+use std::sync::atomic::AtomicBool;
+
+use attack::{attack_in_threads, AttackError, Attacker};
+use config::ArgsConfig;
+use loss::LossMeter;
+use summary::Statistics;
+
+/// Performs the whole attack described by `config`: binds the sockets,
+/// sends the packets from all the threads, and returns the collected
+/// statistics. Unlike the program itself, it doesn't handle Ctrl+C, so
+/// the attack ends only by the configured limits.
+pub fn run(config: &ArgsConfig) -> Result<Statistics, AttackError> {
+    run_until(config, &AtomicBool::new(false))
+}
+
+/// The same as `run`, but also stops the attack as soon as `interrupted`
+/// becomes `true`.
+pub fn run_until(config: &ArgsConfig, interrupted: &AtomicBool) -> Result<Statistics, AttackError> {
+    // The receivers must be bound before the first packet is sent to them
+    let meter = if config.measure_loss {
+        let receivers: Vec<_> = config.receiver_addresses().cloned().collect();
+        Some(LossMeter::bind(&receivers)?)
+    } else {
+        None
+    };
+    let result = attack_in_threads(&mut Attacker::group_from_args_config(config)?, interrupted);
+
+    match (meter, result) {
+        (None, result) => result,
+        (Some(meter), Ok(statistics)) => Ok(statistics.with_received(meter.finish())),
+        (Some(meter), Err(mut error)) => {
+            error.statistics = Box::new(error.statistics.with_received(meter.finish()));
+            Err(error)
+        }
+    }
+}
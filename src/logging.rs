@@ -17,56 +17,88 @@
  * For more information see <https://github.com/Gymmasssorla/anevicon>.
  */
 
-use std::io::{stderr, stdout};
+use std::env;
+use std::io::stderr;
 
 use colored::Colorize;
 use fern::colors::{Color, ColoredLevelConfig};
 use fern::Dispatch;
-use log::Level;
+use log::LevelFilter;
 use time::{self, strftime};
 
-pub fn setup_logging(debug: bool) {
+/// Sets up the logging system, which prints all the messages up to
+/// `level` to stderr, so stdout stays clean for the results. A level
+/// specified by the `RUST_LOG` environment variable (such as `warn`)
+/// overrides `level`. The messages are prefixed by the `label`, if any.
+pub fn setup_logging(level: LevelFilter, label: Option<&str>) {
     let colors = ColoredLevelConfig::new()
         .info(Color::Green)
         .warn(Color::Yellow)
         .error(Color::Red)
         .debug(Color::Magenta)
         .trace(Color::Cyan);
+    let label = label_prefix(label);
 
     Dispatch::new()
         .format(move |out, message, record| {
             out.finish(format_args!(
-                "[{anevicon}] {level} [{date_time}]: {message}",
+                "[{anevicon}] {level} [{date_time}]: {label}{message}",
                 anevicon = "anevicon".magenta().bold(),
                 level = colors.color(record.level()).to_string().underline(),
                 date_time = strftime("%x %X %z", &time::now()).unwrap().cyan(),
+                label = label,
                 message = message,
             ));
         })
-        // Print all traces and debugging information to stderr
-        .chain(
-            Dispatch::new()
-                .filter(move |metadata| {
-                    if !debug {
-                        return false;
-                    }
-
-                    match metadata.level() {
-                        Level::Info | Level::Warn | Level::Error => false,
-                        Level::Debug | Level::Trace => true,
-                    }
-                })
-                .chain(stderr()),
-        )
-        // Print all notifications, warnings and errors to stdout
-        .chain(
-            Dispatch::new()
-                .filter(move |metadata| match metadata.level() {
-                    Level::Info | Level::Warn | Level::Error => true,
-                    Level::Debug | Level::Trace => false,
-                })
-                .chain(stdout()),
-        )
+        .level(effective_level(level, env::var("RUST_LOG").ok()))
+        .chain(stderr())
         .apply()
         .expect("Cannot correctly setup the logging system");
 }
+
+/// Returns the prefix of the messages of an attack labeled by `label`,
+/// such as `[nightly] `, or nothing for an unlabeled attack.
+pub fn label_prefix(label: Option<&str>) -> String {
+    match label {
+        Some(label) => format!("[{}] ", label),
+        None => String::new(),
+    }
+}
+
+/// Returns the level specified by `rust_log` if it is a valid one, or
+/// `level` otherwise.
+fn effective_level(level: LevelFilter, rust_log: Option<String>) -> LevelFilter {
+    rust_log
+        .and_then(|rust_log| rust_log.trim().parse().ok())
+        .unwrap_or(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_label_prefix() {
+        assert_eq!(label_prefix(Some("nightly")), "[nightly] ");
+        assert_eq!(label_prefix(None), "");
+    }
+
+    #[test]
+    fn overrides_level_by_rust_log() {
+        assert_eq!(effective_level(LevelFilter::Info, None), LevelFilter::Info);
+        assert_eq!(
+            effective_level(LevelFilter::Info, Some(String::from("trace"))),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            effective_level(LevelFilter::Trace, Some(String::from("WARN"))),
+            LevelFilter::Warn
+        );
+
+        // Module directives aren't supported, so they are ignored
+        assert_eq!(
+            effective_level(LevelFilter::Debug, Some(String::from("anevicon=info"))),
+            LevelFilter::Debug
+        );
+    }
+}
@@ -0,0 +1,147 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How long a receiving socket waits for a datagram before it checks
+/// whether the attack is over. After the attack, the same timeout
+/// without any datagram means that the socket buffer is drained.
+const RECEIVE_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Receives the packets sent to the loopback receivers on the same host,
+/// so the program can estimate how many of them were lost locally.
+pub struct LossMeter {
+    finished: Arc<AtomicBool>,
+    receivers: Vec<JoinHandle<u64>>,
+}
+
+impl LossMeter {
+    /// Binds a socket to each of the distinct `receivers` and starts
+    /// counting the datagrams in the background. A receiver already
+    /// bound by another program cannot be measured.
+    pub fn bind(receivers: &[SocketAddr]) -> io::Result<LossMeter> {
+        let mut addresses = receivers.to_vec();
+        addresses.sort();
+        addresses.dedup();
+
+        let sockets = addresses
+            .iter()
+            .map(|address| {
+                UdpSocket::bind(address)
+                    .and_then(|socket| {
+                        socket.set_read_timeout(Some(RECEIVE_TIMEOUT))?;
+                        Ok(socket)
+                    })
+                    .map_err(|error| {
+                        io::Error::new(
+                            error.kind(),
+                            format!(
+                                "cannot bind the receiver {} to measure the loss: {}",
+                                address, error
+                            ),
+                        )
+                    })
+            })
+            .collect::<io::Result<Vec<UdpSocket>>>()?;
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let receivers = sockets
+            .into_iter()
+            .map(|socket| {
+                let finished = finished.clone();
+                thread::spawn(move || count_datagrams(&socket, &finished))
+            })
+            .collect();
+
+        Ok(LossMeter {
+            finished,
+            receivers,
+        })
+    }
+
+    /// Waits until the datagrams still in flight are received and returns
+    /// the count of all the received ones.
+    pub fn finish(self) -> u64 {
+        self.finished.store(true, Ordering::SeqCst);
+        self.receivers
+            .into_iter()
+            .map(|receiver| receiver.join().expect("A receiving thread has panicked"))
+            .sum()
+    }
+}
+
+/// Counts the datagrams arriving to `socket` until `finished` becomes
+/// `true` and the socket buffer is drained.
+fn count_datagrams(socket: &UdpSocket, finished: &AtomicBool) -> u64 {
+    let mut buffer = vec![0; 65536];
+    let mut received = 0;
+
+    loop {
+        match socket.recv(&mut buffer) {
+            Ok(_) => received += 1,
+            Err(ref error)
+                if error.kind() == io::ErrorKind::WouldBlock
+                    || error.kind() == io::ErrorKind::TimedOut =>
+            {
+                if finished.load(Ordering::SeqCst) {
+                    return received;
+                }
+            }
+            // The errors of the previous datagrams cannot stop the counting
+            Err(_) if !finished.load(Ordering::SeqCst) => (),
+            Err(_) => return received,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_received_datagrams() {
+        let probe = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let receiver = probe.local_addr().unwrap();
+        drop(probe);
+
+        let meter = LossMeter::bind(&[receiver, receiver]).expect("Cannot bind the loss meter");
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        for _ in 0..10 {
+            sender.send_to(b"packet", receiver).unwrap();
+        }
+        assert_eq!(meter.finish(), 10);
+    }
+
+    #[test]
+    fn reports_bound_receiver() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let receiver = server.local_addr().unwrap();
+
+        let error = LossMeter::bind(&[receiver]).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::AddrInUse);
+        assert!(error
+            .to_string()
+            .starts_with(&format!("cannot bind the receiver {} ", receiver)));
+    }
+}
@@ -17,33 +17,137 @@
  * For more information see <https://github.com/Gymmasssorla/anevicon>.
  */
 
-use log::error;
-use structopt::StructOpt;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
-use attack::Attacker;
-use config::ArgsConfig;
-use logging::setup_logging;
+use log::{error, info};
+use structopt::StructOpt;
 
-mod attack;
-mod config;
-mod logging;
-mod summary;
+use anevicon::attack::handle_interrupts;
+use anevicon::config::{log_level, ArgsConfig, OutputFormat};
+use anevicon::interfaces::list_interfaces;
+use anevicon::logging::{label_prefix, setup_logging};
+use anevicon::summary::{format_bound, write_prometheus};
 
 fn main() {
-    let config = ArgsConfig::from_args();
+    let matches = ArgsConfig::clap().get_matches();
 
-    setup_logging(config.debug);
+    setup_logging(log_level(&matches), matches.value_of("label"));
 
-    let attacker = match Attacker::from_args_config(&config) {
-        Err(error) => {
-            error!("Cannot setup the attacker: {}!", error);
+    // Listing the interfaces doesn't need the rest of the configuration
+    if matches.is_present("list_interfaces") {
+        match list_interfaces() {
+            Err(error) => {
+                error!("Cannot list the network interfaces: {}!", error);
+                std::process::exit(1);
+            }
+            Ok(interfaces) => {
+                for interface in interfaces {
+                    println!("{}", interface);
+                }
+                return;
+            }
+        }
+    }
+
+    // Report all the errors at once, so they can be fixed together
+    let config = match ArgsConfig::from_matches_all(&matches) {
+        Err(errors) => {
+            for error in errors {
+                error!("Invalid configuration: {}!", error);
+            }
             std::process::exit(1);
         }
-        Ok(attacker) => attacker,
+        Ok(config) => config,
+    };
+
+    if config.show_config {
+        eprintln!("{}", config.to_json());
+    }
+    if config.dry_run {
+        println!("{:#}", config);
+        return;
+    }
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    if let Err(error) = handle_interrupts(interrupted.clone()) {
+        error!("Cannot setup the interruption handler: {}!", error);
+        std::process::exit(1);
+    }
+
+    let start_time = time::now_utc();
+    let result = anevicon::run_until(&config, &interrupted);
+
+    // Even a failed attack reports how far it got, so it can be analysed
+    let (statistics, failure) = match result {
+        Ok(statistics) => (statistics, None),
+        Err(error) => (*error.statistics, Some(error.error)),
     };
 
-    if let Err(error) = attacker.attack() {
+    // The standard output is kept for the summary only
+    let (p50, p90, p99) = statistics.percentiles();
+    info!(
+        "The send durations are: p50 < {}, p90 < {}, p99 < {}.",
+        format_bound(p50),
+        format_bound(p90),
+        format_bound(p99)
+    );
+    for line in statistics.send_durations().to_string().lines() {
+        info!("{}", line);
+    }
+    let pacing = statistics.pacing();
+    info!(
+        "The sending calls deviated from the planned times by {} on average \
+         (p95 < {}, max {}).",
+        format_bound(pacing.mean()),
+        format_bound(pacing.p95()),
+        format_bound(pacing.max())
+    );
+
+    match (config.output_format, config.label.as_deref()) {
+        (OutputFormat::Human, label) => println!(
+            "{}The total result is: {}.",
+            label_prefix(label),
+            statistics
+        ),
+        (OutputFormat::Json, _) => println!("{}", statistics.to_report_json(&config, &start_time)),
+    }
+
+    // A collector keeps the previous metrics if the new ones cannot be written
+    let mut written = true;
+    if let Some(ref path) = config.prometheus_path {
+        let receiver = match config.unix_socket {
+            Some(ref socket) => socket.display().to_string(),
+            None => config
+                .receiver_addresses()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(","),
+        };
+        if let Err(error) = write_prometheus(path, &statistics.to_prometheus(&receiver)) {
+            error!(
+                "Cannot write the Prometheus file {}: {}!",
+                path.display(),
+                error
+            );
+            written = false;
+        }
+    }
+
+    if let Some(error) = failure {
         error!("An error occurred during the attack: {}!", error);
         std::process::exit(1);
     }
+
+    if !statistics.is_successful(config.fail_under) {
+        error!(
+            "Only {:.2}% of the packets were sent, which is below --fail-under!",
+            statistics.success_percent()
+        );
+        std::process::exit(1);
+    }
+
+    if !written {
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,527 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::path::Path;
+use std::time::Duration;
+
+#[cfg(unix)]
+use std::mem;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
+/// A socket sending packets: a UDP one or, with `--unix-socket`, a UNIX
+/// domain datagram one, which is always connected to its receiver.
+#[derive(Debug)]
+pub enum Socket {
+    Udp(UdpSocket),
+    #[cfg(unix)]
+    Unix(UnixDatagram),
+}
+
+impl Socket {
+    /// Creates an unbound UNIX domain datagram socket connected to the
+    /// socket bound to `path`.
+    #[cfg(unix)]
+    pub fn connect_unix(path: &Path) -> io::Result<Socket> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Socket::Unix(socket))
+    }
+
+    #[cfg(not(unix))]
+    pub fn connect_unix(_: &Path) -> io::Result<Socket> {
+        Err(unsupported())
+    }
+
+    /// Sends a packet to `receiver` or, if the socket is `connected`, to
+    /// its peer.
+    pub fn send_to(&self, buf: &[u8], receiver: &SocketAddr, connected: bool) -> io::Result<usize> {
+        match self {
+            Socket::Udp(socket) if connected => socket.send(buf),
+            Socket::Udp(socket) => socket.send_to(buf, receiver),
+            #[cfg(unix)]
+            Socket::Unix(socket) => socket.send(buf),
+        }
+    }
+
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            Socket::Udp(socket) => socket.set_write_timeout(timeout),
+            #[cfg(unix)]
+            Socket::Unix(socket) => socket.set_write_timeout(timeout),
+        }
+    }
+
+    pub fn as_udp(&self) -> Option<&UdpSocket> {
+        match self {
+            Socket::Udp(socket) => Some(socket),
+            #[cfg(unix)]
+            Socket::Unix(_) => None,
+        }
+    }
+}
+
+/// Sends all the `packets`, each to its own receiver (or to the peer if
+/// the socket is `connected`), and returns the count of the sent ones,
+/// which might be less than requested. An error is returned only if not
+/// a single packet was sent. A UDP socket on Linux sends them by a single
+/// `sendmmsg` call, and the other sockets send them one by one.
+pub fn send_batch(
+    socket: &Socket,
+    packets: &[(&[u8], &SocketAddr)],
+    connected: bool,
+) -> io::Result<usize> {
+    match socket {
+        #[cfg(target_os = "linux")]
+        Socket::Udp(socket) => send_mmsg(socket, packets, connected),
+        socket => {
+            for (sent, &(payload, receiver)) in packets.iter().enumerate() {
+                if let Err(error) = socket.send_to(payload, receiver, connected) {
+                    return if sent == 0 { Err(error) } else { Ok(sent) };
+                }
+            }
+
+            Ok(packets.len())
+        }
+    }
+}
+
+/// Sets the `SO_SNDBUF` option and returns the effective size, which
+/// might differ from the requested one because the kernel may clamp
+/// (or, like Linux, double) it.
+#[cfg(unix)]
+pub fn set_send_buffer_size(socket: &UdpSocket, size: usize) -> io::Result<usize> {
+    let size = size.min(libc::c_int::MAX as usize) as libc::c_int;
+
+    setsockopt(socket, libc::SOL_SOCKET, libc::SO_SNDBUF, size)?;
+    getsockopt::<libc::c_int>(socket, libc::SOL_SOCKET, libc::SO_SNDBUF).map(|size| size as usize)
+}
+
+/// Sets the type of service of outgoing packets: `IP_TOS` for an IPv4
+/// socket or `IPV6_TCLASS` for an IPv6 one. The DSCP class occupies its
+/// upper six bits.
+#[cfg(unix)]
+pub fn set_tos(socket: &UdpSocket, tos: u8) -> io::Result<()> {
+    if socket.local_addr()?.is_ipv4() {
+        setsockopt(
+            socket,
+            libc::IPPROTO_IP,
+            libc::IP_TOS,
+            libc::c_int::from(tos),
+        )
+    } else {
+        setsockopt(
+            socket,
+            libc::IPPROTO_IPV6,
+            libc::IPV6_TCLASS,
+            libc::c_int::from(tos),
+        )
+    }
+}
+
+/// Sets the hop limit of outgoing multicast packets of an IPv6 socket
+/// (`IPV6_MULTICAST_HOPS`), which the standard library doesn't provide.
+#[cfg(unix)]
+pub fn set_multicast_hops_v6(socket: &UdpSocket, hops: u32) -> io::Result<()> {
+    let hops = hops.min(libc::c_int::MAX as u32) as libc::c_int;
+    setsockopt(socket, libc::IPPROTO_IPV6, libc::IPV6_MULTICAST_HOPS, hops)
+}
+
+/// Binds the socket to the network interface `name` (`SO_BINDTODEVICE`),
+/// so packets leave only through it. This option is supported only on
+/// Linux.
+#[cfg(target_os = "linux")]
+pub fn bind_to_device(socket: &UdpSocket, name: &str) -> io::Result<()> {
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_BINDTODEVICE,
+            name.as_ptr() as *const libc::c_void,
+            name.len() as libc::socklen_t,
+        )
+    };
+
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Sets the priority of the packets sent by the socket (`SO_PRIORITY`),
+/// which the queueing disciplines might take into account. This option
+/// is supported only on Linux.
+#[cfg(target_os = "linux")]
+pub fn set_priority(socket: &UdpSocket, priority: u32) -> io::Result<()> {
+    let priority = priority.min(libc::c_int::MAX as u32) as libc::c_int;
+    setsockopt(socket, libc::SOL_SOCKET, libc::SO_PRIORITY, priority)
+}
+
+/// Sets the don't-fragment bit of outgoing packets, so the packets
+/// exceeding the path MTU are dropped (and the local ones fail with
+/// `EMSGSIZE`) instead of being fragmented. Linux provides it by the
+/// `IP_MTU_DISCOVER` option, and the BSDs by `IP_DONTFRAG`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_dont_fragment(socket: &UdpSocket) -> io::Result<()> {
+    if socket.local_addr()?.is_ipv4() {
+        setsockopt(
+            socket,
+            libc::IPPROTO_IP,
+            libc::IP_MTU_DISCOVER,
+            libc::IP_PMTUDISC_DO,
+        )
+    } else {
+        setsockopt(
+            socket,
+            libc::IPPROTO_IPV6,
+            libc::IPV6_MTU_DISCOVER,
+            libc::IPV6_PMTUDISC_DO,
+        )
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+pub fn set_dont_fragment(socket: &UdpSocket) -> io::Result<()> {
+    if socket.local_addr()?.is_ipv4() {
+        setsockopt(
+            socket,
+            libc::IPPROTO_IP,
+            libc::IP_DONTFRAG,
+            1 as libc::c_int,
+        )
+    } else {
+        setsockopt(
+            socket,
+            libc::IPPROTO_IPV6,
+            libc::IPV6_DONTFRAG,
+            1 as libc::c_int,
+        )
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_mmsg(
+    socket: &UdpSocket,
+    packets: &[(&[u8], &SocketAddr)],
+    connected: bool,
+) -> io::Result<usize> {
+    let mut addresses: Vec<(libc::sockaddr_storage, libc::socklen_t)> = packets
+        .iter()
+        .map(|&(_, receiver)| raw_address(receiver))
+        .collect();
+    let mut iovecs: Vec<libc::iovec> = packets
+        .iter()
+        .map(|&(payload, _)| libc::iovec {
+            iov_base: payload.as_ptr() as *mut libc::c_void,
+            iov_len: payload.len(),
+        })
+        .collect();
+
+    // The headers point into the vectors above, so they must not be moved
+    let mut messages: Vec<libc::mmsghdr> = iovecs
+        .iter_mut()
+        .zip(addresses.iter_mut())
+        .map(|(iovec, (address, length))| {
+            let mut header: libc::msghdr = unsafe { mem::zeroed() };
+            if !connected {
+                header.msg_name = address as *mut libc::sockaddr_storage as *mut libc::c_void;
+                header.msg_namelen = *length;
+            }
+            header.msg_iov = iovec;
+            header.msg_iovlen = 1;
+
+            libc::mmsghdr {
+                msg_hdr: header,
+                msg_len: 0,
+            }
+        })
+        .collect();
+
+    let result = unsafe {
+        libc::sendmmsg(
+            socket.as_raw_fd(),
+            messages.as_mut_ptr(),
+            messages.len() as libc::c_uint,
+            0,
+        )
+    };
+
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result as usize)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn raw_address(address: &SocketAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+
+    let length = match address {
+        SocketAddr::V4(address) => {
+            let raw = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in) };
+            raw.sin_family = libc::AF_INET as libc::sa_family_t;
+            raw.sin_port = address.port().to_be();
+            raw.sin_addr = libc::in_addr {
+                s_addr: u32::from_ne_bytes(address.ip().octets()),
+            };
+            mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(address) => {
+            let raw = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in6) };
+            raw.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            raw.sin6_port = address.port().to_be();
+            raw.sin6_flowinfo = address.flowinfo();
+            raw.sin6_addr = libc::in6_addr {
+                s6_addr: address.ip().octets(),
+            };
+            raw.sin6_scope_id = address.scope_id();
+            mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+
+    (storage, length as libc::socklen_t)
+}
+
+#[cfg(unix)]
+fn setsockopt<T>(
+    socket: &UdpSocket,
+    level: libc::c_int,
+    name: libc::c_int,
+    value: T,
+) -> io::Result<()> {
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            level,
+            name,
+            &value as *const T as *const libc::c_void,
+            mem::size_of::<T>() as libc::socklen_t,
+        )
+    };
+
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn getsockopt<T: Default>(
+    socket: &UdpSocket,
+    level: libc::c_int,
+    name: libc::c_int,
+) -> io::Result<T> {
+    let mut value = T::default();
+    let mut length = mem::size_of::<T>() as libc::socklen_t;
+
+    let result = unsafe {
+        libc::getsockopt(
+            socket.as_raw_fd(),
+            level,
+            name,
+            &mut value as *mut T as *mut libc::c_void,
+            &mut length,
+        )
+    };
+
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(value)
+    }
+}
+
+#[cfg(not(unix))]
+pub fn set_send_buffer_size(_: &UdpSocket, _: usize) -> io::Result<usize> {
+    Err(unsupported())
+}
+
+#[cfg(not(unix))]
+pub fn set_multicast_hops_v6(_: &UdpSocket, _: u32) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(unix))]
+pub fn set_tos(_: &UdpSocket, _: u8) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn bind_to_device(_: &UdpSocket, _: &str) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd"
+)))]
+pub fn set_dont_fragment(_: &UdpSocket) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        "This socket option isn't supported on your platform",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn sets_priority() {
+        let socket = UdpSocket::bind("127.0.0.1:0").expect("Cannot bind a testing socket");
+
+        set_priority(&socket, 5).expect("Cannot set SO_PRIORITY");
+        assert_eq!(
+            getsockopt::<libc::c_int>(&socket, libc::SOL_SOCKET, libc::SO_PRIORITY).unwrap(),
+            5
+        );
+    }
+
+    #[test]
+    fn sets_send_buffer_size() {
+        let socket = UdpSocket::bind("127.0.0.1:0").expect("Cannot bind a testing socket");
+
+        // The kernel might adjust the value, but never makes it lower
+        let size = set_send_buffer_size(&socket, 256 * 1024).expect("Cannot set SO_SNDBUF");
+        assert!(size >= 256 * 1024);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn sets_tos() {
+        let socket = UdpSocket::bind("127.0.0.1:0").expect("Cannot bind a testing socket");
+
+        set_tos(&socket, 0xb8).expect("Cannot set IP_TOS");
+        assert_eq!(
+            getsockopt::<libc::c_int>(&socket, libc::IPPROTO_IP, libc::IP_TOS).unwrap(),
+            0xb8
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn sets_multicast_hops_v6() {
+        let socket = match UdpSocket::bind("[::1]:0") {
+            Ok(socket) => socket,
+            // IPv6 might be disabled on the testing machine
+            Err(_) => return,
+        };
+
+        set_multicast_hops_v6(&socket, 7).expect("Cannot set IPV6_MULTICAST_HOPS");
+        assert_eq!(
+            getsockopt::<libc::c_int>(&socket, libc::IPPROTO_IPV6, libc::IPV6_MULTICAST_HOPS)
+                .unwrap(),
+            7
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn sets_dont_fragment() {
+        let socket = UdpSocket::bind("127.0.0.1:0").expect("Cannot bind a testing socket");
+
+        set_dont_fragment(&socket).expect("Cannot set IP_MTU_DISCOVER");
+        assert_eq!(
+            getsockopt::<libc::c_int>(&socket, libc::IPPROTO_IP, libc::IP_MTU_DISCOVER).unwrap(),
+            libc::IP_PMTUDISC_DO
+        );
+    }
+
+    #[test]
+    fn sends_batch() {
+        let server = UdpSocket::bind("127.0.0.1:0").expect("Cannot setup the testing server");
+        let socket =
+            Socket::Udp(UdpSocket::bind("127.0.0.1:0").expect("Cannot bind a testing socket"));
+        let receiver = server.local_addr().unwrap();
+
+        let packets: [(&[u8], &SocketAddr); 3] = [
+            (b"first", &receiver),
+            (b"second", &receiver),
+            (b"third", &receiver),
+        ];
+        assert_eq!(send_batch(&socket, &packets, false).unwrap(), 3);
+
+        server
+            .set_read_timeout(Some(std::time::Duration::from_secs(1)))
+            .unwrap();
+        let mut buffer = [0; 16];
+        for &(payload, _) in &packets {
+            let length = server.recv(&mut buffer).expect("A packet was lost");
+            assert_eq!(&buffer[..length], payload);
+        }
+
+        // A connected socket ignores the receivers
+        socket.as_udp().unwrap().connect(receiver).unwrap();
+        let unused = "127.0.0.1:9".parse().unwrap();
+        assert_eq!(
+            send_batch(&socket, &[(b"fourth", &unused)], true).unwrap(),
+            1
+        );
+        let length = server.recv(&mut buffer).expect("A packet was lost");
+        assert_eq!(&buffer[..length], b"fourth");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn sends_to_unix_socket() {
+        let path = std::env::temp_dir().join(format!("anevicon-{}-socket", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let server = UnixDatagram::bind(&path).expect("Cannot setup the testing server");
+
+        let socket = Socket::connect_unix(&path).expect("Cannot connect to the testing server");
+        assert!(socket.as_udp().is_none());
+
+        // The receivers are ignored, because the socket is connected anyway
+        let unused = "127.0.0.1:9".parse().unwrap();
+        let packets: [(&[u8], &SocketAddr); 2] = [(b"first", &unused), (b"second", &unused)];
+        assert_eq!(send_batch(&socket, &packets, false).unwrap(), 2);
+
+        let mut buffer = [0; 16];
+        for &(payload, _) in &packets {
+            let length = server.recv(&mut buffer).expect("A packet was lost");
+            assert_eq!(&buffer[..length], payload);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn binds_to_device() {
+        let socket = UdpSocket::bind("127.0.0.1:0").expect("Cannot bind a testing socket");
+
+        bind_to_device(&socket, "lo").expect("Cannot bind to the loopback interface");
+        assert!(bind_to_device(&socket, "anevicon0").is_err());
+    }
+}
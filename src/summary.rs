@@ -17,15 +17,315 @@
  * For more information see <https://github.com/Gymmasssorla/anevicon>.
  */
 
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fmt::{self, Display, Formatter};
-use std::time::{Duration, Instant};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::config::{json_string, ArgsConfig};
 
 use humantime::format_duration;
+use time::Tm;
+
+pub const CSV_HEADER: &str = "timestamp,packets_sent,packets_failed,bytes_sent,pps";
+
+/// The count of the send duration buckets. Each bucket is twice as wide
+/// as the previous one, starting from one microsecond, so the last one
+/// holds all the durations of 4 seconds and longer.
+pub const SEND_DURATION_BUCKETS: usize = 24;
+const HISTOGRAM_WIDTH: u64 = 40;
+
+/// Creates a CSV file for per-interval summaries if it doesn't exist yet
+/// and writes the header into it. An existing file is opened as is, so
+/// the rows of several runs are appended to it.
+pub fn prepare_csv(path: &Path) -> io::Result<File> {
+    match OpenOptions::new().append(true).create_new(true).open(path) {
+        Ok(mut file) => writeln!(file, "{}", CSV_HEADER).map(|_| file),
+        Err(ref error) if error.kind() == io::ErrorKind::AlreadyExists => {
+            OpenOptions::new().append(true).open(path)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Checks that `prepare_csv` would succeed, without leaving a new file
+/// behind. An existing file is only opened for appending.
+pub fn check_csv(path: &Path) -> io::Result<()> {
+    match OpenOptions::new().append(true).create_new(true).open(path) {
+        Ok(_) => fs::remove_file(path),
+        Err(ref error) if error.kind() == io::ErrorKind::AlreadyExists => {
+            OpenOptions::new().append(true).open(path).map(|_| ())
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Returns the temporary file which the Prometheus metrics are written
+/// to before they replace `path`. It's in the same directory, so the
+/// renaming is atomic.
+pub fn prometheus_temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Replaces the file at `path` by the Prometheus `metrics` atomically,
+/// so the textfile collector never reads a partially written file.
+pub fn write_prometheus(path: &Path, metrics: &str) -> io::Result<()> {
+    let temp_path = prometheus_temp_path(path);
+
+    let result = fs::write(&temp_path, metrics).and_then(|_| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Escapes a Prometheus label value, in which only the backslash, the
+/// double quote, and the line feed are special.
+fn prometheus_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// A fixed-bucket histogram of the durations of the sending calls, so
+/// the memory doesn't grow with the count of sent packets.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct SendDurations {
+    counts: [u64; SEND_DURATION_BUCKETS],
+}
+
+impl SendDurations {
+    pub fn record(&mut self, duration: Duration) {
+        self.counts[SendDurations::bucket(duration)] += 1;
+    }
+
+    pub fn merge(&mut self, other: &SendDurations) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other;
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Returns the index of the bucket holding `duration`, where the
+    /// `index`-th bucket holds durations shorter than `2^index`
+    /// microseconds, but not shorter than the previous bucket.
+    fn bucket(duration: Duration) -> usize {
+        let micros = duration.as_micros();
+
+        if micros == 0 {
+            0
+        } else {
+            (128 - micros.leading_zeros() as usize).min(SEND_DURATION_BUCKETS - 1)
+        }
+    }
+
+    /// Returns the upper bound of the `index`-th bucket. The last bucket
+    /// is unbounded, so twice its lower bound is returned instead.
+    fn upper_bound(index: usize) -> Duration {
+        Duration::from_micros(1 << index)
+    }
+
+    /// Returns the duration which at least `percent` percents of the
+    /// recorded ones are shorter than, rounded up to the bucket upper
+    /// bound. Nothing recorded results in the zero duration.
+    pub fn percentile(&self, percent: f64) -> Duration {
+        let rank = (self.count() as f64 * percent / 100.0).ceil().max(1.0) as u64;
+        let mut seen = 0;
+
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return SendDurations::upper_bound(index);
+            }
+        }
+
+        Duration::from_secs(0)
+    }
+}
+
+impl Display for SendDurations {
+    /// Draws the histogram line by line, from the first to the last
+    /// non-empty bucket, with the bars proportional to the most filled
+    /// bucket.
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let first = self.counts.iter().position(|&count| count > 0);
+        let last = self.counts.iter().rposition(|&count| count > 0);
+        let (first, last) = match (first, last) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return write!(fmt, "no sending calls"),
+        };
+        let max = self.counts.iter().max().cloned().unwrap_or(1);
+
+        for index in first..=last {
+            let count = self.counts[index];
+            let bar = (count * HISTOGRAM_WIDTH).div_ceil(max);
+
+            if index > first {
+                writeln!(fmt)?;
+            }
+            write!(
+                fmt,
+                "{:>8} {:<width$} {}",
+                format!("< {}", format_bound(SendDurations::upper_bound(index))),
+                "#".repeat(bar as usize),
+                count,
+                width = HISTOGRAM_WIDTH as usize
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How far the realized starts of the sending calls deviated from the
+/// planned ones, that is, how accurately the configured periodicity or
+/// rate was kept. The deviations are put into the same histogram as the
+/// send durations, and their total and maximum are kept exactly.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct PacingDeviations {
+    histogram: SendDurations,
+    total: Duration,
+    max: Duration,
+}
+
+impl PacingDeviations {
+    /// Records a sending call which was `planned` to start at some time
+    /// (since the attack start), but `actually` started at another one.
+    /// Too early starts are deviations as well as too late ones.
+    pub fn record(&mut self, planned: Duration, actually: Duration) {
+        let deviation = actually.abs_diff(planned);
+
+        self.histogram.record(deviation);
+        self.total += deviation;
+        self.max = self.max.max(deviation);
+    }
+
+    pub fn merge(&mut self, other: &PacingDeviations) {
+        self.histogram.merge(&other.histogram);
+        self.total += other.total;
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.histogram.count()
+    }
+
+    /// Returns the mean deviation, which is zero if nothing is recorded.
+    pub fn mean(&self) -> Duration {
+        match self.count() {
+            0 => Duration::from_secs(0),
+            count => Duration::from_secs_f64(self.total.as_secs_f64() / count as f64),
+        }
+    }
+
+    /// Returns the deviation which 95% of the recorded ones are shorter
+    /// than, rounded up to the histogram bucket bound.
+    pub fn p95(&self) -> Duration {
+        self.histogram.percentile(95.0)
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+}
+
+/// The packets sent to a single receiver, counted only if several
+/// receivers are specified. The failures include the packets rejected
+/// as unreachable.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct PerReceiverStats {
+    packets_sent: u64,
+    packets_failed: u64,
+    bytes_sent: u64,
+}
+
+impl PerReceiverStats {
+    pub fn packets_sent(&self) -> u64 {
+        self.packets_sent
+    }
+
+    pub fn packets_failed(&self) -> u64 {
+        self.packets_failed
+    }
+
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    fn merge(&mut self, other: &PerReceiverStats) {
+        self.packets_sent += other.packets_sent;
+        self.packets_failed += other.packets_failed;
+        self.bytes_sent += other.bytes_sent;
+    }
+}
+
+/// Formats a bucket bound in the most suitable unit, truncating the
+/// fraction, which is meaningless for the bounds of powers of two.
+pub fn format_bound(bound: Duration) -> String {
+    if bound < Duration::from_millis(1) {
+        format!("{}us", bound.as_micros())
+    } else if bound < Duration::from_secs(1) {
+        format!("{}ms", bound.as_millis())
+    } else {
+        format!("{:.1}s", bound.as_secs_f64())
+    }
+}
+
+/// The condition which stopped the packet sending.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StopReason {
+    Interrupted,
+    Duration,
+    MaxRuntime,
+    Packets,
+    Bytes,
+}
+
+impl StopReason {
+    /// Returns the machine-readable name of this reason, used in the JSON
+    /// summary.
+    pub fn name(self) -> &'static str {
+        match self {
+            StopReason::Interrupted => "interrupted",
+            StopReason::Duration => "duration",
+            StopReason::MaxRuntime => "max_runtime",
+            StopReason::Packets => "packets",
+            StopReason::Bytes => "bytes",
+        }
+    }
+}
+
+impl Display for StopReason {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str(match self {
+            StopReason::Interrupted => "interrupted",
+            StopReason::Duration => "duration elapsed",
+            StopReason::MaxRuntime => "max runtime exceeded",
+            StopReason::Packets => "packet limit reached",
+            StopReason::Bytes => "byte limit reached",
+        })
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct AttackSummary {
     bytes_sent: usize,
     packets_sent: usize,
+    packets_failed: usize,
+    packets_unreachable: usize,
+    send_durations: SendDurations,
+    pacing: PacingDeviations,
+    receivers: HashMap<SocketAddr, PerReceiverStats>,
+    stop_reason: Option<StopReason>,
     initial_time: Instant,
 }
 
@@ -34,6 +334,12 @@ impl AttackSummary {
         AttackSummary {
             bytes_sent: 0,
             packets_sent: 0,
+            packets_failed: 0,
+            packets_unreachable: 0,
+            send_durations: SendDurations::default(),
+            pacing: PacingDeviations::default(),
+            receivers: HashMap::new(),
+            stop_reason: None,
             initial_time: Instant::now(),
         }
     }
@@ -43,6 +349,72 @@ impl AttackSummary {
         self.packets_sent += additional_packets;
     }
 
+    pub fn update_failed(&mut self, additional_packets: usize) {
+        self.packets_failed += additional_packets;
+    }
+
+    /// Counts the packets not sent because the receiver responded with
+    /// ICMP "port unreachable", separately from the other failures.
+    pub fn update_unreachable(&mut self, additional_packets: usize) {
+        self.packets_unreachable += additional_packets;
+    }
+
+    /// Attributes a sent packet of `length` bytes to its `receiver`. The
+    /// totals are updated separately by `update`.
+    pub fn update_receiver(&mut self, receiver: SocketAddr, length: usize) {
+        let stats = self.receivers.entry(receiver).or_default();
+        stats.packets_sent += 1;
+        stats.bytes_sent += length as u64;
+    }
+
+    /// Attributes a packet which wasn't sent to its `receiver`.
+    pub fn update_receiver_failed(&mut self, receiver: SocketAddr) {
+        self.receivers.entry(receiver).or_default().packets_failed += 1;
+    }
+
+    /// Records how long a single sending call (of one packet or a whole
+    /// batch) took.
+    pub fn record_send(&mut self, duration: Duration) {
+        self.send_durations.record(duration);
+    }
+
+    /// Records how far the start of a sending call deviated from the
+    /// `planned` one, both measured since the attack start.
+    pub fn record_pacing(&mut self, planned: Duration, actually: Duration) {
+        self.pacing.record(planned, actually);
+    }
+
+    /// Records the condition which stopped the packet sending.
+    pub fn stop(&mut self, reason: StopReason) {
+        self.stop_reason = Some(reason);
+    }
+
+    /// Adds the results of another summary (for example, of another
+    /// thread), keeping the earliest initial time. An interruption of
+    /// any of them wins over the other stop reasons, since it stopped
+    /// the whole attack.
+    pub fn merge(&mut self, other: &AttackSummary) {
+        self.bytes_sent += other.bytes_sent;
+        self.packets_sent += other.packets_sent;
+        self.packets_failed += other.packets_failed;
+        self.packets_unreachable += other.packets_unreachable;
+        self.send_durations.merge(&other.send_durations);
+        self.pacing.merge(&other.pacing);
+        for (receiver, stats) in &other.receivers {
+            self.receivers.entry(*receiver).or_default().merge(stats);
+        }
+        self.stop_reason = match (self.stop_reason, other.stop_reason) {
+            (_, Some(StopReason::Interrupted)) => Some(StopReason::Interrupted),
+            (None, reason) => reason,
+            (reason, _) => reason,
+        };
+        self.initial_time = self.initial_time.min(other.initial_time);
+    }
+
+    pub fn bytes_sent(&self) -> usize {
+        self.bytes_sent
+    }
+
     pub fn megabytes_sent(&self) -> usize {
         self.bytes_sent / 1024 / 1024
     }
@@ -51,34 +423,434 @@ impl AttackSummary {
         self.packets_sent
     }
 
+    pub fn packets_failed(&self) -> usize {
+        self.packets_failed
+    }
+
+    pub fn packets_unreachable(&self) -> usize {
+        self.packets_unreachable
+    }
+
     pub fn time_passed(&self) -> Duration {
         self.initial_time.elapsed()
     }
 
     pub fn megabytes_in_sec(&self) -> usize {
-        let secs_passed = self.time_passed().as_secs() as usize;
+        self.statistics().megabytes_in_sec()
+    }
 
-        if secs_passed == 0 {
-            0
-        } else {
-            self.megabytes_sent() / secs_passed
+    pub fn packets_in_sec(&self) -> f64 {
+        self.statistics().average_pps()
+    }
+
+    /// Takes a snapshot of this summary with the time passed so far, so
+    /// the result doesn't change after the attack is finished.
+    pub fn statistics(&self) -> Statistics {
+        Statistics {
+            packets_sent: self.packets_sent as u64,
+            packets_failed: self.packets_failed as u64,
+            packets_unreachable: self.packets_unreachable as u64,
+            bytes_sent: self.bytes_sent as u64,
+            send_durations: self.send_durations,
+            pacing: self.pacing,
+            receivers: self.receivers.clone(),
+            packets_received: None,
+            stop_reason: self.stop_reason,
+            elapsed: self.time_passed(),
         }
     }
+
+    /// Formats this summary as a CSV row with the current UNIX
+    /// timestamp, according to `CSV_HEADER`.
+    pub fn to_csv_row(&self) -> String {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.statistics().csv_row(timestamp)
+    }
+}
+
+impl Default for AttackSummary {
+    fn default() -> AttackSummary {
+        AttackSummary::new()
+    }
 }
 
 impl Display for AttackSummary {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        self.statistics().fmt(fmt)
+    }
+}
+
+/// The results of a finished (or running) attack, returned to the caller
+/// instead of being only printed to the terminal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Statistics {
+    packets_sent: u64,
+    packets_failed: u64,
+    packets_unreachable: u64,
+    bytes_sent: u64,
+    send_durations: SendDurations,
+    pacing: PacingDeviations,
+    receivers: HashMap<SocketAddr, PerReceiverStats>,
+    packets_received: Option<u64>,
+    stop_reason: Option<StopReason>,
+    elapsed: Duration,
+}
+
+impl Statistics {
+    pub fn packets_sent(&self) -> u64 {
+        self.packets_sent
+    }
+
+    pub fn packets_failed(&self) -> u64 {
+        self.packets_failed
+    }
+
+    pub fn packets_unreachable(&self) -> u64 {
+        self.packets_unreachable
+    }
+
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    pub fn megabytes_sent(&self) -> u64 {
+        self.bytes_sent / 1024 / 1024
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the count of the packets received back by `--measure-loss`,
+    /// or `None` if the loss isn't measured.
+    pub fn packets_received(&self) -> Option<u64> {
+        self.packets_received
+    }
+
+    /// Returns these statistics along with the count of the packets
+    /// received back by `--measure-loss`.
+    pub fn with_received(self, packets_received: u64) -> Statistics {
+        Statistics {
+            packets_received: Some(packets_received),
+            ..self
+        }
+    }
+
+    /// Returns the percentage of the sent packets which weren't received
+    /// back, or `None` if the loss isn't measured. The packets received
+    /// from somebody else can only lower it down to zero.
+    pub fn loss_percent(&self) -> Option<f64> {
+        self.packets_received
+            .map(|received| match self.packets_sent {
+                0 => 0.0,
+                sent => sent.saturating_sub(received) as f64 * 100.0 / sent as f64,
+            })
+    }
+
+    /// Returns the condition which stopped the packet sending, or `None`
+    /// if the attack hasn't finished (or has failed).
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    /// Returns the packets sent to each receiver, which is empty unless
+    /// several receivers are specified. The totals cover all of them.
+    pub fn receivers(&self) -> &HashMap<SocketAddr, PerReceiverStats> {
+        &self.receivers
+    }
+
+    /// Returns the per-receiver statistics ordered by the addresses,
+    /// so the summaries are stable.
+    fn sorted_receivers(&self) -> Vec<(&SocketAddr, &PerReceiverStats)> {
+        let mut receivers: Vec<_> = self.receivers.iter().collect();
+        receivers.sort_by_key(|&(receiver, _)| *receiver);
+        receivers
+    }
+
+    /// Returns how accurately the sending calls were paced.
+    pub fn pacing(&self) -> &PacingDeviations {
+        &self.pacing
+    }
+
+    pub fn send_durations(&self) -> &SendDurations {
+        &self.send_durations
+    }
+
+    /// Returns the 50th, 90th, and 99th percentiles of the sending call
+    /// durations, each rounded up to its histogram bucket.
+    pub fn percentiles(&self) -> (Duration, Duration, Duration) {
+        (
+            self.send_durations.percentile(50.0),
+            self.send_durations.percentile(90.0),
+            self.send_durations.percentile(99.0),
+        )
+    }
+
+    pub fn megabytes_in_sec(&self) -> usize {
+        (self.megabytes_sent() as usize)
+            .checked_div(self.elapsed.as_secs() as usize)
+            .unwrap_or(0)
+    }
+
+    /// Returns the average count of packets sent per second, or zero if
+    /// no time has passed yet.
+    pub fn average_pps(&self) -> f64 {
+        let secs_passed = self.elapsed.as_secs_f64();
+
+        if secs_passed == 0.0 {
+            0.0
+        } else {
+            self.packets_sent as f64 / secs_passed
+        }
+    }
+
+    /// Returns the average count of megabits (million bits) sent per
+    /// second, or zero if no time has passed yet.
+    pub fn average_mbps(&self) -> f64 {
+        let secs_passed = self.elapsed.as_secs_f64();
+
+        if secs_passed == 0.0 {
+            0.0
+        } else {
+            self.bytes_sent as f64 * 8.0 / 1_000_000.0 / secs_passed
+        }
+    }
+
+    fn csv_row(&self, timestamp: Duration) -> String {
+        format!(
+            "{:.3},{},{},{},{:.2}",
+            timestamp.as_secs_f64(),
+            self.packets_sent,
+            self.packets_failed,
+            self.bytes_sent,
+            self.average_pps(),
+        )
+    }
+
+    /// Returns the percentage of the successfully sent packets among all
+    /// the attempted ones, which is 100 if no packets were attempted.
+    pub fn success_percent(&self) -> f64 {
+        match self.packets_sent + self.packets_failed + self.packets_unreachable {
+            0 => 100.0,
+            attempted => self.packets_sent as f64 * 100.0 / attempted as f64,
+        }
+    }
+
+    /// Checks whether the percentage of the successfully sent packets is
+    /// at least `fail_under`. Without a threshold, an attack is always
+    /// successful.
+    pub fn is_successful(&self, fail_under: Option<f64>) -> bool {
+        match fail_under {
+            Some(percent) => self.success_percent() >= percent,
+            None => true,
+        }
+    }
+
+    /// Formats these statistics as a single JSON object for machine
+    /// processing.
+    pub fn to_json(&self) -> String {
+        let (p50, p90, p99) = self.percentiles();
+
+        format!(
+            "{{\
+             \"packets_sent\":{packets},\
+             \"packets_failed\":{failed},\
+             \"packets_unreachable\":{unreachable},\
+             \"bytes_sent\":{bytes},\
+             \"elapsed_secs\":{elapsed},\
+             \"average_pps\":{pps},\
+             \"average_mbps\":{mbps},\
+             \"send_p50_secs\":{p50},\
+             \"send_p90_secs\":{p90},\
+             \"send_p99_secs\":{p99},\
+             \"pacing_mean_secs\":{pacing_mean},\
+             \"pacing_p95_secs\":{pacing_p95},\
+             \"pacing_max_secs\":{pacing_max},\
+             \"receivers\":{{{receivers}}},\
+             \"packets_received\":{received},\
+             \"loss_percent\":{loss},\
+             \"stop_reason\":{stop_reason}\
+             }}",
+            packets = self.packets_sent,
+            failed = self.packets_failed,
+            unreachable = self.packets_unreachable,
+            bytes = self.bytes_sent,
+            elapsed = self.elapsed.as_secs_f64(),
+            pps = self.average_pps(),
+            mbps = self.average_mbps(),
+            p50 = p50.as_secs_f64(),
+            p90 = p90.as_secs_f64(),
+            p99 = p99.as_secs_f64(),
+            pacing_mean = self.pacing.mean().as_secs_f64(),
+            pacing_p95 = self.pacing.p95().as_secs_f64(),
+            pacing_max = self.pacing.max().as_secs_f64(),
+            receivers = self
+                .sorted_receivers()
+                .into_iter()
+                .map(|(receiver, stats)| format!(
+                    "{}:{{\"packets_sent\":{},\"packets_failed\":{},\"bytes_sent\":{}}}",
+                    json_string(&receiver.to_string()),
+                    stats.packets_sent,
+                    stats.packets_failed,
+                    stats.bytes_sent
+                ))
+                .collect::<Vec<_>>()
+                .join(","),
+            received = match self.packets_received {
+                Some(received) => received.to_string(),
+                None => String::from("null"),
+            },
+            loss = match self.loss_percent() {
+                Some(loss) => loss.to_string(),
+                None => String::from("null"),
+            },
+            stop_reason = match self.stop_reason {
+                Some(reason) => json_string(reason.name()),
+                None => String::from("null"),
+            },
+        )
+    }
+
+    /// Formats these statistics in the Prometheus exposition format. With
+    /// several receivers, each of them has its own samples, otherwise
+    /// the totals are labeled by `receiver`. The failed packets include
+    /// the unreachable ones.
+    pub fn to_prometheus(&self, receiver: &str) -> String {
+        let samples: Vec<(String, PerReceiverStats)> = if self.receivers.is_empty() {
+            vec![(
+                receiver.to_string(),
+                PerReceiverStats {
+                    packets_sent: self.packets_sent,
+                    packets_failed: self.packets_failed + self.packets_unreachable,
+                    bytes_sent: self.bytes_sent,
+                },
+            )]
+        } else {
+            self.sorted_receivers()
+                .into_iter()
+                .map(|(receiver, stats)| (receiver.to_string(), *stats))
+                .collect()
+        };
+
+        let values = |value: &dyn Fn(&PerReceiverStats) -> String| {
+            samples
+                .iter()
+                .map(|(_, stats)| value(stats))
+                .collect::<Vec<String>>()
+        };
+        let metrics = [
+            (
+                "anevicon_packets_sent_total",
+                "counter",
+                "The count of the sent packets.",
+                values(&|stats| stats.packets_sent.to_string()),
+            ),
+            (
+                "anevicon_packets_failed_total",
+                "counter",
+                "The count of the packets which weren't sent.",
+                values(&|stats| stats.packets_failed.to_string()),
+            ),
+            (
+                "anevicon_bytes_sent_total",
+                "counter",
+                "The count of the sent bytes.",
+                values(&|stats| stats.bytes_sent.to_string()),
+            ),
+            (
+                "anevicon_run_duration_seconds",
+                "gauge",
+                "The time of sending the packets.",
+                values(&|_| self.elapsed.as_secs_f64().to_string()),
+            ),
+        ];
+
+        let mut output = String::new();
+        for (name, kind, help, values) in &metrics {
+            output.push_str(&format!(
+                "# HELP {} {}\n# TYPE {} {}\n",
+                name, help, name, kind
+            ));
+            for ((receiver, _), value) in samples.iter().zip(values) {
+                output.push_str(&format!(
+                    "{}{{receiver=\"{}\"}} {}\n",
+                    name,
+                    prometheus_label(receiver),
+                    value
+                ));
+            }
+        }
+        output
+    }
+
+    /// Formats these statistics as a self-describing JSON object for
+    /// archiving: the `meta` object holds the program version, the
+    /// `start_time` of the attack (RFC 3339, in UTC), and the whole
+    /// `config`, and the `result` object holds the statistics as
+    /// `to_json` formats them. If the attack has a label, the object
+    /// starts with the `label` field, so the summaries of several
+    /// attacks can be told apart.
+    pub fn to_report_json(&self, config: &ArgsConfig, start_time: &Tm) -> String {
+        format!(
+            "{{\
+             {label}\
+             \"meta\":{{\
+             \"version\":{version},\
+             \"start_time\":{start_time},\
+             \"config\":{config}\
+             }},\
+             \"result\":{result}\
+             }}",
+            label = config
+                .label
+                .as_deref()
+                .map(|label| format!("\"label\":{},", json_string(label)))
+                .unwrap_or_default(),
+            version = json_string(env!("CARGO_PKG_VERSION")),
+            start_time = json_string(&start_time.to_utc().rfc3339().to_string()),
+            config = config.to_compact_json(),
+            result = self.to_json(),
+        )
+    }
+}
+
+impl Display for Statistics {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         write!(
             fmt,
             "packets sent: {packets}, \
+             packets failed: {failed}, \
+             destination unreachable: {unreachable}, \
              megabytes sent: {megabytes}, \
-             the average speed: {speed} MB/s, \
+             the average speed: {speed} MB/s \
+             ({pps:.2} packets/s, {mbps:.2} Mbit/s), \
              time passed: {time_passed}",
-            packets = self.packets_sent(),
+            packets = self.packets_sent,
+            failed = self.packets_failed,
+            unreachable = self.packets_unreachable,
             megabytes = self.megabytes_sent(),
             speed = self.megabytes_in_sec(),
-            time_passed = format_duration(self.time_passed())
-        )
+            pps = self.average_pps(),
+            mbps = self.average_mbps(),
+            time_passed = format_duration(self.elapsed)
+        )?;
+
+        if let (Some(received), Some(loss)) = (self.packets_received, self.loss_percent()) {
+            write!(fmt, ", packets received: {} ({:.2}% lost)", received, loss)?;
+        }
+        if let Some(reason) = self.stop_reason {
+            write!(fmt, ", stopped: {}", reason)?;
+        }
+        for (receiver, stats) in self.sorted_receivers() {
+            write!(
+                fmt,
+                ", {}: {} sent, {} failed",
+                receiver, stats.packets_sent, stats.packets_failed
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -111,6 +883,7 @@ mod tests {
 
         assert_eq!(summary.megabytes_sent(), 0);
         assert_eq!(summary.packets_sent(), 0);
+        assert_eq!(summary.packets_failed(), 0);
         assert_eq!(summary.megabytes_in_sec(), 0);
     }
 
@@ -125,6 +898,11 @@ mod tests {
         summary.update(1024 * 1024 * 85, 4258);
         assert_eq!(summary.megabytes_sent(), 85 + 23);
         assert_eq!(summary.packets_sent(), 2698 + 4258);
+
+        // Failed packets are counted separately from the sent ones
+        summary.update_failed(12);
+        assert_eq!(summary.packets_failed(), 12);
+        assert_eq!(summary.packets_sent(), 2698 + 4258);
     }
 
     #[test]
@@ -179,4 +957,570 @@ mod tests {
 
         assert!(summary.time_passed() >= initial_time.elapsed());
     }
+
+    #[test]
+    fn merge_works() {
+        let mut first = AttackSummary::new();
+        first.update(1024 * 1024 * 3, 15);
+
+        sleep(Duration::from_millis(10));
+        let mut second = AttackSummary::new();
+        second.update(1024 * 1024 * 5, 25);
+        second.update_failed(2);
+
+        second.merge(&first);
+        assert_eq!(second.megabytes_sent(), 8);
+        assert_eq!(second.packets_sent(), 40);
+        assert_eq!(second.packets_failed(), 2);
+
+        // The earliest initial time must be kept
+        assert!(second.time_passed() >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn formats_json() {
+        let mut summary = AttackSummary::new();
+        summary.update(4096, 4);
+        summary.update_failed(1);
+
+        let json = summary.statistics().to_json();
+        assert!(json.starts_with('{') && json.ends_with('}'));
+        assert!(json.contains("\"packets_sent\":4,"));
+        assert!(json.contains("\"packets_failed\":1,"));
+        assert!(json.contains("\"bytes_sent\":4096,"));
+        assert!(json.contains("\"elapsed_secs\":"));
+        assert!(json.contains("\"average_pps\":"));
+        assert!(json.contains("\"average_mbps\":"));
+    }
+
+    #[test]
+    fn decides_success_by_threshold() {
+        let statistics = |sent: usize, failed: usize| {
+            let mut summary = AttackSummary::new();
+            summary.update(sent * 64, sent);
+            summary.update_failed(failed);
+            summary.statistics()
+        };
+
+        assert_eq!(statistics(90, 10).success_percent(), 90.0);
+        assert_eq!(statistics(0, 0).success_percent(), 100.0);
+        assert_eq!(statistics(0, 5).success_percent(), 0.0);
+
+        let statistics = statistics(95, 5);
+        assert!(statistics.is_successful(None));
+        assert!(statistics.is_successful(Some(95.0)));
+        assert!(statistics.is_successful(Some(0.0)));
+        assert!(!statistics.is_successful(Some(95.5)));
+        assert!(!statistics.is_successful(Some(100.0)));
+    }
+
+    #[test]
+    fn formats_csv_row() {
+        let mut summary = AttackSummary::new();
+        summary.update(65000 * 3, 3);
+        summary.update_failed(2);
+
+        let row = summary
+            .statistics()
+            .csv_row(Duration::from_millis(1_558_000_000_250));
+        assert!(row.starts_with("1558000000.250,3,2,195000,"));
+        assert_eq!(row.split(',').count(), CSV_HEADER.split(',').count());
+    }
+
+    #[test]
+    fn statistics_accumulate_bytes() {
+        let mut summary = AttackSummary::new();
+        summary.update(65000, 1);
+        summary.update(1472, 1);
+        summary.update_failed(3);
+
+        let statistics = summary.statistics();
+        assert_eq!(statistics.bytes_sent(), 65000 + 1472);
+        assert_eq!(statistics.packets_sent(), 2);
+        assert_eq!(statistics.packets_failed(), 3);
+
+        // A snapshot must not change after further updates
+        summary.update(1024, 1);
+        assert_eq!(statistics.bytes_sent(), 65000 + 1472);
+    }
+
+    #[test]
+    fn computes_average_pps() {
+        let statistics = Statistics {
+            packets_sent: 3000,
+            packets_failed: 0,
+            packets_unreachable: 0,
+            bytes_sent: 3000 * 1024,
+            send_durations: SendDurations::default(),
+            pacing: PacingDeviations::default(),
+            receivers: HashMap::new(),
+            packets_received: None,
+            stop_reason: None,
+            elapsed: Duration::from_millis(1500),
+        };
+        assert_eq!(statistics.average_pps(), 2000.0);
+
+        // Nothing must be divided by zero elapsed time
+        let statistics = Statistics {
+            elapsed: Duration::from_secs(0),
+            ..statistics
+        };
+        assert_eq!(statistics.average_pps(), 0.0);
+    }
+
+    #[test]
+    fn computes_average_mbps() {
+        let statistics = Statistics {
+            packets_sent: 1000,
+            packets_failed: 0,
+            packets_unreachable: 0,
+            bytes_sent: 1000 * 1250,
+            send_durations: SendDurations::default(),
+            pacing: PacingDeviations::default(),
+            receivers: HashMap::new(),
+            packets_received: None,
+            stop_reason: None,
+            elapsed: Duration::from_secs(2),
+        };
+        assert_eq!(statistics.average_mbps(), 5.0);
+        assert!(statistics
+            .to_string()
+            .contains("(500.00 packets/s, 5.00 Mbit/s)"));
+
+        // An instantaneous run must not be divided by zero elapsed time
+        let statistics = Statistics {
+            elapsed: Duration::from_secs(0),
+            ..statistics
+        };
+        assert_eq!(statistics.average_mbps(), 0.0);
+        assert!(statistics
+            .to_string()
+            .contains("(0.00 packets/s, 0.00 Mbit/s)"));
+    }
+
+    #[test]
+    fn prepares_csv_once() {
+        let path = std::env::temp_dir().join(format!("anevicon-{}.csv", std::process::id()));
+
+        // A check leaves neither the file nor its header behind
+        check_csv(&path).expect("Cannot check a new CSV file");
+        assert!(!path.exists());
+
+        // The header must be written only into a newly created file
+        prepare_csv(&path).expect("Cannot create a CSV file");
+        check_csv(&path).expect("Cannot check an existing CSV file");
+        prepare_csv(&path).expect("Cannot open an existing CSV file");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!("{}\n", CSV_HEADER)
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(prepare_csv(Path::new("/nonexistent/anevicon.csv")).is_err());
+        assert!(check_csv(Path::new("/nonexistent/anevicon.csv")).is_err());
+    }
+
+    #[test]
+    fn buckets_send_durations() {
+        let micros = |micros| SendDurations::bucket(Duration::from_micros(micros));
+
+        assert_eq!(SendDurations::bucket(Duration::from_nanos(999)), 0);
+        assert_eq!(micros(1), 1);
+        assert_eq!(micros(2), 2);
+        assert_eq!(micros(3), 2);
+        assert_eq!(micros(4), 3);
+        assert_eq!(micros(1023), 10);
+        assert_eq!(micros(1024), 11);
+
+        // Everything too long falls into the last bucket
+        assert_eq!(micros(1 << 22), SEND_DURATION_BUCKETS - 1);
+        assert_eq!(
+            SendDurations::bucket(Duration::from_secs(3600)),
+            SEND_DURATION_BUCKETS - 1
+        );
+
+        // Each duration is shorter than the upper bound of its bucket
+        for &duration in &[1, 5, 100, 3000, 70_000] {
+            let bucket = micros(duration);
+            assert!(Duration::from_micros(duration) < SendDurations::upper_bound(bucket));
+            assert!(Duration::from_micros(duration) >= SendDurations::upper_bound(bucket) / 2);
+        }
+    }
+
+    #[test]
+    fn computes_percentiles() {
+        let mut summary = AttackSummary::new();
+        assert_eq!(
+            summary.statistics().percentiles(),
+            (
+                Duration::from_secs(0),
+                Duration::from_secs(0),
+                Duration::from_secs(0)
+            )
+        );
+
+        // 50 fast sends, 45 slower ones, and 5 very slow ones
+        for _ in 0..50 {
+            summary.record_send(Duration::from_micros(3));
+        }
+        for _ in 0..45 {
+            summary.record_send(Duration::from_micros(100));
+        }
+        for _ in 0..5 {
+            summary.record_send(Duration::from_millis(20));
+        }
+
+        let statistics = summary.statistics();
+        assert_eq!(statistics.send_durations().count(), 100);
+        assert_eq!(
+            statistics.percentiles(),
+            (
+                Duration::from_micros(4),
+                Duration::from_micros(128),
+                Duration::from_micros(32_768)
+            )
+        );
+        assert_eq!(
+            statistics.send_durations().percentile(95.0),
+            Duration::from_micros(128)
+        );
+        assert_eq!(
+            statistics.send_durations().percentile(96.0),
+            Duration::from_micros(32_768)
+        );
+
+        let json = statistics.to_json();
+        assert!(json.contains("\"send_p50_secs\":0.000004,"));
+        assert!(json.contains("\"send_p99_secs\":0.032768,"));
+    }
+
+    #[test]
+    fn merges_send_durations() {
+        let (mut first, mut second) = (AttackSummary::new(), AttackSummary::new());
+        first.record_send(Duration::from_micros(3));
+        second.record_send(Duration::from_millis(20));
+        second.record_send(Duration::from_millis(20));
+
+        first.merge(&second);
+        let durations = *first.statistics().send_durations();
+        assert_eq!(durations.count(), 3);
+        assert_eq!(durations.percentile(33.0), Duration::from_micros(4));
+        assert_eq!(durations.percentile(34.0), Duration::from_micros(32_768));
+    }
+
+    #[test]
+    fn draws_histogram() {
+        let mut durations = SendDurations::default();
+        assert_eq!(durations.to_string(), "no sending calls");
+
+        for _ in 0..4 {
+            durations.record(Duration::from_micros(3));
+        }
+        durations.record(Duration::from_micros(20));
+
+        let histogram = durations.to_string();
+        let lines: Vec<&str> = histogram.lines().collect();
+
+        // The empty buckets in between are drawn as well
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("   < 4us ########################################"));
+        assert!(lines[0].ends_with(" 4"));
+        assert!(lines[1].starts_with("   < 8us  "));
+        assert!(lines[1].ends_with(" 0"));
+        assert!(lines[3].starts_with("  < 32us ########## "));
+        assert!(lines[3].ends_with(" 1"));
+    }
+
+    #[test]
+    fn formats_bucket_bounds() {
+        assert_eq!(format_bound(Duration::from_micros(512)), "512us");
+        assert_eq!(format_bound(Duration::from_micros(2048)), "2ms");
+        assert_eq!(format_bound(Duration::from_micros(1 << 23)), "8.4s");
+    }
+
+    #[test]
+    fn counts_unreachable_separately() {
+        let mut summary = AttackSummary::new();
+        summary.update(64 * 3, 3);
+        summary.update_failed(1);
+        summary.update_unreachable(4);
+
+        let mut other = AttackSummary::new();
+        other.update_unreachable(2);
+        summary.merge(&other);
+
+        let statistics = summary.statistics();
+        assert_eq!(statistics.packets_failed(), 1);
+        assert_eq!(statistics.packets_unreachable(), 6);
+        assert_eq!(statistics.success_percent(), 30.0);
+        assert!(statistics
+            .to_string()
+            .contains("packets failed: 1, destination unreachable: 6,"));
+        assert!(statistics
+            .to_json()
+            .contains("\"packets_failed\":1,\"packets_unreachable\":6,"));
+    }
+
+    #[test]
+    fn formats_report_json() {
+        use structopt::StructOpt;
+
+        let config = ArgsConfig::from_iter_safe(vec![
+            "anevicon",
+            "--receiver",
+            "127.0.0.1:1280",
+            "--label",
+            "nightly",
+        ])
+        .unwrap();
+        let mut summary = AttackSummary::new();
+        summary.update(4096, 4);
+
+        let json = summary.statistics().to_report_json(
+            &config,
+            &time::at_utc(time::Timespec::new(1_558_000_000, 0)),
+        );
+        assert_eq!(json.lines().count(), 1);
+
+        let report: serde_json::Value = serde_json::from_str(&json).expect("Invalid JSON");
+        assert_eq!(report["label"], "nightly");
+        assert_eq!(report["meta"]["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(report["meta"]["config"]["label"], "nightly");
+        assert_eq!(report["result"]["packets_sent"], 4);
+
+        let start_time = report["meta"]["start_time"].as_str().unwrap();
+        assert_eq!(start_time, "2019-05-16T09:46:40Z");
+        assert_eq!(
+            time::strptime(start_time, "%Y-%m-%dT%H:%M:%SZ")
+                .expect("Cannot parse the start time")
+                .to_timespec()
+                .sec,
+            1_558_000_000
+        );
+    }
+
+    #[test]
+    fn reports_stop_reason() {
+        let mut summary = AttackSummary::new();
+        assert_eq!(summary.statistics().stop_reason(), None);
+        assert!(summary
+            .statistics()
+            .to_json()
+            .ends_with("\"stop_reason\":null}"));
+        assert!(!summary.statistics().to_string().contains("stopped"));
+
+        summary.stop(StopReason::Packets);
+        let statistics = summary.statistics();
+        assert_eq!(statistics.stop_reason(), Some(StopReason::Packets));
+        assert!(statistics
+            .to_json()
+            .ends_with("\"stop_reason\":\"packets\"}"));
+        assert!(statistics
+            .to_string()
+            .ends_with(", stopped: packet limit reached"));
+    }
+
+    #[test]
+    fn merges_stop_reasons() {
+        let stopped = |reason: Option<StopReason>| {
+            let mut summary = AttackSummary::new();
+            summary.stop_reason = reason;
+            summary
+        };
+        let merged = |first: Option<StopReason>, second: Option<StopReason>| {
+            let mut summary = stopped(first);
+            summary.merge(&stopped(second));
+            summary.statistics().stop_reason()
+        };
+
+        assert_eq!(merged(None, None), None);
+        assert_eq!(
+            merged(None, Some(StopReason::Bytes)),
+            Some(StopReason::Bytes)
+        );
+        assert_eq!(
+            merged(Some(StopReason::Duration), Some(StopReason::Packets)),
+            Some(StopReason::Duration)
+        );
+        assert_eq!(
+            merged(Some(StopReason::Packets), Some(StopReason::Interrupted)),
+            Some(StopReason::Interrupted)
+        );
+    }
+
+    #[test]
+    fn computes_pacing_deviations() {
+        // A packet is planned each 10ms, but the scheduler wakes up later
+        let planned = |index: u64| Duration::from_millis(index * 10);
+        let actual = [0, 10_050, 20_100, 30_000, 39_990, 52_000];
+
+        let mut deviations = PacingDeviations::default();
+        assert_eq!(deviations.mean(), Duration::from_secs(0));
+        for (index, &micros) in actual.iter().enumerate() {
+            deviations.record(planned(index as u64), Duration::from_micros(micros));
+        }
+
+        assert_eq!(deviations.count(), 6);
+        assert_eq!(deviations.max(), Duration::from_millis(2));
+        // (0 + 50 + 100 + 0 + 10 + 2000) / 6 microseconds
+        assert_eq!(deviations.mean(), Duration::from_micros(360));
+        assert_eq!(deviations.p95(), Duration::from_micros(2048));
+
+        let mut merged = PacingDeviations::default();
+        merged.record(Duration::from_secs(1), Duration::from_secs(4));
+        merged.merge(&deviations);
+        assert_eq!(merged.count(), 7);
+        assert_eq!(merged.max(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn reports_pacing_in_json() {
+        let mut summary = AttackSummary::new();
+        summary.record_pacing(Duration::from_millis(10), Duration::from_millis(11));
+
+        let json = summary.statistics().to_json();
+        assert!(json.contains("\"pacing_mean_secs\":0.001,"));
+        assert!(json.contains("\"pacing_p95_secs\":0.001024,"));
+        assert!(json.contains("\"pacing_max_secs\":0.001,"));
+    }
+
+    #[test]
+    fn accumulates_receiver_statistics() {
+        let (first, second) = (
+            "127.0.0.1:1280".parse().unwrap(),
+            "[::1]:1280".parse().unwrap(),
+        );
+
+        let mut summary = AttackSummary::new();
+        summary.update(96, 3);
+        summary.update_receiver(first, 32);
+        summary.update_receiver(second, 32);
+        summary.update_receiver(first, 32);
+        summary.update_failed(1);
+        summary.update_receiver_failed(second);
+
+        let mut other = AttackSummary::new();
+        other.update(8, 1);
+        other.update_receiver(second, 8);
+        summary.merge(&other);
+
+        let statistics = summary.statistics();
+        assert_eq!(statistics.packets_sent(), 4);
+        assert_eq!(statistics.packets_failed(), 1);
+
+        let receivers = statistics.receivers();
+        assert_eq!(receivers.len(), 2);
+        assert_eq!(receivers[&first].packets_sent(), 2);
+        assert_eq!(receivers[&first].packets_failed(), 0);
+        assert_eq!(receivers[&first].bytes_sent(), 64);
+        assert_eq!(receivers[&second].packets_sent(), 2);
+        assert_eq!(receivers[&second].packets_failed(), 1);
+        assert_eq!(receivers[&second].bytes_sent(), 40);
+
+        assert!(statistics
+            .to_string()
+            .ends_with(", 127.0.0.1:1280: 2 sent, 0 failed, [::1]:1280: 2 sent, 1 failed"));
+        assert!(statistics.to_json().contains(
+            "\"receivers\":{\
+             \"127.0.0.1:1280\":{\"packets_sent\":2,\"packets_failed\":0,\"bytes_sent\":64},\
+             \"[::1]:1280\":{\"packets_sent\":2,\"packets_failed\":1,\"bytes_sent\":40}},"
+        ));
+        assert!(AttackSummary::new()
+            .statistics()
+            .to_json()
+            .contains("\"receivers\":{},"));
+    }
+
+    #[test]
+    fn formats_prometheus_metrics() {
+        let statistics = Statistics {
+            packets_sent: 10,
+            packets_failed: 2,
+            packets_unreachable: 1,
+            bytes_sent: 640,
+            send_durations: SendDurations::default(),
+            pacing: PacingDeviations::default(),
+            receivers: HashMap::new(),
+            packets_received: None,
+            stop_reason: None,
+            elapsed: Duration::from_millis(2500),
+        };
+
+        assert_eq!(
+            statistics.to_prometheus("127.0.0.1:1280"),
+            "# HELP anevicon_packets_sent_total The count of the sent packets.\n\
+             # TYPE anevicon_packets_sent_total counter\n\
+             anevicon_packets_sent_total{receiver=\"127.0.0.1:1280\"} 10\n\
+             # HELP anevicon_packets_failed_total The count of the packets which weren't sent.\n\
+             # TYPE anevicon_packets_failed_total counter\n\
+             anevicon_packets_failed_total{receiver=\"127.0.0.1:1280\"} 3\n\
+             # HELP anevicon_bytes_sent_total The count of the sent bytes.\n\
+             # TYPE anevicon_bytes_sent_total counter\n\
+             anevicon_bytes_sent_total{receiver=\"127.0.0.1:1280\"} 640\n\
+             # HELP anevicon_run_duration_seconds The time of sending the packets.\n\
+             # TYPE anevicon_run_duration_seconds gauge\n\
+             anevicon_run_duration_seconds{receiver=\"127.0.0.1:1280\"} 2.5\n"
+        );
+        assert!(statistics
+            .to_prometheus("/tmp/a \"b\"\\c")
+            .contains("{receiver=\"/tmp/a \\\"b\\\"\\\\c\"} 10\n"));
+
+        // Several receivers have their own samples
+        let mut summary = AttackSummary::new();
+        summary.update(48, 2);
+        summary.update_receiver("127.0.0.1:1280".parse().unwrap(), 16);
+        summary.update_receiver("127.0.0.1:1281".parse().unwrap(), 32);
+        let metrics = summary.statistics().to_prometheus("ignored");
+        assert!(metrics.contains("anevicon_bytes_sent_total{receiver=\"127.0.0.1:1280\"} 16\n"));
+        assert!(metrics.contains("anevicon_bytes_sent_total{receiver=\"127.0.0.1:1281\"} 32\n"));
+        assert!(!metrics.contains("ignored"));
+    }
+
+    #[test]
+    fn writes_prometheus_file() {
+        let path = std::env::temp_dir().join(format!("anevicon-{}.prom", std::process::id()));
+        fs::write(&path, "stale").unwrap();
+
+        write_prometheus(&path, "anevicon_packets_sent_total 1\n").unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "anevicon_packets_sent_total 1\n"
+        );
+        assert!(!prometheus_temp_path(&path).exists());
+        fs::remove_file(&path).unwrap();
+
+        assert!(write_prometheus(Path::new("/nonexistent/anevicon.prom"), "").is_err());
+    }
+
+    #[test]
+    fn reports_received_packets() {
+        let mut summary = AttackSummary::new();
+        summary.update(400, 4);
+
+        let statistics = summary.statistics();
+        assert_eq!(statistics.loss_percent(), None);
+        assert!(statistics
+            .to_json()
+            .contains("\"packets_received\":null,\"loss_percent\":null,"));
+
+        let statistics = statistics.with_received(3);
+        assert_eq!(statistics.packets_received(), Some(3));
+        assert_eq!(statistics.loss_percent(), Some(25.0));
+        assert!(statistics
+            .to_string()
+            .contains(", packets received: 3 (25.00% lost)"));
+        assert!(statistics
+            .to_json()
+            .contains("\"packets_received\":3,\"loss_percent\":25,"));
+
+        // Strangers cannot make the loss negative
+        assert_eq!(statistics.with_received(5).loss_percent(), Some(0.0));
+        assert_eq!(
+            AttackSummary::new()
+                .statistics()
+                .with_received(0)
+                .loss_percent(),
+            Some(0.0)
+        );
+    }
 }
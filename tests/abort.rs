@@ -0,0 +1,69 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::net::UdpSocket;
+use std::process::{Command, Output};
+
+fn run_failing_attack(args: &[&str]) -> Output {
+    // Nobody listens on the port, so the connected socket gets ECONNREFUSED
+    let receiver = UdpSocket::bind("127.0.0.1:0")
+        .and_then(|socket| socket.local_addr())
+        .expect("Cannot find a free port")
+        .to_string();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", &receiver, "--connect"])
+        .args(["--wait", "0s", "--duration", "10s", "--length", "64"])
+        .args(args)
+        .env_remove("RUST_LOG")
+        .output()
+        .expect("Cannot launch the program");
+
+    assert!(!output.status.success(), "The attack hasn't failed");
+    output
+}
+
+#[test]
+fn prints_summary_of_failed_attack() {
+    let output = run_failing_attack(&[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.starts_with("The total result is: packets sent: "));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("An error occurred during the attack"));
+}
+
+#[test]
+fn prints_json_summary_of_failed_attack() {
+    let output = run_failing_attack(&["--output-format", "json"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(stdout.starts_with("{\"meta\":{"));
+    assert!(stdout.contains("\"result\":{\"packets_sent\":"));
+    assert!(stdout.contains("\"stop_reason\":null}"));
+}
+
+#[test]
+fn prints_summary_of_failed_setup() {
+    let output = run_failing_attack(&["--send-file", "/nonexistent/payload.bin"]);
+
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with(
+        "The total result is: packets sent: 0, packets failed: 0, destination unreachable: 0,"
+    ));
+}
@@ -0,0 +1,102 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::net::UdpSocket;
+use std::process::Command;
+use std::time::Duration;
+
+#[test]
+fn validates_without_sending() {
+    let server = UdpSocket::bind("127.0.0.1:0").expect("Cannot setup the testing server");
+    let receiver = server.local_addr().unwrap().to_string();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", &receiver, "--wait", "0s", "--dry-run"])
+        .output()
+        .expect("Cannot launch the program");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+    assert!(stdout.contains(&format!("receiver: {}\n", receiver)));
+
+    // Not a single packet must be sent
+    server
+        .set_read_timeout(Some(Duration::from_millis(300)))
+        .unwrap();
+    assert!(server.recv_from(&mut [0; 64]).is_err());
+}
+
+#[test]
+fn reports_invalid_config() {
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args([
+            "--receiver",
+            "127.0.0.1:1280",
+            "--repeat-payload",
+            "--dry-run",
+        ])
+        .output()
+        .expect("Cannot launch the program");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid configuration"));
+}
+
+#[test]
+fn validates_without_binding_sender() {
+    // 203.0.113.0/24 (TEST-NET-3) isn't assigned to any local interface
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args([
+            "--receiver",
+            "127.0.0.1:1280",
+            "--sender",
+            "203.0.113.77:5000",
+            "--dry-run",
+        ])
+        .output()
+        .expect("Cannot launch the program");
+
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+}
+
+#[test]
+fn validates_without_creating_csv() {
+    let path = std::env::temp_dir().join(format!("anevicon-{}-dry.csv", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", "127.0.0.1:1280", "--dry-run", "--csv"])
+        .arg(&path)
+        .output()
+        .expect("Cannot launch the program");
+
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+    assert!(!path.exists());
+}
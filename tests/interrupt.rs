@@ -0,0 +1,66 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+#![cfg(unix)]
+
+use std::net::UdpSocket;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn prints_summary_on_sigint() {
+    let server = UdpSocket::bind("127.0.0.1:0").expect("Cannot setup the testing server");
+
+    let child = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", &server.local_addr().unwrap().to_string()])
+        .args([
+            "--wait",
+            "0s",
+            "--length",
+            "64",
+            "--send-periodicity",
+            "1ms",
+            "-v",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Cannot launch the program");
+
+    // Give the program some time to start sending packets
+    thread::sleep(Duration::from_millis(500));
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGINT);
+    }
+
+    let output = child
+        .wait_with_output()
+        .expect("Cannot wait for the program");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+    assert!(stderr.contains("because it was interrupted"));
+    assert!(stdout.contains("The total result is"));
+}
@@ -0,0 +1,65 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::net::UdpSocket;
+use std::process::Command;
+
+fn run_attack(args: &[&str]) -> (String, String) {
+    let server = UdpSocket::bind("127.0.0.1:0").expect("Cannot setup the testing server");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", &server.local_addr().unwrap().to_string()])
+        .args(["--wait", "0s", "--length", "64", "--packets", "20"])
+        .args(["--label", "nightly"])
+        .args(args)
+        .env_remove("RUST_LOG")
+        .output()
+        .expect("Cannot launch the program");
+
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+    (
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn labels_human_summary() {
+    let (stdout, stderr) = run_attack(&["--display-periodicity", "10", "-v"]);
+
+    assert!(stdout.starts_with("[nightly] The total result is: packets sent: 20,"));
+    assert!(stderr
+        .lines()
+        .filter(|line| line.contains("The attack is running with"))
+        .all(|line| line.contains("[nightly] The attack is running with")));
+    assert!(stderr.contains("[nightly] The attack is running with"));
+}
+
+#[test]
+fn labels_json_summary() {
+    let (stdout, _) = run_attack(&["--output-format", "json"]);
+
+    assert!(stdout.starts_with("{\"label\":\"nightly\",\"meta\":{\"version\":"));
+    assert!(stdout.contains(",\"label\":\"nightly\","));
+    assert!(stdout.contains("\"result\":{\"packets_sent\":20,"));
+}
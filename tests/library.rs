@@ -0,0 +1,67 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::net::UdpSocket;
+use std::time::Duration;
+
+use anevicon::config::ArgsConfig;
+
+#[test]
+fn runs_attack_from_library() {
+    let server = UdpSocket::bind("127.0.0.1:0").expect("Cannot setup the testing server");
+    let receiver = server.local_addr().unwrap().to_string();
+
+    let config = ArgsConfig::from_args_iter(vec![
+        "anevicon",
+        "--receiver",
+        &receiver,
+        "--wait",
+        "0s",
+        "--length",
+        "64",
+        "--packets",
+        "25",
+    ])
+    .expect("Cannot build the configuration");
+
+    let statistics = anevicon::run(&config).expect("An error occurred during the attack");
+    assert!(statistics.packets_sent() > 0);
+    assert_eq!(statistics.packets_sent(), 25);
+    assert_eq!(statistics.bytes_sent(), 25 * 64);
+
+    server
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .unwrap();
+    let mut buffer = [0; 64];
+    assert_eq!(server.recv(&mut buffer).expect("A packet was lost"), 64);
+}
+
+#[test]
+fn reports_setup_errors() {
+    let config = ArgsConfig::from_args_iter(vec![
+        "anevicon",
+        "--receiver",
+        "127.0.0.1:1280",
+        "--send-file",
+        "/nonexistent/payload.bin",
+    ])
+    .expect("Cannot build the configuration");
+
+    assert!(anevicon::run(&config).is_err());
+}
@@ -0,0 +1,74 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::net::UdpSocket;
+use std::process::Command;
+
+#[test]
+fn tracks_received_packets() {
+    // The program binds the receiver itself, so the port must be free
+    let probe = UdpSocket::bind("127.0.0.1:0").expect("Cannot find a free port");
+    let receiver = probe.local_addr().unwrap().to_string();
+    drop(probe);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", &receiver, "--wait", "0s"])
+        .args([
+            "--length",
+            "64",
+            "--packets",
+            "20",
+            "--send-periodicity",
+            "1ms",
+        ])
+        .args(["--measure-loss", "--output-format", "json"])
+        .output()
+        .expect("Cannot launch the program");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+    assert!(stdout.contains("\"packets_sent\":20,"));
+    assert!(
+        stdout.contains("\"packets_received\":20,\"loss_percent\":0,"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn rejects_bound_receiver() {
+    let server = UdpSocket::bind("127.0.0.1:0").expect("Cannot setup the testing server");
+    let receiver = server.local_addr().unwrap().to_string();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", &receiver, "--wait", "0s", "--packets", "1"])
+        .arg("--measure-loss")
+        .output()
+        .expect("Cannot launch the program");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&format!(
+        "cannot bind the receiver {} to measure the loss",
+        receiver
+    )));
+}
@@ -0,0 +1,48 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::process::Command;
+
+fn dry_run_stderr(length: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args([
+            "--receiver",
+            "127.0.0.1:1280",
+            "--length",
+            length,
+            "--dry-run",
+        ])
+        .output()
+        .expect("Cannot launch the program");
+
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn warns_about_exceeding_mtu() {
+    assert!(dry_run_stderr("1473").contains("The packet length of 1473 bytes exceeds 1472 bytes"));
+
+    // The largest payload of a typical MTU is sent silently
+    assert!(!dry_run_stderr("1472").contains("exceeds"));
+}
@@ -0,0 +1,52 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::fs;
+use std::net::UdpSocket;
+use std::process::Command;
+
+#[test]
+fn writes_prometheus_file() {
+    let server = UdpSocket::bind("127.0.0.1:0").expect("Cannot setup the testing server");
+    let receiver = server.local_addr().unwrap().to_string();
+    let path = std::env::temp_dir().join(format!("anevicon-{}-test.prom", std::process::id()));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", &receiver, "--wait", "0s"])
+        .args(["--length", "64", "--packets", "20"])
+        .args(["--prometheus-file", path.to_str().unwrap()])
+        .output()
+        .expect("Cannot launch the program");
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+
+    let metrics = fs::read_to_string(&path).expect("Cannot read the Prometheus file");
+    fs::remove_file(&path).unwrap();
+    assert!(metrics.contains(&format!(
+        "anevicon_packets_sent_total{{receiver=\"{}\"}} 20\n",
+        receiver
+    )));
+    assert!(metrics.contains(&format!(
+        "anevicon_bytes_sent_total{{receiver=\"{}\"}} 1280\n",
+        receiver
+    )));
+}
@@ -0,0 +1,61 @@
+/* anevicon: The most powerful UDP-based load generator, written in Rust.
+ * Copyright (C) 2019  Temirkhan Myrzamadi <gymmasssorla@gmail.com>
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ *
+ * For more information see <https://github.com/Gymmasssorla/anevicon>.
+ */
+
+use std::net::UdpSocket;
+use std::process::Command;
+
+fn run_attack(args: &[&str]) -> (String, String) {
+    let server = UdpSocket::bind("127.0.0.1:0").expect("Cannot setup the testing server");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anevicon"))
+        .args(["--receiver", &server.local_addr().unwrap().to_string()])
+        .args(["--wait", "0s", "--length", "64", "--packets", "300"])
+        .args(["--send-periodicity", "1ms", "--report-interval", "10ms"])
+        .args(args)
+        .env_remove("RUST_LOG")
+        .output()
+        .expect("Cannot launch the program");
+
+    assert!(
+        output.status.success(),
+        "The program exited with {}",
+        output.status
+    );
+    (
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn reports_progress_to_stderr() {
+    let (stdout, stderr) = run_attack(&["-v"]);
+
+    assert!(stderr.contains("The attack is running for"));
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.starts_with("The total result is: packets sent: 300,"));
+}
+
+#[test]
+fn prints_only_summary_in_quiet_mode() {
+    let (stdout, stderr) = run_attack(&["--quiet"]);
+
+    assert!(stderr.is_empty(), "Unexpected messages: {}", stderr);
+    assert!(stdout.starts_with("The total result is: packets sent: 300,"));
+}